@@ -1,5 +1,6 @@
 //! Configuration management for Code Intelligence Core
 
+use crate::errors::{CoreError, Result};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -11,6 +12,98 @@ pub struct DatabaseConfig {
     pub timeout_seconds: u64,
 }
 
+/// Where a database URL points
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatabaseLocation {
+    /// A private in-memory database
+    Memory,
+    /// A database file, relative paths resolving against the working directory
+    File(PathBuf),
+}
+
+/// How a database file is opened, from the URL's `mode` option
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DatabaseMode {
+    /// `ro`: an existing file, without write access
+    ReadOnly,
+    /// `rw`: an existing file, which is not created when missing
+    ReadWrite,
+    /// `rwc`: created when missing
+    #[default]
+    ReadWriteCreate,
+}
+
+/// A parsed `DATABASE_URL`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseUrl {
+    pub location: DatabaseLocation,
+    pub mode: DatabaseMode,
+}
+
+impl DatabaseUrl {
+    /// Parse `sqlite::memory:`, `sqlite:///absolute/path.db`,
+    /// `sqlite://./relative.db` or `sqlite://relative.db`, or a bare path
+    ///
+    /// A `sqlite:` URL may end in `?mode=ro`, `rw`, `rwc` or `memory`; other
+    /// options are rejected rather than ignored. Bare paths are taken as
+    /// written, `?` included.
+    pub fn parse(url: &str) -> Result<Self> {
+        let invalid =
+            |reason: &str| CoreError::Config(format!("Invalid database URL {}: {}", url, reason));
+        let Some(rest) = url.strip_prefix("sqlite:") else {
+            if url.is_empty() {
+                return Err(invalid("no database is named"));
+            }
+            return Ok(Self::at(url));
+        };
+        // `sqlite://` leaves absolute paths their leading `/`
+        let rest = rest.strip_prefix("//").unwrap_or(rest);
+        let (path, options) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let mut mode = DatabaseMode::default();
+        let mut memory = path == ":memory:";
+        for option in options.split('&').filter(|option| !option.is_empty()) {
+            match option.split_once('=') {
+                Some(("mode", "ro")) => mode = DatabaseMode::ReadOnly,
+                Some(("mode", "rw")) => mode = DatabaseMode::ReadWrite,
+                Some(("mode", "rwc")) => mode = DatabaseMode::ReadWriteCreate,
+                Some(("mode", "memory")) => memory = true,
+                Some(("mode", other)) => {
+                    return Err(invalid(&format!(
+                        "unknown mode `{}`; use ro, rw, rwc or memory",
+                        other
+                    )))
+                }
+                _ => return Err(invalid(&format!("unsupported option `{}`", option))),
+            }
+        }
+
+        if memory {
+            if mode == DatabaseMode::ReadOnly {
+                return Err(invalid("an in-memory database cannot be opened read-only"));
+            }
+            return Ok(Self {
+                location: DatabaseLocation::Memory,
+                mode,
+            });
+        }
+        if path.is_empty() {
+            return Err(invalid("no database is named"));
+        }
+        Ok(Self {
+            mode,
+            ..Self::at(path)
+        })
+    }
+
+    fn at(path: &str) -> Self {
+        Self {
+            location: DatabaseLocation::File(PathBuf::from(path)),
+            mode: DatabaseMode::default(),
+        }
+    }
+}
+
 /// Cache configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheConfig {
@@ -116,3 +209,73 @@ impl Default for PerformanceConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, mode: DatabaseMode) -> DatabaseUrl {
+        DatabaseUrl {
+            location: DatabaseLocation::File(PathBuf::from(path)),
+            mode,
+        }
+    }
+
+    #[test]
+    fn test_database_url_forms() {
+        let memory = DatabaseUrl {
+            location: DatabaseLocation::Memory,
+            mode: DatabaseMode::ReadWriteCreate,
+        };
+        assert_eq!(DatabaseUrl::parse("sqlite::memory:").unwrap(), memory);
+        assert_eq!(DatabaseUrl::parse("sqlite://:memory:").unwrap(), memory);
+        assert_eq!(
+            DatabaseUrl::parse("sqlite://shared.db?mode=memory").unwrap(),
+            memory
+        );
+
+        assert_eq!(
+            DatabaseUrl::parse("sqlite:///var/lib/codesight/index.db").unwrap(),
+            file("/var/lib/codesight/index.db", DatabaseMode::ReadWriteCreate)
+        );
+        assert_eq!(
+            DatabaseUrl::parse("sqlite://./data/index.db").unwrap(),
+            file("./data/index.db", DatabaseMode::ReadWriteCreate)
+        );
+        assert_eq!(
+            DatabaseUrl::parse("sqlite://index.db").unwrap(),
+            file("index.db", DatabaseMode::ReadWriteCreate)
+        );
+        assert_eq!(
+            DatabaseUrl::parse("sqlite:index.db").unwrap(),
+            file("index.db", DatabaseMode::ReadWriteCreate)
+        );
+        assert_eq!(
+            DatabaseUrl::parse("/tmp/index.db").unwrap(),
+            file("/tmp/index.db", DatabaseMode::ReadWriteCreate)
+        );
+
+        assert_eq!(
+            DatabaseUrl::parse("sqlite:///srv/index.db?mode=ro").unwrap(),
+            file("/srv/index.db", DatabaseMode::ReadOnly)
+        );
+        assert_eq!(
+            DatabaseUrl::parse("sqlite://./index.db?mode=rw").unwrap(),
+            file("./index.db", DatabaseMode::ReadWrite)
+        );
+        assert_eq!(
+            DatabaseUrl::parse("sqlite://./index.db?mode=rwc").unwrap(),
+            file("./index.db", DatabaseMode::ReadWriteCreate)
+        );
+
+        for invalid in [
+            "",
+            "sqlite://",
+            "sqlite://index.db?mode=rx",
+            "sqlite://index.db?cache=shared",
+            "sqlite::memory:?mode=ro",
+        ] {
+            assert!(DatabaseUrl::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
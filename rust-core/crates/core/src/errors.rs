@@ -28,6 +28,47 @@ pub enum CoreError {
 
     #[error("Validation error: {0}")]
     ValidationError(String),
+
+    #[error("Operation timed out: {0}")]
+    Timeout(String),
+
+    #[error("Database path {}: {problem}", .path.display())]
+    DatabasePath {
+        path: std::path::PathBuf,
+        problem: DatabasePathProblem,
+    },
+}
+
+/// Why a database file cannot be opened, each with the fix to suggest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabasePathProblem {
+    /// The parent directory is absent and could not be created
+    DirectoryMissing,
+    /// The file or its directory cannot be written, or read when read-only
+    PermissionDenied,
+    /// The path names a directory rather than a database file
+    IsDirectory,
+    /// A read-only or `mode=rw` open was asked for a database that does not exist
+    NotFound,
+}
+
+impl std::fmt::Display for DatabasePathProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DatabasePathProblem::DirectoryMissing => {
+                "parent directory does not exist and could not be created; create it or point DATABASE_URL elsewhere"
+            }
+            DatabasePathProblem::PermissionDenied => {
+                "permission denied; make the file and its directory writable, point DATABASE_URL elsewhere, or open read-only"
+            }
+            DatabasePathProblem::IsDirectory => {
+                "is a directory; DATABASE_URL must name a database file, such as index.db inside it"
+            }
+            DatabasePathProblem::NotFound => {
+                "does not exist, and a read-only or `mode=rw` open cannot create it; index into it writable first"
+            }
+        })
+    }
 }
 
 /// Legacy error type for backwards compatibility
@@ -45,6 +45,13 @@ pub enum EntityType {
     Module,
     Import,
     Export,
+    Enum,
+    Struct,
+    TypeAlias,
+    Trait,
+    /// An HTTP route, with its method and path in the `http_method` and
+    /// `route_path` metadata
+    Endpoint,
 }
 
 /// Core code entity
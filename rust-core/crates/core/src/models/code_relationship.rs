@@ -29,6 +29,57 @@ pub enum RelationshipType {
     DependsOn,
 }
 
+impl RelationshipType {
+    const ALL: [RelationshipType; 7] = [
+        RelationshipType::Imports,
+        RelationshipType::Calls,
+        RelationshipType::Extends,
+        RelationshipType::Implements,
+        RelationshipType::References,
+        RelationshipType::Uses,
+        RelationshipType::DependsOn,
+    ];
+
+    /// The snake_case name used in serialized form and in storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelationshipType::Imports => "imports",
+            RelationshipType::Calls => "calls",
+            RelationshipType::Extends => "extends",
+            RelationshipType::Implements => "implements",
+            RelationshipType::References => "references",
+            RelationshipType::Uses => "uses",
+            RelationshipType::DependsOn => "depends_on",
+        }
+    }
+
+    /// Look up a type by its snake_case name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == name)
+    }
+}
+
+/// Where a relationship came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationshipSource {
+    /// Extracted from source code; replaced whenever the file is re-indexed
+    #[default]
+    Parsed,
+    /// Added by a user to record an architectural relationship the code does
+    /// not show; kept across re-indexing
+    Manual,
+}
+
+impl RelationshipSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelationshipSource::Parsed => "parsed",
+            RelationshipSource::Manual => "manual",
+        }
+    }
+}
+
 /// Represents a relationship between two code entities
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeRelationship {
@@ -44,6 +95,9 @@ pub struct CodeRelationship {
     pub confidence: f32,
     /// Optional context snippet showing the relationship
     pub context: Option<String>,
+    /// Whether the relationship was parsed or added by hand
+    #[serde(default)]
+    pub source: RelationshipSource,
     /// Timestamp when the relationship was created
     pub created_at: DateTime<Utc>,
     /// Timestamp when the relationship was last updated
@@ -65,6 +119,7 @@ impl CodeRelationship {
             relationship_type,
             confidence: confidence.clamp(0.0, 1.0),
             context: None,
+            source: RelationshipSource::Parsed,
             created_at: Utc::now(),
             updated_at: None,
         }
@@ -88,6 +143,23 @@ impl CodeRelationship {
         relationship
     }
 
+    /// Create a user-defined relationship, with full confidence
+    pub fn manual(
+        source_entity_id: Uuid,
+        target_entity_id: Uuid,
+        relationship_type: RelationshipType,
+    ) -> Self {
+        let mut relationship =
+            Self::new(source_entity_id, target_entity_id, relationship_type, 1.0);
+        relationship.source = RelationshipSource::Manual;
+        relationship
+    }
+
+    /// Whether this relationship was added by hand rather than parsed
+    pub fn is_manual(&self) -> bool {
+        self.source == RelationshipSource::Manual
+    }
+
     /// Set the context for this relationship
     pub fn set_context(&mut self, context: String) {
         self.context = Some(context);
@@ -312,6 +384,27 @@ mod tests {
         assert_eq!(rel.confidence, 0.9);
         assert!(rel.is_high_confidence());
         assert!(!rel.has_context());
+        assert!(!rel.is_manual());
+    }
+
+    #[test]
+    fn test_manual_relationship() {
+        let rel =
+            CodeRelationship::manual(Uuid::new_v4(), Uuid::new_v4(), RelationshipType::DependsOn);
+        assert!(rel.is_manual());
+        assert_eq!(rel.confidence, 1.0);
+
+        let json = serde_json::to_value(&rel).unwrap();
+        assert_eq!(json["source"], "manual");
+        assert_eq!(
+            json["relationship_type"],
+            RelationshipType::DependsOn.as_str()
+        );
+        assert_eq!(
+            RelationshipType::from_name("depends_on"),
+            Some(RelationshipType::DependsOn)
+        );
+        assert_eq!(RelationshipType::from_name("owns"), None);
     }
 
     #[test]
@@ -2,12 +2,16 @@
 
 use crate::error::CoreError;
 use crate::models::configuration::Configuration;
-use crate::services::{Service, ServiceHealth, ConfigurationService};
+use crate::services::{
+    Service, ServiceHealth, ConfigurationService, ServiceEvent, ServiceEventHandler,
+};
 use crate::traits::{Validate, Timestamped};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc, Duration};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
@@ -20,8 +24,16 @@ pub struct AnalyticsService {
     aggregators: Arc<RwLock<Vec<MetricsAggregator>>>,
     reporters: Arc<RwLock<Vec<MetricsReporter>>>,
     service_metrics: Arc<RwLock<AnalyticsServiceMetrics>>,
+    /// JSON-lines file that stored events are appended to on shutdown
+    event_log_path: Option<PathBuf>,
+    /// Queries estimated to cost more than this are rejected unrun
+    max_query_cost: u64,
 }
 
+/// Default for `AnalyticsService::with_max_query_cost`: a month of hourly
+/// buckets, grouped three ways, over a few hundred series
+pub const DEFAULT_MAX_QUERY_COST: u64 = 1_000_000;
+
 /// Analytics service metrics
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct AnalyticsServiceMetrics {
@@ -515,9 +527,67 @@ impl AnalyticsService {
             aggregators: Arc::new(RwLock::new(Vec::new())),
             reporters: Arc::new(RwLock::new(Vec::new())),
             service_metrics: Arc::new(RwLock::new(AnalyticsServiceMetrics::default())),
+            event_log_path: None,
+            max_query_cost: DEFAULT_MAX_QUERY_COST,
         })
     }
 
+    /// Append stored events to `path` on shutdown so they survive restarts
+    pub fn with_event_log_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.event_log_path = Some(path.into());
+        self
+    }
+
+    /// Reject queries whose estimated cost exceeds `max_cost`
+    ///
+    /// See `estimate_query_cost` for how the cost is estimated.
+    pub fn with_max_query_cost(mut self, max_cost: u64) -> Self {
+        self.max_query_cost = max_cost;
+        self
+    }
+
+    /// Process every queued event, not just one batch
+    pub async fn drain_events(&self) -> Result<(), CoreError> {
+        loop {
+            let queued = self.event_processor.read().unwrap().processing_queue.len();
+            if queued == 0 {
+                return Ok(());
+            }
+            self.process_events().await?;
+        }
+    }
+
+    /// Append all stored events to `path`, one JSON object per line
+    ///
+    /// Returns the number of events written.
+    pub async fn flush_events(&self, path: &Path) -> Result<usize, CoreError> {
+        let mut lines = Vec::new();
+        let event_count = {
+            let store = self.metrics_store.read().unwrap();
+            for event in &store.event_logs {
+                serde_json::to_writer(&mut lines, event)
+                    .map_err(|e| CoreError::SerializationError(format!("Failed to serialize event: {}", e)))?;
+                lines.push(b'\n');
+            }
+            store.event_logs.len()
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| CoreError::IoError(format!("Failed to create event log directory: {}", e)))?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| CoreError::IoError(format!("Failed to open event log: {}", e)))?;
+        file.write_all(&lines)
+            .and_then(|_| file.sync_all())
+            .map_err(|e| CoreError::IoError(format!("Failed to write event log: {}", e)))?;
+
+        Ok(event_count)
+    }
+
     /// Record an analytics event
     pub async fn record_event(&self, event: AnalyticsEvent) -> Result<(), CoreError> {
         // Validate event
@@ -619,6 +689,16 @@ impl AnalyticsService {
 
     /// Execute analytics query
     pub async fn query(&self, query: AnalyticsQuery) -> Result<AnalyticsQueryResult, CoreError> {
+        let cost = self.estimate_query_cost(&query);
+        if cost > self.max_query_cost {
+            let hours = query_span_hours(&query.time_range);
+            let affordable_hours = (hours * self.max_query_cost / cost).max(1);
+            return Err(CoreError::ValidationError(format!(
+                "Query cost {} exceeds the limit of {}; narrow the time range to about {} hours or group by fewer fields",
+                cost, self.max_query_cost, affordable_hours
+            )));
+        }
+
         let start_time = std::time::Instant::now();
         
         let results = match query.query_type {
@@ -640,6 +720,29 @@ impl AnalyticsService {
         })
     }
 
+    /// Estimate how expensive a query is before running it
+    ///
+    /// The cost is the hours the time range spans, times the group-by
+    /// dimensions, times the series the query would scan: metric time series
+    /// for metric queries and distinct event types otherwise. Each factor
+    /// counts as at least one.
+    pub fn estimate_query_cost(&self, query: &AnalyticsQuery) -> u64 {
+        let series = {
+            let store = self.metrics_store.read().unwrap();
+            match query.query_type {
+                QueryType::Metrics | QueryType::TimeSeries => store.time_series_data.len(),
+                QueryType::Events | QueryType::Aggregated => {
+                    self.service_metrics.read().unwrap().event_type_distribution.len()
+                }
+            }
+        };
+        let dimensions = query.group_by.len().max(1) as u64;
+
+        query_span_hours(&query.time_range)
+            .saturating_mul(dimensions)
+            .saturating_mul(series.max(1) as u64)
+    }
+
     /// Get analytics dashboard data
     pub async fn get_dashboard_data(&self, dashboard_id: &str) -> Result<DashboardData, CoreError> {
         // This would typically load dashboard configuration and execute multiple queries
@@ -1130,10 +1233,12 @@ impl Service for AnalyticsService {
 
     async fn shutdown(&self) -> Result<(), CoreError> {
         // Process any remaining events
-        self.process_events().await?;
+        self.drain_events().await?;
         
-        // Generate final reports if needed
-        // This would be implemented based on requirements
+        // Persist them before the in-memory store goes away
+        if let Some(path) = &self.event_log_path {
+            self.flush_events(path).await?;
+        }
         
         Ok(())
     }
@@ -1176,6 +1281,55 @@ impl Service for AnalyticsService {
     }
 }
 
+/// Records every service event as an analytics event of type `service.<kind>`
+#[async_trait]
+impl ServiceEventHandler for AnalyticsService {
+    async fn handle_event(&self, event: ServiceEvent) -> Result<(), CoreError> {
+        let mut properties = HashMap::new();
+        let (kind, service_name) = match event {
+            ServiceEvent::Started { service_name } => ("started", service_name),
+            ServiceEvent::Stopped { service_name } => ("stopped", service_name),
+            ServiceEvent::Error { service_name, error } => {
+                properties.insert("error".to_string(), serde_json::Value::String(error));
+                ("error", service_name)
+            }
+            ServiceEvent::ConfigurationChanged { service_name } => {
+                ("configuration_changed", service_name)
+            }
+            ServiceEvent::Custom { service_name, event_type, data } => {
+                properties.insert("event_type".to_string(), serde_json::Value::String(event_type));
+                properties.insert("data".to_string(), data);
+                ("custom", service_name)
+            }
+        };
+
+        self.record_event(AnalyticsEvent {
+            id: Uuid::new_v4().to_string(),
+            event_type: format!("service.{}", kind),
+            timestamp: Utc::now(),
+            source: "service_events".to_string(),
+            user_id: None,
+            session_id: None,
+            properties,
+            context: EventContext {
+                service: service_name,
+                version: self.version().to_string(),
+                environment: "runtime".to_string(),
+                request_id: None,
+                trace_id: None,
+                additional_context: HashMap::new(),
+            },
+        })
+        .await
+    }
+}
+
+/// Whole hours a time range covers, rounded up and at least one
+fn query_span_hours(range: &TimeRange) -> u64 {
+    let minutes = (range.end - range.start).num_minutes().max(0) as u64;
+    ((minutes + 59) / 60).max(1)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1279,6 +1433,45 @@ mod tests {
         assert!(query_result.total_results > 0);
     }
 
+    #[tokio::test]
+    async fn test_shutdown_flushes_queued_events() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("events.jsonl");
+        let service = create_test_service().await.with_event_log_path(&log_path);
+
+        // More than one batch, so a single process_events pass would leave some queued
+        for i in 0..250 {
+            let event = AnalyticsEvent {
+                id: format!("event-{}", i),
+                event_type: "search".to_string(),
+                timestamp: Utc::now(),
+                source: "test".to_string(),
+                user_id: None,
+                session_id: None,
+                properties: HashMap::new(),
+                context: EventContext {
+                    service: "test_service".to_string(),
+                    version: "1.0.0".to_string(),
+                    environment: "test".to_string(),
+                    request_id: None,
+                    trace_id: None,
+                    additional_context: HashMap::new(),
+                },
+            };
+            service.record_event(event).await.unwrap();
+        }
+
+        service.shutdown().await.unwrap();
+
+        let content = std::fs::read_to_string(&log_path).unwrap();
+        let events: Vec<AnalyticsEvent> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 250);
+        assert_eq!(events[0].id, "event-0");
+    }
+
     #[tokio::test]
     async fn test_dashboard_data() {
         let service = create_test_service().await;
@@ -1348,4 +1541,46 @@ mod tests {
         let result = service.add_reporter(reporter).await;
         assert!(result.is_ok());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_service_events_are_recorded() {
+        let service = create_test_service().await;
+        service
+            .handle_event(ServiceEvent::Started { service_name: "IndexingService".to_string() })
+            .await
+            .unwrap();
+
+        let metrics = service.get_service_metrics().await;
+        assert_eq!(metrics.event_type_distribution.get("service.started"), Some(&1));
+    }
+
+    #[tokio::test]
+    async fn test_over_budget_query_is_rejected_before_running() {
+        let service = create_test_service().await.with_max_query_cost(24 * 7);
+
+        let query = |days: i64, group_by: &[&str]| AnalyticsQuery {
+            query_type: QueryType::Events,
+            time_range: TimeRange {
+                start: Utc::now() - Duration::days(days),
+                end: Utc::now(),
+            },
+            filters: Vec::new(),
+            aggregations: Vec::new(),
+            group_by: group_by.iter().map(|field| field.to_string()).collect(),
+            order_by: Vec::new(),
+            limit: None,
+            offset: None,
+        };
+
+        assert!(service.query(query(7, &["source"])).await.is_ok());
+
+        let heavy = query(365, &["source", "service", "user_id"]);
+        assert_eq!(service.estimate_query_cost(&heavy), 365 * 24 * 3);
+        match service.query(heavy).await {
+            Err(CoreError::ValidationError(message)) => {
+                assert!(message.contains("narrow the time range"), "{}", message)
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+}
@@ -12,8 +12,10 @@ use crate::services::{Service, ServiceHealth, ConfigurationService};
 use crate::traits::{Validate, Timestamped};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc, Duration};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, BTreeMap};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use tokio::time::{timeout, Duration as TokioDuration};
 use uuid::Uuid;
@@ -27,8 +29,23 @@ pub struct CacheService {
     metrics: Arc<RwLock<CacheServiceMetrics>>,
     policies: Arc<RwLock<HashMap<String, CachePolicy>>>,
     stats_collector: Arc<RwLock<CacheStatsCollector>>,
+    /// Applied to backend operations when a request carries no `timeout_ms`
+    default_timeout: TokioDuration,
+    /// Extra attempts for reads that time out; writes are never retried
+    read_retries: u32,
+    /// When set, shutdown persists snapshottable backends here instead of
+    /// clearing them, and initialize reloads them
+    snapshot_path: Option<PathBuf>,
+    /// Backend lookups a `get_multi` keeps in flight at once
+    max_concurrent_gets: usize,
 }
 
+/// Default timeout for a single backend operation
+const DEFAULT_OPERATION_TIMEOUT_MS: u64 = 5000;
+
+/// Default number of lookups a `get_multi` issues concurrently
+const DEFAULT_MAX_CONCURRENT_GETS: usize = 16;
+
 /// Cache service metrics
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct CacheServiceMetrics {
@@ -41,6 +58,7 @@ pub struct CacheServiceMetrics {
     pub total_size_bytes: u64,
     pub average_response_time_ms: f64,
     pub error_count: u64,
+    pub timeout_count: u64,
     pub backend_metrics: HashMap<String, BackendMetrics>,
 }
 
@@ -52,6 +70,7 @@ pub struct BackendMetrics {
     pub writes: u64,
     pub deletes: u64,
     pub errors: u64,
+    pub timeouts: u64,
     pub size_bytes: u64,
     pub entry_count: u64,
     pub average_response_time_ms: f64,
@@ -75,8 +94,24 @@ pub trait CacheBackend: Send + Sync + std::fmt::Debug {
     /// Check if key exists
     async fn exists(&self, key: &CacheKey) -> Result<bool, CoreError>;
     
-    /// Get multiple values
-    async fn get_multi(&self, keys: &[CacheKey]) -> Result<Vec<Option<CacheEntry>>, CoreError>;
+    /// Get multiple values, keeping up to `max_concurrent` lookups in flight
+    ///
+    /// Returns one result per key, in the order of `keys`, whatever order
+    /// the lookups complete in; a missing key yields `None` at its position.
+    /// By default each key is a separate `get`; a backend that can pipeline
+    /// or batch lookups (e.g. Redis `MGET`) should override this.
+    async fn get_multi(
+        &self,
+        keys: &[CacheKey],
+        max_concurrent: usize,
+    ) -> Result<Vec<Option<CacheEntry>>, CoreError> {
+        // `buffered` yields in input order even when later lookups finish first
+        stream::iter(keys)
+            .map(|key| self.get(key))
+            .buffered(max_concurrent.max(1))
+            .try_collect()
+            .await
+    }
     
     /// Set multiple values
     async fn set_multi(&self, entries: Vec<CacheEntry>) -> Result<(), CoreError>;
@@ -95,6 +130,12 @@ pub trait CacheBackend: Send + Sync + std::fmt::Debug {
     
     /// Health check
     async fn health_check(&self) -> Result<(), CoreError>;
+
+    /// Live entries to persist across restarts, or `None` if the backend
+    /// keeps its own state (e.g. Redis)
+    async fn snapshot(&self) -> Result<Option<Vec<CacheEntry>>, CoreError> {
+        Ok(None)
+    }
 }
 
 /// In-memory cache backend
@@ -249,11 +290,105 @@ impl CacheService {
             metrics: Arc::new(RwLock::new(CacheServiceMetrics::default())),
             policies: Arc::new(RwLock::new(HashMap::new())),
             stats_collector: Arc::new(RwLock::new(CacheStatsCollector::default())),
+            default_timeout: TokioDuration::from_millis(DEFAULT_OPERATION_TIMEOUT_MS),
+            read_retries: 0,
+            snapshot_path: None,
+            max_concurrent_gets: DEFAULT_MAX_CONCURRENT_GETS,
         })
     }
 
+    /// Persist the cache to `path` on shutdown and reload it on initialize
+    pub fn with_snapshot_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.snapshot_path = Some(path.into());
+        self
+    }
+
+    /// Write every snapshottable backend's live entries to `path`
+    ///
+    /// Returns the number of entries written. The file is replaced atomically
+    /// so a crash mid-write leaves the previous snapshot intact.
+    pub async fn save_snapshot(&self, path: &Path) -> Result<usize, CoreError> {
+        let mut snapshot: HashMap<String, Vec<CacheEntry>> = HashMap::new();
+        {
+            let backends = self.backends.read().unwrap();
+            for (name, backend) in backends.iter() {
+                if let Some(entries) = backend.snapshot().await? {
+                    snapshot.insert(name.clone(), entries);
+                }
+            }
+        }
+        let entry_count = snapshot.values().map(Vec::len).sum();
+
+        let content = serde_json::to_vec(&snapshot)
+            .map_err(|e| CoreError::SerializationError(format!("Failed to serialize cache snapshot: {}", e)))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| CoreError::IoError(format!("Failed to create snapshot directory: {}", e)))?;
+        }
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, content)
+            .map_err(|e| CoreError::IoError(format!("Failed to write cache snapshot: {}", e)))?;
+        std::fs::rename(&temp_path, path)
+            .map_err(|e| CoreError::IoError(format!("Failed to replace cache snapshot: {}", e)))?;
+
+        Ok(entry_count)
+    }
+
+    /// Restore entries saved by [`save_snapshot`](Self::save_snapshot)
+    ///
+    /// A missing file restores nothing. Entries that expired while the service
+    /// was down, or whose backend is no longer registered, are dropped.
+    pub async fn load_snapshot(&self, path: &Path) -> Result<usize, CoreError> {
+        let content = match std::fs::read(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(CoreError::IoError(format!("Failed to read cache snapshot: {}", e))),
+        };
+        let snapshot: HashMap<String, Vec<CacheEntry>> = serde_json::from_slice(&content)
+            .map_err(|e| CoreError::ParseError(format!("Invalid cache snapshot: {}", e)))?;
+
+        let now = Utc::now();
+        let mut restored = 0;
+        let backends = self.backends.read().unwrap();
+        for (name, entries) in snapshot {
+            let Some(backend) = backends.get(&name) else {
+                continue;
+            };
+            let live: Vec<CacheEntry> = entries
+                .into_iter()
+                .filter(|entry| entry.expires_at.map_or(true, |expires_at| expires_at > now))
+                .collect();
+            restored += live.len();
+            backend.set_multi(live).await?;
+        }
+
+        Ok(restored)
+    }
+
+    /// Set the fallback operation timeout and how often timed-out reads are retried
+    pub fn with_timeout_policy(mut self, default_timeout: TokioDuration, read_retries: u32) -> Self {
+        self.default_timeout = default_timeout;
+        self.read_retries = read_retries;
+        self
+    }
+
+    /// Limit how many backend lookups a `get_multi` keeps in flight at once
+    pub fn with_max_concurrent_gets(mut self, limit: usize) -> Self {
+        self.max_concurrent_gets = limit.max(1);
+        self
+    }
+
     /// Get a value from cache
     pub async fn get(&self, key: &CacheKey) -> Result<Option<CacheEntry>, CoreError> {
+        self.get_with_timeout(key, None).await
+    }
+
+    /// Get a value from cache, giving up after `timeout_ms` (or the service default)
+    pub async fn get_with_timeout(
+        &self,
+        key: &CacheKey,
+        timeout_ms: Option<u64>,
+    ) -> Result<Option<CacheEntry>, CoreError> {
         let start_time = std::time::Instant::now();
         
         let backend_name = self.get_primary_backend().await?;
@@ -261,7 +396,20 @@ impl CacheService {
         let backend = backends.get(&backend_name)
             .ok_or_else(|| CoreError::NotFound(format!("Backend '{}' not found", backend_name)))?;
         
-        let result = backend.get(key).await;
+        // Reads are idempotent, so a timed-out attempt can safely be repeated
+        let mut attempt = 0;
+        let result = loop {
+            let result = self.with_timeout(
+                &CacheOperation::Get,
+                &backend_name,
+                timeout_ms,
+                backend.get(key),
+            ).await;
+            match result {
+                Err(CoreError::Timeout(_)) if attempt < self.read_retries => attempt += 1,
+                result => break result,
+            }
+        };
         let response_time = start_time.elapsed();
         
         // Update metrics
@@ -286,7 +434,16 @@ impl CacheService {
     }
 
     /// Set a value in cache
-    pub async fn set(&self, mut entry: CacheEntry) -> Result<(), CoreError> {
+    pub async fn set(&self, entry: CacheEntry) -> Result<(), CoreError> {
+        self.set_with_timeout(entry, None).await
+    }
+
+    /// Set a value in cache, giving up after `timeout_ms` (or the service default)
+    pub async fn set_with_timeout(
+        &self,
+        mut entry: CacheEntry,
+        timeout_ms: Option<u64>,
+    ) -> Result<(), CoreError> {
         let start_time = std::time::Instant::now();
         
         // Apply default policy if none specified
@@ -299,7 +456,12 @@ impl CacheService {
         let backend = backends.get(&backend_name)
             .ok_or_else(|| CoreError::NotFound(format!("Backend '{}' not found", backend_name)))?;
         
-        let result = backend.set(entry.clone()).await;
+        let result = self.with_timeout(
+            &CacheOperation::Set,
+            &backend_name,
+            timeout_ms,
+            backend.set(entry.clone()),
+        ).await;
         let response_time = start_time.elapsed();
         
         // Update metrics
@@ -329,6 +491,15 @@ impl CacheService {
 
     /// Delete a value from cache
     pub async fn delete(&self, key: &CacheKey) -> Result<bool, CoreError> {
+        self.delete_with_timeout(key, None).await
+    }
+
+    /// Delete a value from cache, giving up after `timeout_ms` (or the service default)
+    pub async fn delete_with_timeout(
+        &self,
+        key: &CacheKey,
+        timeout_ms: Option<u64>,
+    ) -> Result<bool, CoreError> {
         let start_time = std::time::Instant::now();
         
         let backend_name = self.get_primary_backend().await?;
@@ -336,7 +507,12 @@ impl CacheService {
         let backend = backends.get(&backend_name)
             .ok_or_else(|| CoreError::NotFound(format!("Backend '{}' not found", backend_name)))?;
         
-        let result = backend.delete(key).await;
+        let result = self.with_timeout(
+            &CacheOperation::Delete,
+            &backend_name,
+            timeout_ms,
+            backend.delete(key),
+        ).await;
         let response_time = start_time.elapsed();
         
         // Update metrics
@@ -375,13 +551,17 @@ impl CacheService {
     }
 
     /// Get multiple values from cache
+    ///
+    /// The backend looks keys up concurrently, up to the
+    /// `with_max_concurrent_gets` limit, and results come back in the order
+    /// of `keys`.
     pub async fn get_multi(&self, keys: &[CacheKey]) -> Result<Vec<Option<CacheEntry>>, CoreError> {
         let backend_name = self.get_primary_backend().await?;
         let backends = self.backends.read().unwrap();
         let backend = backends.get(&backend_name)
             .ok_or_else(|| CoreError::NotFound(format!("Backend '{}' not found", backend_name)))?;
         
-        backend.get_multi(keys).await
+        backend.get_multi(keys, self.max_concurrent_gets).await
     }
 
     /// Set multiple values in cache
@@ -514,6 +694,45 @@ impl CacheService {
         Ok(())
     }
 
+    /// Run a backend operation under the request timeout, falling back to the service default
+    async fn with_timeout<T, F>(
+        &self,
+        operation: &CacheOperation,
+        backend_name: &str,
+        timeout_ms: Option<u64>,
+        future: F,
+    ) -> Result<T, CoreError>
+    where
+        F: std::future::Future<Output = Result<T, CoreError>>,
+    {
+        let limit = timeout_ms
+            .map(TokioDuration::from_millis)
+            .unwrap_or(self.default_timeout);
+
+        match timeout(limit, future).await {
+            Ok(result) => result,
+            Err(_) => {
+                self.record_timeout(backend_name).await;
+                Err(CoreError::Timeout(format!(
+                    "Cache {:?} on backend '{}' exceeded {}ms",
+                    operation,
+                    backend_name,
+                    limit.as_millis()
+                )))
+            }
+        }
+    }
+
+    /// Count a timed-out backend operation
+    async fn record_timeout(&self, backend_name: &str) {
+        let mut metrics = self.metrics.write().unwrap();
+        metrics.timeout_count += 1;
+        metrics.backend_metrics
+            .entry(backend_name.to_string())
+            .or_insert_with(BackendMetrics::default)
+            .timeouts += 1;
+    }
+
     /// Get primary backend name
     async fn get_primary_backend(&self) -> Result<String, CoreError> {
         let primary = self.primary_backend.read().unwrap();
@@ -808,14 +1027,6 @@ impl CacheBackend for MemoryBackend {
         Ok(storage.contains_key(&key.key))
     }
     
-    async fn get_multi(&self, keys: &[CacheKey]) -> Result<Vec<Option<CacheEntry>>, CoreError> {
-        let mut results = Vec::new();
-        for key in keys {
-            results.push(self.get(key).await?);
-        }
-        Ok(results)
-    }
-    
     async fn set_multi(&self, entries: Vec<CacheEntry>) -> Result<(), CoreError> {
         for entry in entries {
             self.set(entry).await?;
@@ -862,6 +1073,19 @@ impl CacheBackend for MemoryBackend {
         let _storage = self.storage.read().unwrap();
         Ok(())
     }
+
+    async fn snapshot(&self) -> Result<Option<Vec<CacheEntry>>, CoreError> {
+        let storage = self.storage.read().unwrap();
+        let now = Utc::now();
+
+        Ok(Some(
+            storage
+                .values()
+                .filter(|entry| entry.expires_at.map_or(true, |expires_at| expires_at > now))
+                .cloned()
+                .collect(),
+        ))
+    }
 }
 
 #[async_trait]
@@ -874,11 +1098,25 @@ impl Service for CacheService {
                 eprintln!("Backend {} health check failed: {}", backend.name(), e);
             }
         }
+        drop(backends);
+
+        // A stale or corrupt snapshot only costs a cold cache
+        if let Some(path) = &self.snapshot_path {
+            if let Err(e) = self.load_snapshot(path).await {
+                eprintln!("Failed to load cache snapshot {}: {}", path.display(), e);
+            }
+        }
         Ok(())
     }
 
     async fn shutdown(&self) -> Result<(), CoreError> {
-        // Clear all caches and save metrics
+        // Keep the warm cache for the next start when snapshots are enabled
+        if let Some(path) = &self.snapshot_path {
+            self.save_snapshot(path).await?;
+            return Ok(());
+        }
+
+        // Otherwise clear all caches
         let backends = self.backends.read().unwrap();
         for backend in backends.values() {
             if let Err(e) = backend.clear().await {
@@ -1032,4 +1270,194 @@ mod tests {
         assert!(deleted[0]);
         assert!(deleted[1]);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_get_multi_preserves_key_order() {
+        let service = create_test_service().await.with_max_concurrent_gets(2);
+        let entries = (0..5)
+            .map(|i| CacheEntry::new(
+                CacheKey::new(format!("key{}", i)),
+                CacheValue::String(format!("value{}", i)),
+            ))
+            .collect();
+        service.set_multi(entries).await.unwrap();
+
+        let order = ["key3", "missing", "key0", "key4", "key1", "key2"];
+        let keys: Vec<CacheKey> = order.iter().map(|key| CacheKey::new(key.to_string())).collect();
+        let expected: Vec<Option<String>> = order
+            .iter()
+            .map(|key| (*key != "missing").then(|| key.to_string()))
+            .collect();
+        let found = |results: &[Option<CacheEntry>]| -> Vec<Option<String>> {
+            results.iter().map(|entry| entry.as_ref().map(|entry| entry.key.key.clone())).collect()
+        };
+
+        let results = service.get_multi(&keys).await.unwrap();
+        assert_eq!(found(&results), expected);
+
+        // Backends hold to the same contract
+        let backend = MemoryBackend::new("memory".to_string(), 100, EvictionPolicy::Lru);
+        for i in 0..5 {
+            backend.set(CacheEntry::new(
+                CacheKey::new(format!("key{}", i)),
+                CacheValue::String(format!("value{}", i)),
+            )).await.unwrap();
+        }
+        let results = backend.get_multi(&keys, 2).await.unwrap();
+        assert_eq!(found(&results), expected);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let snapshot_path = temp_dir.path().join("cache").join("snapshot.json");
+
+        let service = create_test_service().await.with_snapshot_path(&snapshot_path);
+        let key = CacheKey::new("warm".to_string());
+        service.set(CacheEntry::new(key.clone(), CacheValue::String("kept".to_string()))).await.unwrap();
+        let mut expired = CacheEntry::new(
+            CacheKey::new("expired".to_string()),
+            CacheValue::String("dropped".to_string()),
+        );
+        expired.expires_at = Some(Utc::now() - Duration::seconds(1));
+        service.set(expired).await.unwrap();
+
+        service.shutdown().await.unwrap();
+        assert!(snapshot_path.exists());
+        // Snapshotting keeps the running cache instead of clearing it
+        assert!(service.get(&key).await.unwrap().is_some());
+
+        let restarted = create_test_service().await.with_snapshot_path(&snapshot_path);
+        restarted.initialize().await.unwrap();
+
+        let restored = restarted.get(&key).await.unwrap().unwrap();
+        assert_eq!(restored.key.key, "warm");
+        assert!(restarted.get(&CacheKey::new("expired".to_string())).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_load_missing_snapshot_restores_nothing() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let service = create_test_service().await;
+
+        let restored = service.load_snapshot(&temp_dir.path().join("missing.json")).await.unwrap();
+        assert_eq!(restored, 0);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_without_snapshot_clears() {
+        let service = create_test_service().await;
+        let key = CacheKey::new("cold".to_string());
+        service.set(CacheEntry::new(key.clone(), CacheValue::String("gone".to_string()))).await.unwrap();
+
+        service.shutdown().await.unwrap();
+        assert!(service.get(&key).await.unwrap().is_none());
+    }
+
+    /// Backend whose reads and writes take longer than any test timeout
+    #[derive(Debug)]
+    struct SlowBackend {
+        delay: TokioDuration,
+    }
+
+    #[async_trait]
+    impl CacheBackend for SlowBackend {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        async fn get(&self, _key: &CacheKey) -> Result<Option<CacheEntry>, CoreError> {
+            tokio::time::sleep(self.delay).await;
+            Ok(None)
+        }
+
+        async fn set(&self, _entry: CacheEntry) -> Result<(), CoreError> {
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+
+        async fn delete(&self, _key: &CacheKey) -> Result<bool, CoreError> {
+            tokio::time::sleep(self.delay).await;
+            Ok(false)
+        }
+
+        async fn exists(&self, _key: &CacheKey) -> Result<bool, CoreError> {
+            Ok(false)
+        }
+
+        async fn get_multi(
+            &self,
+            keys: &[CacheKey],
+            _max_concurrent: usize,
+        ) -> Result<Vec<Option<CacheEntry>>, CoreError> {
+            Ok(vec![None; keys.len()])
+        }
+
+        async fn set_multi(&self, _entries: Vec<CacheEntry>) -> Result<(), CoreError> {
+            Ok(())
+        }
+
+        async fn delete_multi(&self, keys: &[CacheKey]) -> Result<Vec<bool>, CoreError> {
+            Ok(vec![false; keys.len()])
+        }
+
+        async fn clear(&self) -> Result<(), CoreError> {
+            Ok(())
+        }
+
+        async fn get_stats(&self) -> Result<BackendMetrics, CoreError> {
+            Ok(BackendMetrics::default())
+        }
+
+        async fn maintenance(&self) -> Result<(), CoreError> {
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<(), CoreError> {
+            Ok(())
+        }
+    }
+
+    async fn create_slow_service(read_retries: u32) -> CacheService {
+        let service = create_test_service()
+            .await
+            .with_timeout_policy(TokioDuration::from_millis(20), read_retries);
+        service.add_backend(
+            "slow".to_string(),
+            Box::new(SlowBackend { delay: TokioDuration::from_secs(5) }),
+        ).await.unwrap();
+        service.set_primary_backend("slow".to_string()).await.unwrap();
+        service
+    }
+
+    #[tokio::test]
+    async fn test_slow_backend_times_out() {
+        let service = create_slow_service(0).await;
+        let key = CacheKey::new("slow_key".to_string());
+
+        let result = service.get(&key).await;
+        assert!(matches!(result, Err(CoreError::Timeout(_))));
+
+        // A per-request timeout overrides the service default
+        let entry = CacheEntry::new(key.clone(), CacheValue::String("value".to_string()));
+        let result = service.set_with_timeout(entry, Some(10)).await;
+        assert!(matches!(result, Err(CoreError::Timeout(_))));
+
+        let metrics = service.get_stats().await.unwrap();
+        assert_eq!(metrics.timeout_count, 2);
+        assert_eq!(metrics.backend_metrics["slow"].timeouts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_reads_are_retried() {
+        let service = create_slow_service(2).await;
+        let key = CacheKey::new("slow_key".to_string());
+
+        let result = service.get(&key).await;
+        assert!(matches!(result, Err(CoreError::Timeout(_))));
+
+        // One initial attempt plus two retries
+        let metrics = service.get_stats().await.unwrap();
+        assert_eq!(metrics.timeout_count, 3);
+    }
+}
@@ -6,7 +6,7 @@ use crate::models::configuration::{
     StorageConfig, CacheConfig, SecurityConfig, LoggingConfig, PerformanceConfig,
     PluginConfig, EnvironmentConfig
 };
-use crate::services::{Service, ServiceHealth};
+use crate::services::{EventBus, Service, ServiceEvent, ServiceHealth};
 use crate::traits::{Validate, Timestamped};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -25,6 +25,7 @@ pub struct ConfigurationService {
     config_sources: Arc<RwLock<Vec<ConfigurationSource>>>,
     validation_rules: Arc<RwLock<HashMap<String, ValidationRule>>>,
     metrics: Arc<RwLock<ConfigurationMetrics>>,
+    events: EventBus,
 }
 
 /// Configuration snapshot for history tracking
@@ -212,9 +213,16 @@ impl ConfigurationService {
             config_sources: Arc::new(RwLock::new(Vec::new())),
             validation_rules: Arc::new(RwLock::new(HashMap::new())),
             metrics: Arc::new(RwLock::new(ConfigurationMetrics::default())),
+            events: EventBus::default(),
         })
     }
 
+    /// Announce configuration changes on `events`
+    pub fn with_event_bus(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
     /// Load configuration from file
     pub async fn load_from_file<P: AsRef<Path>>(&self, path: P) -> Result<(), CoreError> {
         let start_time = std::time::Instant::now();
@@ -688,8 +696,11 @@ impl ConfigurationService {
         
         // Update metrics
         drop(watchers);
-        let mut metrics = self.metrics.write().unwrap();
-        metrics.watcher_triggers += 1;
+        self.metrics.write().unwrap().watcher_triggers += 1;
+
+        self.events.emit(ServiceEvent::ConfigurationChanged {
+            service_name: self.name().to_string(),
+        });
     }
 
     /// Update load metrics
@@ -0,0 +1,126 @@
+//! In-process broadcast of service events
+
+use crate::services::{ServiceEvent, ServiceEventHandler};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// Events a slow handler may fall behind by before it starts missing them
+pub const DEFAULT_EVENT_BUS_CAPACITY: usize = 1024;
+
+/// Broadcast channel that services emit `ServiceEvent`s on
+///
+/// Clones share the channel, so every service handed a clone reaches the same
+/// handlers. Emitting never blocks: a handler that falls more than the
+/// capacity behind skips the oldest events it missed.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<ServiceEvent>,
+}
+
+impl EventBus {
+    /// Create a bus buffering up to `capacity` events per handler
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender }
+    }
+
+    /// Send an event to every registered handler
+    ///
+    /// Returns how many handlers it reached; having none is not an error.
+    pub fn emit(&self, event: ServiceEvent) -> usize {
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    /// Receive events emitted from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<ServiceEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Pass every event emitted from now on to `handler` on its own task
+    ///
+    /// Handler errors are logged and do not stop delivery. The task ends once
+    /// every clone of the bus is dropped, or when the handle is aborted.
+    pub fn register_handler(&self, handler: Arc<dyn ServiceEventHandler>) -> JoinHandle<()> {
+        let mut receiver = self.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        if let Err(e) = handler.handle_event(event).await {
+                            tracing::warn!("Service event handler failed: {}", e);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Service event handler skipped {} events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Number of handlers and subscribers currently listening
+    pub fn receiver_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_BUS_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::CoreError;
+    use async_trait::async_trait;
+    use tokio::sync::mpsc;
+
+    struct ForwardingHandler {
+        sender: mpsc::UnboundedSender<ServiceEvent>,
+    }
+
+    #[async_trait]
+    impl ServiceEventHandler for ForwardingHandler {
+        async fn handle_event(&self, event: ServiceEvent) -> Result<(), CoreError> {
+            self.sender
+                .send(event)
+                .map_err(|e| CoreError::Internal(e.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registered_handler_receives_emitted_event() {
+        let bus = EventBus::default();
+        assert_eq!(
+            bus.emit(ServiceEvent::Started {
+                service_name: "unheard".to_string()
+            }),
+            0
+        );
+
+        let (sender, mut received) = mpsc::unbounded_channel();
+        let handle = bus.register_handler(Arc::new(ForwardingHandler { sender }));
+        assert_eq!(bus.receiver_count(), 1);
+
+        let delivered = bus.clone().emit(ServiceEvent::ConfigurationChanged {
+            service_name: "configuration".to_string(),
+        });
+        assert_eq!(delivered, 1);
+
+        let event = tokio::time::timeout(std::time::Duration::from_secs(1), received.recv())
+            .await
+            .expect("handler should receive the event")
+            .unwrap();
+        assert!(matches!(
+            event,
+            ServiceEvent::ConfigurationChanged { ref service_name } if service_name == "configuration"
+        ));
+
+        drop(bus);
+        handle.await.unwrap();
+    }
+}
@@ -11,7 +11,7 @@ use crate::models::{
 };
 use crate::services::{
     Service, ServiceHealth, ConfigurationService, CodebaseService, 
-    EmbeddingService, PluginService
+    EmbeddingService, PluginService, EventBus, ServiceEvent
 };
 use crate::traits::{Validate, Timestamped};
 use async_trait::async_trait;
@@ -34,6 +34,7 @@ pub struct IndexingService {
     indexes: Arc<RwLock<HashMap<String, Index>>>,
     metrics: Arc<RwLock<IndexingMetrics>>,
     worker_pool: Arc<RwLock<WorkerPool>>,
+    events: EventBus,
 }
 
 /// Indexing service metrics
@@ -167,9 +168,16 @@ impl IndexingService {
             indexes: Arc::new(RwLock::new(HashMap::new())),
             metrics: Arc::new(RwLock::new(IndexingMetrics::default())),
             worker_pool: Arc::new(RwLock::new(worker_pool)),
+            events: EventBus::default(),
         })
     }
 
+    /// Announce the start and end of every job on `events`
+    pub fn with_event_bus(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
     /// Queue a new indexing job
     pub async fn queue_indexing_job(
         &self,
@@ -306,19 +314,33 @@ impl IndexingService {
         Ok(())
     }
 
-    /// Process an indexing job
-    async fn process_job(&self, job_id: &str, worker_id: &str) -> Result<(), CoreError> {
+    /// Run a job to the end on the calling task, bypassing the internal queue
+    ///
+    /// Progress is recorded on `job` as files are processed. Files that fail
+    /// are collected in the result's `errors` rather than aborting the run.
+    /// `Started` and `Stopped` are emitted around the run, with an `Error`
+    /// before `Stopped` if it aborts.
+    pub async fn execute_job(&self, job: &mut IndexJob) -> Result<IndexingResult, CoreError> {
+        let service_name = self.name().to_string();
+        self.events.emit(ServiceEvent::Started { service_name: service_name.clone() });
+
+        let result = self.run_job(job).await;
+        if let Err(e) = &result {
+            self.events.emit(ServiceEvent::Error {
+                service_name: service_name.clone(),
+                error: e.to_string(),
+            });
+        }
+        self.events.emit(ServiceEvent::Stopped { service_name });
+        result
+    }
+
+    async fn run_job(&self, job: &mut IndexJob) -> Result<IndexingResult, CoreError> {
         let start_time = std::time::Instant::now();
-        
-        // Get job and codebase
-        let mut job = self.get_job_status(job_id).await?;
         let codebase = self.codebase_service.get_codebase(&job.codebase_id).await?;
         
-        // Update codebase status
-        self.update_codebase_status(&job.codebase_id, CodebaseStatus::Indexing).await?;
-        
         let mut result = IndexingResult {
-            job_id: job_id.to_string(),
+            job_id: job.id.clone(),
             codebase_id: job.codebase_id.clone(),
             status: IndexJobStatus::Running,
             files_processed: 0,
@@ -332,16 +354,16 @@ impl IndexingService {
         // Process based on job type
         match job.job_type {
             IndexJobType::FullIndex => {
-                self.process_full_index(&mut job, &codebase, &mut result).await?;
+                self.process_full_index(job, &codebase, &mut result).await?;
             }
             IndexJobType::IncrementalIndex => {
-                self.process_incremental_index(&mut job, &codebase, &mut result).await?;
+                self.process_incremental_index(job, &codebase, &mut result).await?;
             }
             IndexJobType::FileReindex => {
-                self.process_file_reindex(&mut job, &codebase, &mut result).await?;
+                self.process_file_reindex(job, &codebase, &mut result).await?;
             }
             IndexJobType::EmbeddingGeneration => {
-                self.process_embedding_generation(&mut job, &codebase, &mut result).await?;
+                self.process_embedding_generation(job, &codebase, &mut result).await?;
             }
             _ => {
                 return Err(CoreError::ValidationError(
@@ -350,13 +372,29 @@ impl IndexingService {
             }
         }
         
-        // Complete job
-        let duration = start_time.elapsed();
-        result.duration_ms = duration.as_millis() as u64;
+        result.duration_ms = start_time.elapsed().as_millis() as u64;
+        result.status = if result.errors.is_empty() {
+            IndexJobStatus::Completed
+        } else {
+            IndexJobStatus::Failed
+        };
+        
+        self.update_metrics_after_job(&result).await;
+        
+        Ok(result)
+    }
+
+    /// Process an indexing job
+    async fn process_job(&self, job_id: &str, worker_id: &str) -> Result<(), CoreError> {
+        let mut job = self.get_job_status(job_id).await?;
+        
+        // Update codebase status
+        self.update_codebase_status(&job.codebase_id, CodebaseStatus::Indexing).await?;
+        
+        let result = self.execute_job(&mut job).await?;
         
         if result.errors.is_empty() {
             job.complete();
-            result.status = IndexJobStatus::Completed;
             self.update_codebase_status(&job.codebase_id, CodebaseStatus::Ready).await?;
         } else {
             let error = crate::models::index_job::JobError::new(
@@ -365,7 +403,6 @@ impl IndexingService {
                 true,
             );
             job.fail(error);
-            result.status = IndexJobStatus::Failed;
         }
         
         // Update job
@@ -374,9 +411,6 @@ impl IndexingService {
             active_jobs.insert(job_id.to_string(), job);
         }
         
-        // Update metrics
-        self.update_metrics_after_job(&result).await;
-        
         // Free worker
         self.free_worker_for_job(job_id).await;
         
@@ -844,6 +878,7 @@ impl Clone for IndexingService {
             indexes: self.indexes.clone(),
             metrics: self.metrics.clone(),
             worker_pool: self.worker_pool.clone(),
+            events: self.events.clone(),
         }
     }
 }
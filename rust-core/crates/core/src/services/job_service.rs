@@ -9,7 +9,7 @@ use crate::models::{
     },
     configuration::Configuration,
 };
-use crate::services::{Service, ServiceHealth, ConfigurationService};
+use crate::services::{Service, ServiceHealth, ConfigurationService, IndexingService};
 use crate::traits::{Validate, Timestamped};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc, Duration};
@@ -29,6 +29,50 @@ pub struct JobService {
     scheduler: Arc<RwLock<JobScheduler>>,
     metrics: Arc<RwLock<JobServiceMetrics>>,
     job_history: Arc<RwLock<Vec<JobExecutionRecord>>>,
+    executor: Arc<dyn JobExecutor>,
+}
+
+/// Runs the work behind a job once a worker picks it up
+///
+/// Implementations record progress and statistics on the job they are given.
+/// Returning an error fails the job with that error.
+#[async_trait]
+pub trait JobExecutor: Send + Sync + std::fmt::Debug {
+    async fn execute(&self, job: &mut IndexJob) -> Result<(), CoreError>;
+}
+
+/// Executor running indexing jobs on the [`IndexingService`]
+#[derive(Debug)]
+pub struct IndexingJobExecutor {
+    indexing: Arc<IndexingService>,
+}
+
+impl IndexingJobExecutor {
+    pub fn new(indexing: Arc<IndexingService>) -> Self {
+        Self { indexing }
+    }
+}
+
+#[async_trait]
+impl JobExecutor for IndexingJobExecutor {
+    async fn execute(&self, job: &mut IndexJob) -> Result<(), CoreError> {
+        let result = self.indexing.execute_job(job).await?;
+        
+        job.stats.files_processed = result.files_processed;
+        job.stats.entities_created = result.entities_created;
+        job.stats.relationships_created = result.relationships_created;
+        job.stats.embeddings_generated = result.embeddings_generated;
+        
+        match result.errors.first() {
+            None => Ok(()),
+            Some(first) => Err(CoreError::IndexError(format!(
+                "{} files failed to index, first {}: {}",
+                result.errors.len(),
+                first.file_path,
+                first.message,
+            ))),
+        }
+    }
 }
 
 /// Job service metrics
@@ -61,6 +105,42 @@ pub struct JobQueue {
     pub recurring_jobs: Vec<RecurringJob>,
 }
 
+impl JobQueue {
+    /// Take the queued entry with the highest effective priority
+    ///
+    /// An entry's effective priority is its priority level plus
+    /// `aging_per_minute` levels for every minute it has waited since
+    /// `queued_at`, so a long-waiting low-priority job eventually outranks
+    /// fresh high-priority ones. Ties go to the entry queued first.
+    pub fn pop_next(&mut self, now: DateTime<Utc>, aging_per_minute: f64) -> Option<JobQueueEntry> {
+        let effective_priority = |entry: &JobQueueEntry| {
+            let waited_minutes = (now - entry.queued_at).num_milliseconds().max(0) as f64 / 60_000.0;
+            entry.priority.clone() as i32 as f64 + waited_minutes * aging_per_minute
+        };
+        
+        // Each queue is FIFO within one priority, so only its head can win
+        let mut best: Option<(&mut VecDeque<JobQueueEntry>, f64, DateTime<Utc>)> = None;
+        for queue in [&mut self.high_priority, &mut self.normal_priority, &mut self.low_priority] {
+            let Some(head) = queue.front() else {
+                continue;
+            };
+            let (priority, queued_at) = (effective_priority(head), head.queued_at);
+            let better = match &best {
+                None => true,
+                Some((_, best_priority, best_queued_at)) => {
+                    priority > *best_priority
+                        || (priority == *best_priority && queued_at < *best_queued_at)
+                }
+            };
+            if better {
+                best = Some((queue, priority, queued_at));
+            }
+        }
+        
+        best.and_then(|(queue, _, _)| queue.pop_front())
+    }
+}
+
 /// Delayed job entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DelayedJob {
@@ -145,6 +225,8 @@ pub struct JobScheduler {
     pub job_timeout_seconds: u64,
     pub retry_attempts: u32,
     pub retry_delay_seconds: u64,
+    /// Priority levels a queued job gains per minute of waiting; 0 disables aging
+    pub priority_aging_per_minute: f64,
 }
 
 /// Job execution record
@@ -162,6 +244,7 @@ pub struct JobExecutionRecord {
     pub cpu_usage_percent: Option<f64>,
     pub error: Option<JobError>,
     pub retry_count: u32,
+    pub progress: JobProgress,
 }
 
 /// Job creation request
@@ -217,8 +300,19 @@ pub struct JobStatistics {
 }
 
 impl JobService {
-    /// Create a new job service
-    pub async fn new(config_service: Arc<ConfigurationService>) -> Result<Self, CoreError> {
+    /// Create a new job service whose workers run jobs on `indexing`
+    pub async fn new(
+        config_service: Arc<ConfigurationService>,
+        indexing: Arc<IndexingService>,
+    ) -> Result<Self, CoreError> {
+        Self::with_executor(config_service, Arc::new(IndexingJobExecutor::new(indexing))).await
+    }
+
+    /// Create a new job service whose workers run jobs with `executor`
+    pub async fn with_executor(
+        config_service: Arc<ConfigurationService>,
+        executor: Arc<dyn JobExecutor>,
+    ) -> Result<Self, CoreError> {
         Ok(Self {
             config_service,
             jobs: Arc::new(RwLock::new(HashMap::new())),
@@ -227,6 +321,7 @@ impl JobService {
             scheduler: Arc::new(RwLock::new(JobScheduler::default())),
             metrics: Arc::new(RwLock::new(JobServiceMetrics::default())),
             job_history: Arc::new(RwLock::new(Vec::new())),
+            executor,
         })
     }
 
@@ -441,6 +536,17 @@ impl JobService {
         Ok(())
     }
 
+    /// Set how many priority levels a queued job gains per minute of waiting
+    pub async fn set_priority_aging(&self, levels_per_minute: f64) -> Result<(), CoreError> {
+        if !levels_per_minute.is_finite() || levels_per_minute < 0.0 {
+            return Err(CoreError::ValidationError(
+                format!("Priority aging rate must be a non-negative number, got {}", levels_per_minute)
+            ));
+        }
+        self.scheduler.write().unwrap().priority_aging_per_minute = levels_per_minute;
+        Ok(())
+    }
+
     /// Get worker status
     pub async fn get_workers(&self) -> Vec<JobWorker> {
         self.workers.read().unwrap().clone()
@@ -600,13 +706,11 @@ impl JobService {
             return Ok(());
         }
         
-        // Process high priority jobs first
+        // Highest effective priority first, so waiting jobs age upward
+        let aging_per_minute = self.scheduler.read().unwrap().priority_aging_per_minute;
+        let now = Utc::now();
         for worker in available_workers {
-            if let Some(job_entry) = queue.high_priority.pop_front() {
-                self.assign_job_to_worker(worker, job_entry).await?;
-            } else if let Some(job_entry) = queue.normal_priority.pop_front() {
-                self.assign_job_to_worker(worker, job_entry).await?;
-            } else if let Some(job_entry) = queue.low_priority.pop_front() {
+            if let Some(job_entry) = queue.pop_next(now, aging_per_minute) {
                 self.assign_job_to_worker(worker, job_entry).await?;
             }
         }
@@ -698,34 +802,57 @@ impl JobService {
         worker.last_activity = Utc::now();
         
         // Update job status
-        {
+        let job = {
             let mut jobs = self.jobs.write().unwrap();
-            if let Some(job) = jobs.get_mut(&job_entry.job_id) {
-                job.status = IndexJobStatus::Running;
-                job.started_at = Some(Utc::now());
-                job.updated_at = Utc::now();
-            }
-        }
+            let job = jobs.get_mut(&job_entry.job_id)
+                .ok_or_else(|| CoreError::NotFound(format!("Job '{}' not found", job_entry.job_id)))?;
+            job.start();
+            job.clone()
+        };
         
-        // In a real implementation, this would start the actual job execution
-        // For now, we'll simulate job completion
         tokio::spawn({
-            let job_id = job_entry.job_id.clone();
             let worker_id = worker.id.clone();
+            let queued_at = job_entry.queued_at;
+            let executor = Arc::clone(&self.executor);
             let jobs = Arc::clone(&self.jobs);
             let workers = Arc::clone(&self.workers);
+            let job_history = Arc::clone(&self.job_history);
             
             async move {
-                // Simulate job execution
-                tokio::time::sleep(TokioDuration::from_secs(1)).await;
+                let mut job = job;
+                let started_at = job.started_at.unwrap_or_else(Utc::now);
+                let outcome = executor.execute(&mut job).await;
+                let succeeded = outcome.is_ok();
+                
+                match outcome {
+                    Ok(()) => job.complete(),
+                    Err(e) => job.fail(JobError::new("EXECUTION_ERROR".to_string(), e.to_string(), true)),
+                }
+                let duration_ms = job.stats.total_execution_time_ms;
                 
-                // Complete job
+                job_history.write().unwrap().push(JobExecutionRecord {
+                    job_id: job.id.clone(),
+                    job_type: job.job_type.clone(),
+                    status: job.status.clone(),
+                    worker_id: worker_id.clone(),
+                    started_at,
+                    completed_at: job.completed_at,
+                    duration_ms: Some(duration_ms),
+                    queue_time_ms: (started_at - queued_at).num_milliseconds().max(0) as u64,
+                    memory_used_mb: None,
+                    cpu_usage_percent: None,
+                    error: job.error.clone(),
+                    retry_count: job.stats.retry_count,
+                    progress: job.progress.clone(),
+                });
+                
+                // A job cancelled while running keeps its cancelled status
                 {
                     let mut jobs = jobs.write().unwrap();
-                    if let Some(job) = jobs.get_mut(&job_id) {
-                        job.status = IndexJobStatus::Completed;
-                        job.completed_at = Some(Utc::now());
-                        job.updated_at = Utc::now();
+                    if let Some(stored) = jobs.get_mut(&job.id) {
+                        if stored.status != IndexJobStatus::Cancelled {
+                            *stored = job;
+                        }
                     }
                 }
                 
@@ -739,7 +866,16 @@ impl JobService {
                             worker.current_job_count -= 1;
                         }
                         worker.total_jobs_processed += 1;
-                        worker.successful_jobs += 1;
+                        if succeeded {
+                            worker.successful_jobs += 1;
+                        } else {
+                            worker.failed_jobs += 1;
+                        }
+                        worker.average_processing_time_ms = (worker.average_processing_time_ms
+                            * (worker.total_jobs_processed - 1) as f64
+                            + duration_ms as f64)
+                            / worker.total_jobs_processed as f64;
+                        worker.last_activity = Utc::now();
                     }
                 }
             }
@@ -748,6 +884,11 @@ impl JobService {
         Ok(())
     }
 
+    /// Execution records of finished jobs, oldest first
+    pub async fn get_job_history(&self) -> Vec<JobExecutionRecord> {
+        self.job_history.read().unwrap().clone()
+    }
+
     /// Check if job matches filters
     fn matches_filters(&self, job: &IndexJob, filters: &JobQueryFilters) -> bool {
         if let Some(ref job_types) = filters.job_types {
@@ -914,6 +1055,8 @@ impl Service for JobService {
             scheduler.job_timeout_seconds = 3600;
             scheduler.retry_attempts = 3;
             scheduler.retry_delay_seconds = 60;
+            // A low-priority job outranks fresh high-priority ones after 10 minutes
+            scheduler.priority_aging_per_minute = 0.2;
         }
         
         Ok(())
@@ -988,10 +1131,55 @@ impl Service for JobService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::{CodebaseService, EmbeddingService, PluginService};
+    use crate::services::codebase_service::CreateCodebaseRequest;
+    use tempfile::TempDir;
 
-    async fn create_test_service() -> JobService {
+    async fn create_services() -> (JobService, Arc<CodebaseService>) {
         let config_service = Arc::new(ConfigurationService::new().await.unwrap());
-        JobService::new(config_service).await.unwrap()
+        let codebase_service = Arc::new(CodebaseService::new(config_service.clone()).await.unwrap());
+        let embedding_service = Arc::new(EmbeddingService::new(config_service.clone()).await.unwrap());
+        let plugin_service = Arc::new(PluginService::new(config_service.clone()).await.unwrap());
+        let indexing_service = Arc::new(IndexingService::new(
+            config_service.clone(),
+            codebase_service.clone(),
+            embedding_service,
+            plugin_service,
+        ).await.unwrap());
+        
+        let service = JobService::new(config_service, indexing_service).await.unwrap();
+        (service, codebase_service)
+    }
+
+    async fn create_test_service() -> JobService {
+        create_services().await.0
+    }
+
+    async fn wait_until_finished(service: &JobService, job_id: &str) -> IndexJob {
+        timeout(TokioDuration::from_secs(10), async {
+            loop {
+                let job = service.get_job(job_id).await.unwrap();
+                if job.is_completed() {
+                    return job;
+                }
+                tokio::time::sleep(TokioDuration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("job did not finish")
+    }
+
+    fn full_index_request(codebase_id: String) -> CreateJobRequest {
+        CreateJobRequest {
+            job_type: IndexJobType::FullIndex,
+            codebase_id,
+            priority: JobPriority::Normal,
+            config: IndexJobConfig::default(),
+            metadata: None,
+            delay_seconds: None,
+            max_retries: None,
+            timeout_seconds: None,
+        }
     }
 
     #[tokio::test]
@@ -1169,4 +1357,95 @@ mod tests {
         
         assert!(result.is_ok());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_full_index_job_runs_the_indexing_service() {
+        let (service, codebase_service) = create_services().await;
+        service.initialize().await.unwrap();
+        
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("lib.rs"), "pub fn alpha() {}\nfn beta() {}\n").unwrap();
+        let codebase = codebase_service.create_codebase(CreateCodebaseRequest {
+            name: "indexed".to_string(),
+            path: temp_dir.path().to_path_buf(),
+            description: None,
+            configuration_id: None,
+            auto_index: false,
+        }).await.unwrap();
+        
+        let job_id = service.create_job(full_index_request(codebase.id)).await.unwrap();
+        service.process_queue().await.unwrap();
+        
+        let job = wait_until_finished(&service, &job_id).await;
+        assert_eq!(job.status, IndexJobStatus::Completed);
+        assert_eq!(job.stats.files_processed, 1);
+        assert_eq!(job.stats.entities_created, 2);
+        
+        let history = service.get_job_history().await;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].status, IndexJobStatus::Completed);
+        assert_eq!(history[0].progress.processed_items, 1);
+        assert!(history[0].duration_ms.is_some());
+        assert!(history[0].error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_job_fails_with_the_executor_error() {
+        let (service, _) = create_services().await;
+        service.initialize().await.unwrap();
+        
+        let job_id = service.create_job(full_index_request("missing_codebase".to_string())).await.unwrap();
+        service.process_queue().await.unwrap();
+        
+        let job = wait_until_finished(&service, &job_id).await;
+        assert_eq!(job.status, IndexJobStatus::Failed);
+        let error = job.error.unwrap();
+        assert!(error.message.contains("missing_codebase"), "{}", error.message);
+        
+        let history = service.get_job_history().await;
+        assert_eq!(history[0].status, IndexJobStatus::Failed);
+        assert_eq!(history[0].error.as_ref().map(|e| e.message.clone()), Some(error.message));
+    }
+
+    /// Serve one job per simulated minute while a fresh high-priority job
+    /// arrives every minute; the minute the old low-priority job is served
+    fn minutes_until_low_priority_runs(aging_per_minute: f64) -> Option<usize> {
+        let start = Utc::now();
+        let mut queue = JobQueue::default();
+        let mut low = JobQueueEntry::new("low".to_string(), JobPriority::Low);
+        low.queued_at = start;
+        queue.low_priority.push_back(low);
+        
+        for minute in 0..120 {
+            let now = start + Duration::minutes(minute as i64);
+            let mut high = JobQueueEntry::new(format!("high_{}", minute), JobPriority::High);
+            high.queued_at = now;
+            queue.high_priority.push_back(high);
+            
+            if queue.pop_next(now, aging_per_minute).unwrap().job_id == "low" {
+                return Some(minute);
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_priority_aging_prevents_starvation() {
+        // Without aging the high-priority flood starves the low-priority job
+        assert_eq!(minutes_until_low_priority_runs(0.0), None);
+        
+        // Two levels behind, it draws level after 10 minutes and wins the
+        // tie as the older entry
+        assert_eq!(minutes_until_low_priority_runs(0.2), Some(10));
+        assert_eq!(minutes_until_low_priority_runs(1.0), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_priority_aging_rate_is_validated() {
+        let service = create_test_service().await;
+        assert!(service.set_priority_aging(0.5).await.is_ok());
+        assert_eq!(service.scheduler.read().unwrap().priority_aging_per_minute, 0.5);
+        assert!(service.set_priority_aging(-1.0).await.is_err());
+        assert!(service.set_priority_aging(f64::NAN).await.is_err());
+    }
+}
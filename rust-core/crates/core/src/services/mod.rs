@@ -9,6 +9,7 @@ pub mod plugin_service;
 pub mod configuration_service;
 pub mod job_service;
 pub mod analytics_service;
+pub mod event_bus;
 
 // Re-export services
 pub use codebase_service::CodebaseService;
@@ -20,10 +21,15 @@ pub use plugin_service::PluginService;
 pub use configuration_service::ConfigurationService;
 pub use job_service::JobService;
 pub use analytics_service::AnalyticsService;
+pub use event_bus::EventBus;
 
 use crate::error::CoreError;
 use async_trait::async_trait;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long a single service's health check may take before it is reported unhealthy
+pub const DEFAULT_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Service registry for managing all core services
 #[derive(Debug, Clone)]
@@ -37,12 +43,24 @@ pub struct ServiceRegistry {
     pub configuration: Arc<ConfigurationService>,
     pub job: Arc<JobService>,
     pub analytics: Arc<AnalyticsService>,
+    pub events: EventBus,
+    pub health_check_timeout: Duration,
 }
 
 impl ServiceRegistry {
     /// Create a new service registry
     pub async fn new() -> Result<Self, CoreError> {
-        let configuration = Arc::new(ConfigurationService::new().await?);
+        Self::with_event_bus(EventBus::default()).await
+    }
+
+    /// Create a service registry whose services emit on `events`
+    ///
+    /// The analytics service is registered as a handler, so every event is
+    /// also recorded there.
+    pub async fn with_event_bus(events: EventBus) -> Result<Self, CoreError> {
+        let configuration = Arc::new(
+            ConfigurationService::new().await?.with_event_bus(events.clone()),
+        );
         let cache = Arc::new(CacheService::new(configuration.clone()).await?);
         let codebase = Arc::new(CodebaseService::new(configuration.clone()).await?);
         let embedding = Arc::new(EmbeddingService::new(configuration.clone()).await?);
@@ -52,7 +70,7 @@ impl ServiceRegistry {
             codebase.clone(),
             embedding.clone(),
             plugin.clone(),
-        ).await?);
+        ).await?.with_event_bus(events.clone()));
         let search = Arc::new(SearchService::new(
             configuration.clone(),
             embedding.clone(),
@@ -63,6 +81,7 @@ impl ServiceRegistry {
             indexing.clone(),
         ).await?);
         let analytics = Arc::new(AnalyticsService::new(configuration.clone()).await?);
+        events.register_handler(analytics.clone());
 
         Ok(Self {
             codebase,
@@ -74,9 +93,17 @@ impl ServiceRegistry {
             configuration,
             job,
             analytics,
+            events,
+            health_check_timeout: DEFAULT_HEALTH_CHECK_TIMEOUT,
         })
     }
 
+    /// Set how long each service's health check may take
+    pub fn with_health_check_timeout(mut self, timeout: Duration) -> Self {
+        self.health_check_timeout = timeout;
+        self
+    }
+
     /// Initialize all services
     pub async fn initialize(&self) -> Result<(), CoreError> {
         self.configuration.initialize().await?;
@@ -106,23 +133,56 @@ impl ServiceRegistry {
     }
 
     /// Get health status of all services
+    ///
+    /// Services are checked concurrently; one that does not answer within
+    /// `health_check_timeout` is reported unhealthy with the message `timeout`.
     pub async fn health_check(&self) -> ServiceHealthStatus {
-        let mut status = ServiceHealthStatus::new();
-        
-        status.add_service("configuration", self.configuration.health_check().await);
-        status.add_service("cache", self.cache.health_check().await);
-        status.add_service("codebase", self.codebase.health_check().await);
-        status.add_service("embedding", self.embedding.health_check().await);
-        status.add_service("plugin", self.plugin.health_check().await);
-        status.add_service("indexing", self.indexing.health_check().await);
-        status.add_service("search", self.search.health_check().await);
-        status.add_service("job", self.job.health_check().await);
-        status.add_service("analytics", self.analytics.health_check().await);
-        
-        status
+        let services: Vec<(&'static str, Arc<dyn Service>)> = vec![
+            ("configuration", self.configuration.clone()),
+            ("cache", self.cache.clone()),
+            ("codebase", self.codebase.clone()),
+            ("embedding", self.embedding.clone()),
+            ("plugin", self.plugin.clone()),
+            ("indexing", self.indexing.clone()),
+            ("search", self.search.clone()),
+            ("job", self.job.clone()),
+            ("analytics", self.analytics.clone()),
+        ];
+
+        check_services(services, self.health_check_timeout).await
     }
 }
 
+/// Run every service's health check concurrently, each bounded by `timeout`
+pub async fn check_services(
+    services: Vec<(&'static str, Arc<dyn Service>)>,
+    timeout: Duration,
+) -> ServiceHealthStatus {
+    let checks: Vec<_> = services
+        .into_iter()
+        .map(|(name, service)| {
+            let check = tokio::spawn(async move {
+                let started = Instant::now();
+                match tokio::time::timeout(timeout, service.health_check()).await {
+                    Ok(health) => health,
+                    Err(_) => ServiceHealth::unhealthy("timeout".to_string())
+                        .with_response_time(started.elapsed().as_millis() as u64),
+                }
+            });
+            (name, check)
+        })
+        .collect();
+
+    let mut status = ServiceHealthStatus::new();
+    for (name, check) in checks {
+        let health = check.await.unwrap_or_else(|e| {
+            ServiceHealth::unhealthy(format!("Health check failed: {}", e))
+        });
+        status.add_service(name, health);
+    }
+    status
+}
+
 /// Health status for all services
 #[derive(Debug, Clone)]
 pub struct ServiceHealthStatus {
@@ -270,4 +330,54 @@ impl Default for ServiceHealthStatus {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestService {
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl Service for TestService {
+        async fn initialize(&self) -> Result<(), CoreError> {
+            Ok(())
+        }
+
+        async fn shutdown(&self) -> Result<(), CoreError> {
+            Ok(())
+        }
+
+        async fn health_check(&self) -> ServiceHealth {
+            tokio::time::sleep(self.delay).await;
+            ServiceHealth::healthy()
+        }
+
+        fn name(&self) -> &'static str {
+            "test"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hung_service_times_out_without_blocking_others() {
+        let services: Vec<(&'static str, Arc<dyn Service>)> = vec![
+            ("hung", Arc::new(TestService { delay: Duration::from_secs(60) })),
+            ("slow", Arc::new(TestService { delay: Duration::from_millis(10) })),
+            ("fast", Arc::new(TestService { delay: Duration::ZERO })),
+        ];
+
+        let started = Instant::now();
+        let status = check_services(services, Duration::from_millis(500)).await;
+
+        // Run concurrently, the checks take about one timeout, far from the
+        // hung service's minute; the bound leaves room for a loaded machine
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!status.is_healthy());
+        assert_eq!(status.services["hung"].status, HealthStatus::Unhealthy);
+        assert_eq!(status.services["hung"].message.as_deref(), Some("timeout"));
+        assert_eq!(status.services["slow"].status, HealthStatus::Healthy);
+        assert_eq!(status.services["fast"].status, HealthStatus::Healthy);
+    }
+}
@@ -22,6 +22,9 @@ use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
+/// Metadata key naming the codebase a `search_all_codebases` result came from
+pub const CODEBASE_ID_KEY: &str = "codebase_id";
+
 /// Service for searching code entities and content
 #[derive(Debug)]
 pub struct SearchService {
@@ -345,7 +348,7 @@ impl SearchService {
         for index in indexes.values() {
             if index.index_type == SearchIndexType::Keyword || 
                index.index_type == SearchIndexType::Hybrid {
-                let index_results = self.search_keyword_index(&index.keyword_index, &search_terms);
+                let index_results = Self::search_keyword_index(&index.keyword_index, &search_terms);
                 results.extend(index_results);
             }
         }
@@ -361,7 +364,7 @@ impl SearchService {
         // Use n-gram index for fuzzy matching
         let indexes = self.search_indexes.read().unwrap();
         for index in indexes.values() {
-            let fuzzy_results = self.search_fuzzy_index(&index.keyword_index, &search_terms);
+            let fuzzy_results = Self::search_fuzzy_index(&index.keyword_index, &search_terms);
             results.extend(fuzzy_results);
         }
         
@@ -385,56 +388,70 @@ impl SearchService {
         // Search through entities
         let indexes = self.search_indexes.read().unwrap();
         for index in indexes.values() {
-            for entity in index.entities.values() {
-                if regex.is_match(&entity.name) || 
-                   regex.is_match(&entity.qualified_name) ||
-                   entity.signature.as_ref().map_or(false, |sig| regex.is_match(sig)) {
-                    
-                    let result = QueryResult::new(
-                        entity.id.clone(),
-                        1.0, // Regex matches get full score
-                        entity.file_path.clone(),
-                    );
-                    results.push(result);
-                }
-            }
+            results.extend(Self::search_regex_index(index, &regex));
         }
         
         Ok(results)
     }
 
-    /// Perform exact search
-    async fn exact_search(&self, query: &Query) -> Result<Vec<QueryResult>, CoreError> {
+    /// Entities of one index whose name, qualified name or signature match `regex`
+    fn search_regex_index(index: &SearchIndex, regex: &regex::Regex) -> Vec<QueryResult> {
         let mut results = Vec::new();
-        let search_text = query.text.to_lowercase();
-        
-        // Search for exact matches
-        let indexes = self.search_indexes.read().unwrap();
-        for index in indexes.values() {
-            for entity in index.entities.values() {
-                let score = if entity.name.to_lowercase() == search_text {
-                    1.0
-                } else if entity.qualified_name.to_lowercase() == search_text {
-                    0.9
-                } else if entity.signature.as_ref()
-                    .map_or(false, |sig| sig.to_lowercase().contains(&search_text)) {
-                    0.8
-                } else {
-                    continue;
-                };
+        for entity in index.entities.values() {
+            if regex.is_match(&entity.name) || 
+               regex.is_match(&entity.qualified_name) ||
+               entity.signature.as_ref().map_or(false, |sig| regex.is_match(sig)) {
                 
                 let result = QueryResult::new(
                     entity.id.clone(),
-                    score,
+                    1.0, // Regex matches get full score
                     entity.file_path.clone(),
                 );
                 results.push(result);
             }
         }
+        results
+    }
+
+    /// Perform exact search
+    async fn exact_search(&self, query: &Query) -> Result<Vec<QueryResult>, CoreError> {
+        let mut results = Vec::new();
+        let search_text = query.text.to_lowercase();
+        
+        // Search for exact matches
+        let indexes = self.search_indexes.read().unwrap();
+        for index in indexes.values() {
+            results.extend(Self::search_exact_index(index, &search_text));
+        }
         
         Ok(results)
     }
 
+    /// Entities of one index named `search_text`, or whose signature contains it
+    fn search_exact_index(index: &SearchIndex, search_text: &str) -> Vec<QueryResult> {
+        let mut results = Vec::new();
+        for entity in index.entities.values() {
+            let score = if entity.name.to_lowercase() == search_text {
+                1.0
+            } else if entity.qualified_name.to_lowercase() == search_text {
+                0.9
+            } else if entity.signature.as_ref()
+                .map_or(false, |sig| sig.to_lowercase().contains(search_text)) {
+                0.8
+            } else {
+                continue;
+            };
+            
+            let result = QueryResult::new(
+                entity.id.clone(),
+                score,
+                entity.file_path.clone(),
+            );
+            results.push(result);
+        }
+        results
+    }
+
     /// Perform structural search (AST-based)
     async fn structural_search(&self, query: &Query) -> Result<Vec<QueryResult>, CoreError> {
         // Structural search would analyze AST patterns
@@ -481,7 +498,6 @@ impl SearchService {
 
     /// Search keyword index
     fn search_keyword_index(
-        &self,
         index: &KeywordIndex,
         terms: &[String],
     ) -> Vec<QueryResult> {
@@ -511,14 +527,13 @@ impl SearchService {
 
     /// Search fuzzy index using n-grams
     fn search_fuzzy_index(
-        &self,
         index: &KeywordIndex,
         terms: &[String],
     ) -> Vec<QueryResult> {
         let mut results = Vec::new();
         
         for term in terms {
-            let ngrams = self.generate_ngrams(term, 3);
+            let ngrams = Self::generate_ngrams(term, 3);
             let mut entity_scores: HashMap<String, f64> = HashMap::new();
             
             for ngram in ngrams {
@@ -543,7 +558,7 @@ impl SearchService {
     }
 
     /// Generate n-grams for fuzzy search
-    fn generate_ngrams(&self, text: &str, n: usize) -> Vec<String> {
+    fn generate_ngrams(text: &str, n: usize) -> Vec<String> {
         if text.len() < n {
             return vec![text.to_string()];
         }
@@ -823,7 +838,7 @@ impl SearchService {
                     .push(entity.id.clone());
                 
                 // Generate n-grams for fuzzy search
-                let ngrams = self.generate_ngrams(&word, 3);
+                let ngrams = Self::generate_ngrams(&word, 3);
                 for ngram in ngrams {
                     keyword_index.ngram_index
                         .entry(ngram)
@@ -854,6 +869,62 @@ impl SearchService {
         Ok(())
     }
 
+    /// Search every codebase's index at once and merge the results by score
+    ///
+    /// Each codebase is searched on its own thread and keeps its best
+    /// `per_codebase_limit` results, each tagged with its codebase under
+    /// [`CODEBASE_ID_KEY`] in `metadata`. The merged results are ranked by
+    /// score, ties going to the codebase id and then the entity id, and cut to
+    /// `total_limit`. Semantic and structural queries have no per-codebase
+    /// index to search and return nothing, as they do in `search`.
+    pub async fn search_all_codebases(
+        &self,
+        query: &Query,
+        per_codebase_limit: usize,
+        total_limit: usize,
+    ) -> Result<Vec<QueryResult>, CoreError> {
+        query.validate()?;
+        let regex = match query.query_type {
+            QueryType::Regex => Some(regex::Regex::new(&query.text).map_err(|e| {
+                CoreError::ValidationError(format!("Invalid regex pattern: {}", e))
+            })?),
+            _ => None,
+        };
+        let search = CodebaseSearch {
+            query_type: query.query_type.clone(),
+            terms: self.extract_search_terms(&query.text),
+            search_text: query.text.to_lowercase(),
+            regex,
+            filters: query.filters.clone(),
+            limit: per_codebase_limit,
+        };
+
+        // Searching is CPU-bound, so each codebase runs on the blocking pool
+        // rather than holding up the async workers
+        let index_ids: Vec<String> = self.search_indexes.read().unwrap().keys().cloned().collect();
+        let searches: Vec<_> = index_ids
+            .into_iter()
+            .map(|index_id| {
+                let indexes = Arc::clone(&self.search_indexes);
+                let search = search.clone();
+                tokio::task::spawn_blocking(move || {
+                    let indexes = indexes.read().unwrap();
+                    indexes.get(&index_id).map(|index| search.run(index)).unwrap_or_default()
+                })
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        for search in searches {
+            results.extend(search.await.map_err(|e| {
+                CoreError::Internal(format!("Codebase search failed: {}", e))
+            })?);
+        }
+        rank_merged_results(&mut results);
+        results.truncate(total_limit);
+        Ok(results)
+    }
+
     /// Remove search index
     pub async fn remove_search_index(&self, codebase_id: &str) -> Result<(), CoreError> {
         let index_id = format!("index_{}", codebase_id);
@@ -865,6 +936,110 @@ impl SearchService {
     }
 }
 
+/// One query as run against each codebase by `search_all_codebases`
+#[derive(Debug, Clone)]
+struct CodebaseSearch {
+    query_type: QueryType,
+    terms: Vec<String>,
+    search_text: String,
+    regex: Option<regex::Regex>,
+    filters: QueryFilters,
+    limit: usize,
+}
+
+impl CodebaseSearch {
+    /// The codebase's best `limit` matches that pass the filters, tagged
+    /// with the codebase under [`CODEBASE_ID_KEY`]
+    ///
+    /// Filtering comes before the cut, so hits the filters reject never use
+    /// up the codebase's share of the results.
+    fn run(&self, index: &SearchIndex) -> Vec<QueryResult> {
+        let mut results = match self.query_type {
+            QueryType::Keyword | QueryType::Hybrid => {
+                SearchService::search_keyword_index(&index.keyword_index, &self.terms)
+            }
+            QueryType::Fuzzy => SearchService::search_fuzzy_index(&index.keyword_index, &self.terms),
+            QueryType::Exact => SearchService::search_exact_index(index, &self.search_text),
+            QueryType::Regex => self.regex.as_ref()
+                .map(|regex| SearchService::search_regex_index(index, regex))
+                .unwrap_or_default(),
+            QueryType::Semantic | QueryType::Structural => Vec::new(),
+        };
+        results.retain_mut(|result| {
+            let Some(entity) = index.entities.get(&result.entity_id) else {
+                return self.filters == QueryFilters::default();
+            };
+            if !matches_filters(entity, &index.codebase_id, &self.filters) {
+                return false;
+            }
+            result.file_path = entity.file_path.clone();
+            true
+        });
+        for result in &mut results {
+            result.metadata.insert(CODEBASE_ID_KEY.to_string(), index.codebase_id.clone());
+        }
+        rank_merged_results(&mut results);
+        results.truncate(self.limit);
+        results
+    }
+}
+
+/// Whether `entity`, from codebase `codebase_id`, passes every filter set in
+/// `filters`; file paths are matched as globs where `*` spans any characters
+fn matches_filters(entity: &CodeEntity, codebase_id: &str, filters: &QueryFilters) -> bool {
+    let any_named = |names: &Option<Vec<String>>, value: &str| {
+        names.as_ref().map_or(true, |names| names.iter().any(|name| name.eq_ignore_ascii_case(value)))
+    };
+    any_named(&filters.languages, &entity.language)
+        && any_named(&filters.entity_types, &format!("{:?}", entity.entity_type))
+        && any_named(&filters.visibility, &format!("{:?}", entity.visibility))
+        && filters.codebase_ids.as_ref().map_or(true, |ids| ids.iter().any(|id| id == codebase_id))
+        && filters.file_paths.as_ref().map_or(true, |patterns| {
+            patterns.iter().any(|pattern| glob_matches(pattern, &entity.file_path))
+        })
+        && filters.date_range.map_or(true, |(from, to)| {
+            entity.created_at >= from && entity.created_at <= to
+        })
+}
+
+/// Whether `text` matches `pattern`, where `*` matches any run of characters
+/// and `?` any one
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let (pattern, text): (Vec<char>, Vec<char>) = (pattern.chars().collect(), text.chars().collect());
+    // Positions in `text` the pattern so far can end at
+    let mut ends = vec![false; text.len() + 1];
+    ends[0] = true;
+    for p in pattern {
+        let mut next = vec![false; text.len() + 1];
+        for i in 0..=text.len() {
+            if !ends[i] {
+                continue;
+            }
+            match p {
+                '*' => next[i..].iter_mut().for_each(|end| *end = true),
+                '?' if i < text.len() => next[i + 1] = true,
+                c if i < text.len() && text[i] == c => next[i + 1] = true,
+                _ => {}
+            }
+        }
+        ends = next;
+    }
+    ends[text.len()]
+}
+
+/// Best score first; ties go to the codebase id, then the entity id, so
+/// merged results come back in the same order on every run
+fn rank_merged_results(results: &mut [QueryResult]) {
+    let codebase = |result: &QueryResult| result.metadata.get(CODEBASE_ID_KEY).cloned();
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| codebase(a).cmp(&codebase(b)))
+            .then_with(|| a.entity_id.cmp(&b.entity_id))
+    });
+}
+
 #[async_trait]
 impl Service for SearchService {
     async fn initialize(&self) -> Result<(), CoreError> {
@@ -946,6 +1121,55 @@ mod tests {
         assert_eq!(metrics.total_queries, 0);
     }
 
+    #[tokio::test]
+    async fn test_search_all_codebases_merges_by_score() {
+        let service = create_test_service().await;
+        for (codebase_id, names) in [
+            ("web", vec!["config", "load.config.web"]),
+            ("api", vec!["load.config", "config", "load"]),
+        ] {
+            let entities = names
+                .into_iter()
+                .map(|name| CodeEntity::new(
+                    Uuid::new_v4(),
+                    EntityType::Function,
+                    name.to_string(),
+                    name.to_string(),
+                    format!("{}/src/{}.ts", codebase_id, name),
+                    1,
+                    1,
+                    "typescript".to_string(),
+                ))
+                .collect();
+            service.update_search_index(codebase_id, entities, Vec::new()).await.unwrap();
+        }
+        let query = Query::keyword("load config".to_string(), "user".to_string());
+        let codebases = |results: &[QueryResult]| -> Vec<String> {
+            results.iter().map(|result| result.metadata[CODEBASE_ID_KEY].clone()).collect()
+        };
+
+        let results = service.search_all_codebases(&query, 2, 3).await.unwrap();
+        assert_eq!(
+            results.iter().map(|result| result.score).collect::<Vec<_>>(),
+            vec![1.0, 1.0, 0.5]
+        );
+        assert_eq!(codebases(&results), vec!["api", "web", "api"]);
+        assert_eq!(results[0].file_path, "api/src/load.config.ts");
+        assert_eq!(results[1].file_path, "web/src/load.config.web.ts");
+
+        // Each codebase contributes at most its own limit
+        let results = service.search_all_codebases(&query, 1, 10).await.unwrap();
+        assert_eq!(codebases(&results), vec!["api", "web"]);
+
+        // Filtered-out hits do not use up a codebase's share
+        let mut filtered = query.clone();
+        filtered.filters.file_paths = Some(vec!["*/src/config.ts".to_string()]);
+        let results = service.search_all_codebases(&filtered, 1, 10).await.unwrap();
+        assert_eq!(codebases(&results), vec!["api", "web"]);
+        assert!(results.iter().all(|result| result.file_path.ends_with("/src/config.ts")));
+        assert!(results.iter().all(|result| result.score == 0.5));
+    }
+
     #[tokio::test]
     async fn test_generate_cache_key() {
         let service = create_test_service().await;
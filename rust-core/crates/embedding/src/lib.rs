@@ -5,20 +5,187 @@
 // pub mod cache;
 
 use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Dimension of the vectors produced by the default backend
+pub const DEFAULT_DIMENSION: usize = 384;
+
+/// Batching configuration
+#[derive(Debug, Clone)]
+pub struct EmbeddingConfig {
+    /// Texts sent to the model per call
+    pub batch_size: usize,
+    /// Batches being embedded at once; bounds the memory held by pending work
+    pub max_inflight_batches: usize,
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 32,
+            max_inflight_batches: 4,
+        }
+    }
+}
+
+/// A model that embeds several texts per call
+///
+/// Calls are made from blocking threads, so implementations may run
+/// CPU-bound inference directly.
+pub trait EmbeddingBackend: Send + Sync {
+    fn dimension(&self) -> usize;
+
+    /// Embed each text, returning one vector per input in input order
+    fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// Stand-in for a real model: a deterministic unit vector per text
+///
+/// The vector depends only on the text's UTF-8 bytes, the seed and the
+/// dimension, so every crate embedding through this type agrees on the
+/// output for the same input. Changing the seed yields an unrelated set of
+/// vectors, which lets tests check that results do not hinge on one draw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MockEmbedding {
+    dimension: usize,
+    seed: u64,
+}
+
+impl MockEmbedding {
+    pub fn new(dimension: usize) -> Self {
+        Self { dimension, seed: 0 }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Embed one text
+    pub fn embed(&self, text: &str) -> Vec<f32> {
+        // FNV-1a over the bytes selects the stream; splitmix64 expands it
+        let mut seed = self.seed;
+        let mut state = text.bytes().fold(
+            0xcbf2_9ce4_8422_2325 ^ splitmix64(&mut seed),
+            |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3),
+        );
+        let mut embedding: Vec<f32> = (0..self.dimension)
+            .map(|_| (splitmix64(&mut state) >> 40) as f32 / (1u64 << 23) as f32 - 1.0)
+            .collect();
+
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for value in &mut embedding {
+                *value /= norm;
+            }
+        }
+        embedding
+    }
+}
+
+impl Default for MockEmbedding {
+    fn default() -> Self {
+        Self::new(DEFAULT_DIMENSION)
+    }
+}
+
+impl EmbeddingBackend for MockEmbedding {
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| self.embed(text)).collect())
+    }
+}
+
+/// Advance `state` and return the next output of the splitmix64 generator
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
 
 /// Main embedding generator
 pub struct EmbeddingGenerator {
-    // Implementation details
+    backend: Arc<dyn EmbeddingBackend>,
+    config: EmbeddingConfig,
 }
 
 impl EmbeddingGenerator {
     pub fn new() -> Self {
-        Self {}
+        Self::with_backend(
+            Arc::new(MockEmbedding::default()),
+            EmbeddingConfig::default(),
+        )
+    }
+
+    pub fn with_backend(backend: Arc<dyn EmbeddingBackend>, config: EmbeddingConfig) -> Self {
+        Self { backend, config }
     }
 
-    pub async fn generate_embedding(&self, _text: &str) -> Result<Vec<f32>> {
-        // TODO: Implement embedding generation
-        Ok(vec![0.0; 384]) // Mock embedding
+    pub fn config(&self) -> &EmbeddingConfig {
+        &self.config
+    }
+
+    pub async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        let mut embeddings = self
+            .generate_embeddings_batch(vec![text.to_string()])
+            .await?;
+        embeddings
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("Embedding backend returned no vector"))
+    }
+
+    /// Embed many texts, `batch_size` per backend call, returning vectors in input order
+    ///
+    /// At most `max_inflight_batches` batches run concurrently; the next batch
+    /// is only dispatched once one finishes.
+    pub async fn generate_embeddings_batch(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let batch_size = self.config.batch_size.max(1);
+        let inflight = Arc::new(Semaphore::new(self.config.max_inflight_batches.max(1)));
+        let batch_count = texts.len().div_ceil(batch_size);
+
+        let mut tasks = JoinSet::new();
+        let mut texts = texts.into_iter();
+        for index in 0..batch_count {
+            let batch: Vec<String> = texts.by_ref().take(batch_size).collect();
+            let permit = Arc::clone(&inflight).acquire_owned().await?;
+            let backend = Arc::clone(&self.backend);
+            tasks.spawn_blocking(move || {
+                let _permit = permit;
+                let embeddings = backend.embed_batch(&batch);
+                (index, batch.len(), embeddings)
+            });
+        }
+
+        let mut batches: Vec<Option<Vec<Vec<f32>>>> = vec![None; batch_count];
+        while let Some(joined) = tasks.join_next().await {
+            let (index, expected, embeddings) = joined?;
+            let embeddings = embeddings?;
+            if embeddings.len() != expected {
+                anyhow::bail!(
+                    "Embedding backend returned {} vectors for {} texts",
+                    embeddings.len(),
+                    expected
+                );
+            }
+            batches[index] = Some(embeddings);
+        }
+
+        Ok(batches.into_iter().flatten().flatten().collect())
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.backend.dimension()
     }
 }
 
@@ -31,6 +198,7 @@ impl Default for EmbeddingGenerator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[tokio::test]
     async fn test_embedding_generator() {
@@ -38,4 +206,97 @@ mod tests {
         let embedding = generator.generate_embedding("test").await.unwrap();
         assert_eq!(embedding.len(), 384);
     }
+
+    #[test]
+    fn test_mock_embedding_is_seeded_and_normalized() {
+        let mock = MockEmbedding::new(16);
+        let embedding = mock.embed("fn parse() {}");
+
+        assert_eq!(embedding, mock.embed("fn parse() {}"));
+        assert_ne!(embedding, mock.embed("fn parse() { }"));
+        assert_ne!(embedding, mock.with_seed(7).embed("fn parse() {}"));
+        assert_eq!(
+            mock.with_seed(7).embed("fn parse() {}"),
+            MockEmbedding::new(16).with_seed(7).embed("fn parse() {}")
+        );
+        let norm: f32 = embedding.iter().map(|x| x * x).sum();
+        assert!((norm - 1.0).abs() < 1e-5);
+        assert!(embedding.iter().all(|x| (-1.0..=1.0).contains(x)));
+    }
+
+    #[tokio::test]
+    async fn test_batch_preserves_input_order() {
+        let generator = EmbeddingGenerator::with_backend(
+            Arc::new(MockEmbedding::new(8)),
+            EmbeddingConfig {
+                batch_size: 3,
+                max_inflight_batches: 2,
+            },
+        );
+        let texts: Vec<String> = (0..10).map(|i| format!("fn entity_{}() {{}}", i)).collect();
+
+        let batched = generator
+            .generate_embeddings_batch(texts.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(batched.len(), texts.len());
+        for (text, embedding) in texts.iter().zip(&batched) {
+            assert_eq!(
+                embedding,
+                &generator.generate_embedding(text).await.unwrap()
+            );
+        }
+        assert!(generator
+            .generate_embeddings_batch(Vec::new())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    /// Records the largest number of concurrent `embed_batch` calls
+    struct CountingBackend {
+        active: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    impl EmbeddingBackend for CountingBackend {
+        fn dimension(&self) -> usize {
+            1
+        }
+
+        fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(active, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            Ok(texts.iter().map(|_| vec![0.0]).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_inflight_batches_are_bounded() {
+        let backend = Arc::new(CountingBackend {
+            active: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        });
+        let generator = EmbeddingGenerator::with_backend(
+            backend.clone(),
+            EmbeddingConfig {
+                batch_size: 2,
+                max_inflight_batches: 2,
+            },
+        );
+
+        let texts = (0..20).map(|i| i.to_string()).collect();
+        assert_eq!(
+            generator
+                .generate_embeddings_batch(texts)
+                .await
+                .unwrap()
+                .len(),
+            20
+        );
+        assert!(backend.peak.load(Ordering::SeqCst) <= 2);
+    }
 }
@@ -1,11 +1,19 @@
 //! FFI bindings for Code Intelligence MCP Server
 
+use code_intelligence_core::config::{DatabaseLocation, DatabaseMode, DatabaseUrl};
+use code_intelligence_core::errors::{CoreError, DatabasePathProblem, Result as CoreResult};
+use code_intelligence_core::{QueryType, SearchQuery};
+use code_intelligence_embedding::MockEmbedding;
+use code_intelligence_parser::CodeParser;
+use code_intelligence_search::{SearchEngine, SearchEngineConfig};
+use code_intelligence_storage::{StorageConfig, StorageManager, StoredEntity, Synchronous};
 use napi::{Error, Result};
 use napi_derive::napi;
-use rusqlite::{params, Connection};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
+use std::sync::{Arc, OnceLock};
 use walkdir::WalkDir;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -29,39 +37,239 @@ pub struct SearchResult {
     pub score: f64,
 }
 
+impl From<CodeEntity> for StoredEntity {
+    fn from(entity: CodeEntity) -> Self {
+        StoredEntity {
+            id: entity.id,
+            name: entity.name,
+            file_path: entity.file_path,
+            entity_type: entity.entity_type,
+            start_line: entity.start_line.max(0) as u32,
+            end_line: entity.end_line.max(0) as u32,
+            content: entity.content,
+            metadata: Default::default(),
+        }
+    }
+}
+
+/// Database URL naming a private in-memory database
+pub const MEMORY_DATABASE_URL: &str = "sqlite::memory:";
+
+/// Options for `init_engine`
+#[derive(Debug, Clone, Default)]
+#[napi(object)]
+pub struct EngineConfig {
+    /// `sqlite:///path/to/index.db`, a bare path, or `sqlite::memory:`,
+    /// optionally ending in `?mode=ro`, `rw` or `rwc`; defaults to
+    /// `DATABASE_URL`
+    pub database_url: Option<String>,
+    /// Pooled connections to a database file (default 4)
+    pub pool_size: Option<u32>,
+    /// Bytes per page of a newly created database file (default 4096)
+    pub page_size: Option<u32>,
+    /// Page cache per connection: pages when positive, KiB when negative
+    /// (default -65536, i.e. 64 MiB)
+    pub cache_size: Option<i64>,
+    /// Bytes of the file memory-mapped for reads (default 256 MiB); 0 disables it
+    pub mmap_size: Option<i64>,
+    /// `off`, `normal` (default), `full` or `extra`; with `normal` a crash can
+    /// lose the writes since the last checkpoint, but not corrupt the index
+    pub synchronous: Option<String>,
+    /// Open an existing database file for searching only; indexing fails
+    pub read_only: Option<bool>,
+}
+
+/// The process-wide engine every exported function works through
+struct Engine {
+    database_url: String,
+    storage: Arc<StorageManager>,
+}
+
+static ENGINE: OnceLock<Engine> = OnceLock::new();
+
 /// Initialize the Code Intelligence engine
+///
+/// The engine is created once per process; later calls with no database URL,
+/// or the one already in use, do nothing, while a different URL is an error.
+/// Functions called before `init_engine` initialize it from `DATABASE_URL`.
 #[napi]
-pub fn init_engine() -> Result<()> {
-    // Initialize SQLite database
-    let db_path = std::env::var("DATABASE_URL")
+pub fn init_engine(config: Option<EngineConfig>) -> Result<()> {
+    let config = config.unwrap_or_default();
+    match config.database_url.clone() {
+        Some(database_url) => install_engine(database_url, &config).map(|_| ()),
+        None => engine().map(|_| ()),
+    }
+}
+
+/// The engine, initialized from `DATABASE_URL` if `init_engine` has not run
+fn engine() -> Result<&'static Engine> {
+    match ENGINE.get() {
+        Some(engine) => Ok(engine),
+        None => install_engine(env_database_url(), &EngineConfig::default()),
+    }
+}
+
+fn install_engine(database_url: String, config: &EngineConfig) -> Result<&'static Engine> {
+    if ENGINE.get().is_none() {
+        let storage = open_storage(&database_url, config)
+            .map_err(|e| Error::from_reason(format!("Failed to open database: {}", e)))?;
+        // A concurrent initialization may have won; it is checked below
+        let _ = ENGINE.set(Engine {
+            database_url: database_url.clone(),
+            storage: Arc::new(storage),
+        });
+    }
+
+    let engine = ENGINE.get().expect("engine is set above");
+    if engine.database_url != database_url {
+        return Err(Error::from_reason(format!(
+            "Engine is already initialized with {}",
+            engine.database_url
+        )));
+    }
+    Ok(engine)
+}
+
+fn env_database_url() -> String {
+    std::env::var("DATABASE_URL")
         .unwrap_or_else(|_| "sqlite:///tmp/code-intelligence.db".to_string())
-        .replace("sqlite://", "");
-
-    let conn = Connection::open(&db_path)
-        .map_err(|e| Error::from_reason(format!("Failed to open database: {}", e)))?;
-
-    // Create tables
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS code_entities (
-            id TEXT PRIMARY KEY,
-            name TEXT NOT NULL,
-            file_path TEXT NOT NULL,
-            entity_type TEXT NOT NULL,
-            start_line INTEGER,
-            end_line INTEGER,
-            content TEXT,
-            indexed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )",
-        [],
-    )
-    .map_err(|e| Error::from_reason(format!("Failed to create table: {}", e)))?;
+}
+
+fn open_storage(database_url: &str, config: &EngineConfig) -> anyhow::Result<StorageManager> {
+    let url = DatabaseUrl::parse(database_url)?;
+    let db_path = match url.location {
+        DatabaseLocation::Memory => return StorageManager::open_in_memory(),
+        DatabaseLocation::File(path) => path,
+    };
+
+    let mut storage_config = StorageConfig::default();
+    if let Some(pool_size) = config.pool_size {
+        storage_config.pool_size = pool_size.max(1) as usize;
+    }
+    if let Some(page_size) = config.page_size {
+        storage_config.page_size = page_size;
+    }
+    if let Some(cache_size) = config.cache_size {
+        storage_config.cache_size = cache_size;
+    }
+    if let Some(mmap_size) = config.mmap_size {
+        storage_config.mmap_size = mmap_size.max(0) as u64;
+    }
+    if let Some(synchronous) = &config.synchronous {
+        storage_config.synchronous = Synchronous::parse(synchronous)
+            .ok_or_else(|| anyhow::anyhow!("Unknown synchronous mode: {}", synchronous))?;
+    }
+    let mode = if config.read_only.unwrap_or(false) {
+        DatabaseMode::ReadOnly
+    } else {
+        url.mode
+    };
+    storage_config.read_only = mode == DatabaseMode::ReadOnly;
+
+    check_database_path(&db_path, mode)?;
+    StorageManager::open_with_config(db_path, storage_config)
+}
+
+/// Make sure a database file can be opened in `mode` before SQLite tries
+///
+/// A missing parent directory is created. For writing, both the file and its
+/// directory must be writable, since the WAL and shared-memory files are
+/// created beside it; read-only opens need only an existing, readable file,
+/// and `rw` opens an existing one.
+fn check_database_path(path: &Path, mode: DatabaseMode) -> CoreResult<()> {
+    let problem = |problem| CoreError::DatabasePath {
+        path: path.to_path_buf(),
+        problem,
+    };
+    // Access errors are always reported as such; others as `otherwise`, if given
+    let io_error = |error: std::io::Error, otherwise: Option<DatabasePathProblem>| {
+        if error.kind() == std::io::ErrorKind::PermissionDenied {
+            return problem(DatabasePathProblem::PermissionDenied);
+        }
+        otherwise.map_or(CoreError::Io(error), problem)
+    };
 
+    if path.is_dir() {
+        return Err(problem(DatabasePathProblem::IsDirectory));
+    }
+    if mode == DatabaseMode::ReadOnly {
+        return fs::File::open(path)
+            .map(|_| ())
+            .map_err(|e| io_error(e, Some(DatabasePathProblem::NotFound)));
+    }
+
+    if mode == DatabaseMode::ReadWrite && !path.exists() {
+        return Err(problem(DatabasePathProblem::NotFound));
+    }
+
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(directory)
+        .map_err(|e| io_error(e, Some(DatabasePathProblem::DirectoryMissing)))?;
+
+    let probe = directory.join(format!(".codesight-probe-{}", std::process::id()));
+    fs::File::create(&probe).map_err(|e| io_error(e, None))?;
+    let _ = fs::remove_file(&probe);
+    if path.exists() {
+        fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .map_err(|e| io_error(e, None))?;
+    }
     Ok(())
 }
 
+/// Storage of the shared engine
+fn storage() -> Result<Arc<StorageManager>> {
+    engine().map(|engine| Arc::clone(&engine.storage))
+}
+
 /// Parse a file and extract entities
+///
+/// Languages with a Tree-sitter grammar go through `CodeParser`; any other
+/// file, or one its grammar cannot parse, falls back to line-based patterns.
 #[napi]
 pub fn parse_file(file_path: String, content: String) -> Result<Vec<CodeEntity>> {
+    if let Some(entities) = parse_with_tree_sitter(&file_path, &content) {
+        return Ok(entities);
+    }
+
+    Ok(extract_with_patterns(&file_path, &content))
+}
+
+/// Shared parser; grammars are loaded once and reused across calls
+fn code_parser() -> &'static CodeParser {
+    static PARSER: OnceLock<CodeParser> = OnceLock::new();
+    PARSER.get_or_init(CodeParser::new)
+}
+
+fn parse_with_tree_sitter(file_path: &str, content: &str) -> Option<Vec<CodeEntity>> {
+    let result = code_parser()
+        .parse_file(Path::new(file_path), content)
+        .ok()?;
+
+    Some(
+        result
+            .entities
+            .into_iter()
+            .map(|entity| CodeEntity {
+                id: format!("{}:{}:{}", file_path, entity.start_line, entity.name),
+                name: entity.name,
+                file_path: file_path.to_string(),
+                entity_type: format!("{:?}", entity.entity_type).to_lowercase(),
+                start_line: entity.start_line as i32,
+                end_line: entity.end_line as i32,
+                content: entity.content,
+            })
+            .collect(),
+    )
+}
+
+/// Heuristic extraction of JavaScript-style functions, classes and variables
+fn extract_with_patterns(file_path: &str, content: &str) -> Vec<CodeEntity> {
+    let file_path = file_path.to_string();
     let mut entities = Vec::new();
 
     // Simple regex-based extraction for now (functions and classes)
@@ -119,98 +327,96 @@ pub fn parse_file(file_path: String, content: String) -> Result<Vec<CodeEntity>>
         }
     }
 
-    Ok(entities)
+    entities
 }
 
 /// Search for code entities
+///
+/// Delegates to the keyword backend of `SearchEngine`, so results rank the
+/// same as searches made through the search crate directly.
 #[napi]
 pub fn search_code(query: String, _codebase_path: Option<String>) -> Result<Vec<SearchResult>> {
-    let db_path = std::env::var("DATABASE_URL")
-        .unwrap_or_else(|_| "sqlite:///tmp/code-intelligence.db".to_string())
-        .replace("sqlite://", "");
-
-    let conn = Connection::open(&db_path)
-        .map_err(|e| Error::from_reason(format!("Failed to open database: {}", e)))?;
-
-    // Simple keyword search
-    let search_pattern = format!("%{}%", query);
-    let mut stmt = conn
-        .prepare(
-            "SELECT file_path, start_line, content FROM code_entities
-         WHERE name LIKE ?1 OR content LIKE ?1
-         ORDER BY
-            CASE WHEN name = ?2 THEN 0
-                 WHEN name LIKE ?3 THEN 1
-                 ELSE 2 END,
-            start_line
-         LIMIT 20",
-        )
-        .map_err(|e| Error::from_reason(format!("Failed to prepare query: {}", e)))?;
-
-    let exact_match = query.clone();
-    let starts_with = format!("{}%", query);
-
-    let results = stmt
-        .query_map(
-            params![&search_pattern, &exact_match, &starts_with],
-            |row| {
-                Ok(SearchResult {
-                    file: row.get(0)?,
-                    line: row.get(1)?,
-                    content: row.get(2)?,
-                    score: calculate_score(&query, &row.get::<_, String>(2)?),
-                })
-            },
-        )
+    let engine = SearchEngine::with_storage(storage()?, SearchEngineConfig::default());
+
+    let results = engine
+        .keyword_search(&SearchQuery {
+            text: query,
+            query_type: QueryType::Keyword,
+            limit: 20,
+            filters: Default::default(),
+        })
         .map_err(|e| Error::from_reason(format!("Query failed: {}", e)))?;
 
-    let mut search_results = Vec::new();
-    for r in results.flatten() {
-        search_results.push(r);
-    }
+    Ok(results
+        .into_iter()
+        .map(|result| SearchResult {
+            file: result.entity.file_path,
+            line: result.entity.start_line as i32,
+            content: result.entity.content,
+            score: f64::from(result.score),
+        })
+        .collect())
+}
 
-    Ok(search_results)
+/// Database size in bytes before and after `compact_index`
+#[derive(Debug, Serialize, Deserialize)]
+#[napi(object)]
+pub struct CompactResult {
+    pub size_before: f64,
+    pub size_after: f64,
+    /// Free pages were released with `PRAGMA incremental_vacuum` instead of `VACUUM`
+    pub incremental: bool,
 }
 
-/// Generate embeddings for text (placeholder for now)
+/// Reclaim space left by deleted entities and refresh query-planner statistics
+///
+/// Re-indexing leaves free pages behind; run this periodically as maintenance.
+/// A full `VACUUM` holds an exclusive lock while it rewrites the database.
 #[napi]
-pub fn generate_embedding(text: String) -> Result<Vec<f32>> {
-    // Simple hash-based mock embedding
-    let hash = text.chars().fold(0u32, |acc, c| acc.wrapping_add(c as u32));
-    let mut embedding = vec![0.0; 384];
-    for (i, val) in embedding.iter_mut().enumerate().take(384) {
-        *val = ((hash.wrapping_mul(i as u32 + 1) % 1000) as f32) / 1000.0;
-    }
-    Ok(embedding)
+pub fn compact_index() -> Result<CompactResult> {
+    let result = storage()?
+        .compact()
+        .map_err(|e| Error::from_reason(format!("Failed to compact index: {}", e)))?;
+
+    Ok(CompactResult {
+        size_before: result.size_before as f64,
+        size_after: result.size_after as f64,
+        incremental: result.incremental,
+    })
+}
+
+/// Generate a mock embedding for text
+///
+/// Vectors come from the embedding crate's `MockEmbedding`, so the same text
+/// and `seed` (default 0) embed identically here and in the MCP server.
+#[napi]
+pub fn generate_embedding(text: String, seed: Option<u32>) -> Result<Vec<f32>> {
+    Ok(MockEmbedding::default()
+        .with_seed(u64::from(seed.unwrap_or(0)))
+        .embed(&text))
 }
 
 /// Index a codebase
+///
+/// Files are parsed on a dedicated pool of `max_index_threads` threads
+/// (default: all cores but one), leaving headroom for the Node event loop.
 #[napi]
-pub fn index_codebase(path: String) -> Result<String> {
+pub fn index_codebase(path: String, max_index_threads: Option<u32>) -> Result<String> {
     let codebase_path = Path::new(&path);
     if !codebase_path.exists() {
         return Err(Error::from_reason(format!("Path does not exist: {}", path)));
     }
 
-    // Initialize database
-    init_engine()?;
-
-    let db_path = std::env::var("DATABASE_URL")
-        .unwrap_or_else(|_| "sqlite:///tmp/code-intelligence.db".to_string())
-        .replace("sqlite://", "");
-
-    let conn = Connection::open(&db_path)
-        .map_err(|e| Error::from_reason(format!("Failed to open database: {}", e)))?;
+    let storage = storage()?;
 
     // Clear existing entries for this codebase
-    conn.execute(
-        "DELETE FROM code_entities WHERE file_path LIKE ?1",
-        params![format!("{}%", path)],
-    )
-    .map_err(|e| Error::from_reason(format!("Failed to clear old entries: {}", e)))?;
+    storage
+        .delete_by_path_prefix(&path)
+        .map_err(|e| Error::from_reason(format!("Failed to clear old entries: {}", e)))?;
 
-    let mut indexed_count = 0;
-    let extensions = ["js", "ts", "jsx", "tsx", "mjs", "cjs"];
+    let mut extensions = CodeParser::all_supported_extensions();
+    extensions.push("cjs");
+    let mut files = Vec::new();
 
     // Walk through directory
     for entry in WalkDir::new(codebase_path)
@@ -239,35 +445,55 @@ pub fn index_codebase(path: String) -> Result<String> {
             continue;
         }
 
-        // Read and parse file
-        if let Ok(content) = fs::read_to_string(path) {
-            let file_path = path.to_str().unwrap_or("").to_string();
-
-            if let Ok(entities) = parse_file(file_path.clone(), content) {
-                // Insert entities into database
-                for entity in entities {
-                    conn.execute(
-                        "INSERT OR REPLACE INTO code_entities (id, name, file_path, entity_type, start_line, end_line, content)
-                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                        params![
-                            entity.id,
-                            entity.name,
-                            entity.file_path,
-                            entity.entity_type,
-                            entity.start_line,
-                            entity.end_line,
-                            entity.content
-                        ],
-                    ).ok(); // Ignore individual insert errors
-                }
-                indexed_count += 1;
-            }
+        files.push(path.to_str().unwrap_or("").to_string());
+    }
+
+    // Read and parse files in parallel; entities are written from this thread
+    let pool = index_thread_pool(max_index_threads)?;
+    let parsed: Vec<Vec<CodeEntity>> = pool.install(|| {
+        files
+            .par_iter()
+            .filter_map(|file_path| {
+                let content = fs::read_to_string(file_path).ok()?;
+                parse_file(file_path.clone(), content).ok()
+            })
+            .collect()
+    });
+
+    let mut indexed_count = 0;
+    for entities in parsed {
+        // Each file's entities are written in one transaction
+        let rows: Vec<StoredEntity> = entities.into_iter().map(StoredEntity::from).collect();
+        if let Err(e) = storage.insert_entities(&rows) {
+            tracing::warn!("Failed to store entities: {}", e);
         }
+        indexed_count += 1;
+    }
+    if let Err(e) = storage.checkpoint() {
+        tracing::warn!("Failed to checkpoint the database: {}", e);
     }
 
     Ok(format!("Indexed {} files in {}", indexed_count, path))
 }
 
+/// Thread count used by `index_codebase` when no cap is given
+fn default_index_threads() -> usize {
+    num_cpus::get().saturating_sub(1).max(1)
+}
+
+/// Build the pool that bounds indexing parallelism
+fn index_thread_pool(max_index_threads: Option<u32>) -> Result<rayon::ThreadPool> {
+    let threads = max_index_threads
+        .map(|threads| threads.max(1) as usize)
+        .unwrap_or_else(default_index_threads);
+
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|index| format!("code-index-{}", index))
+        .build()
+        .map_err(|e| Error::from_reason(format!("Failed to create indexing thread pool: {}", e)))
+}
+
 // Helper functions
 fn extract_function_name(line: &str) -> Option<String> {
     let patterns = vec![
@@ -327,35 +553,158 @@ fn extract_variable_name(line: &str) -> Option<String> {
     None
 }
 
-fn calculate_score(query: &str, content: &str) -> f64 {
-    let query_lower = query.to_lowercase();
-    let content_lower = content.to_lowercase();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Point the shared engine at an in-memory database
+    ///
+    /// Every test touching the engine calls this first, so whichever runs
+    /// first initializes it the same way.
+    fn memory_engine() {
+        init_engine(Some(EngineConfig {
+            database_url: Some(MEMORY_DATABASE_URL.to_string()),
+            ..Default::default()
+        }))
+        .unwrap();
+    }
+
+    #[test]
+    fn test_init_engine() {
+        memory_engine();
+        assert!(init_engine(None).is_ok());
+
+        let error = init_engine(Some(EngineConfig {
+            database_url: Some("sqlite:///tmp/elsewhere.db".to_string()),
+            ..Default::default()
+        }))
+        .unwrap_err();
+        assert!(error
+            .reason
+            .contains("already initialized with sqlite::memory:"));
+    }
 
-    if content_lower.contains(&query_lower) {
-        // Exact match gets higher score
-        if content_lower == query_lower {
-            return 1.0;
+    #[test]
+    fn test_open_storage_applies_pragmas() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("index.db");
+        let database_url = format!("sqlite://{}", db_path.display());
+        let storage = open_storage(
+            &database_url,
+            &EngineConfig {
+                cache_size: Some(-1024),
+                synchronous: Some("full".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let conn = storage.connection().unwrap();
+        let cache_size: i64 = conn
+            .query_row("PRAGMA cache_size", [], |row| row.get(0))
+            .unwrap();
+        let synchronous: i64 = conn
+            .query_row("PRAGMA synchronous", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!((cache_size, synchronous), (-1024, 2));
+
+        let error = open_storage(
+            &database_url,
+            &EngineConfig {
+                synchronous: Some("sometimes".to_string()),
+                ..Default::default()
+            },
+        )
+        .err()
+        .unwrap();
+        assert!(error.to_string().contains("Unknown synchronous mode"));
+    }
+
+    #[test]
+    fn test_open_storage_creates_missing_parent_directory() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("nested/deeper/index.db");
+        open_storage(
+            &format!("sqlite://{}", db_path.display()),
+            &EngineConfig::default(),
+        )
+        .unwrap();
+        assert!(db_path.is_file());
+
+        let read_only = EngineConfig {
+            read_only: Some(true),
+            ..Default::default()
+        };
+        let storage = open_storage(&db_path.to_string_lossy(), &read_only).unwrap();
+        assert_eq!(storage.entity_count().unwrap(), 0);
+        assert!(storage.clear().is_err());
+
+        // The URL can ask for the same
+        let storage = open_storage(
+            &format!("sqlite://{}?mode=ro", db_path.display()),
+            &EngineConfig::default(),
+        )
+        .unwrap();
+        assert!(storage.config().read_only);
+        assert!(storage.clear().is_err());
+    }
+
+    #[test]
+    fn test_database_path_problems_are_reported_clearly() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let problem = |path: &Path, mode: DatabaseMode| match check_database_path(path, mode) {
+            Err(CoreError::DatabasePath { problem, .. }) => Some(problem),
+            _ => None,
+        };
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(
+            problem(temp_dir.path(), DatabaseMode::ReadWriteCreate),
+            Some(DatabasePathProblem::IsDirectory)
+        );
+        for mode in [DatabaseMode::ReadOnly, DatabaseMode::ReadWrite] {
+            assert_eq!(
+                problem(&temp_dir.path().join("absent.db"), mode),
+                Some(DatabasePathProblem::NotFound)
+            );
         }
-        // Starts with query gets high score
-        if content_lower.starts_with(&query_lower) {
-            return 0.9;
+
+        let locked = temp_dir.path().join("locked");
+        fs::create_dir(&locked).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o555)).unwrap();
+        // Permissions do not bind root, so there is nothing to check
+        if fs::File::create(locked.join("probe")).is_err() {
+            let error = open_storage(
+                &locked.join("index.db").to_string_lossy(),
+                &EngineConfig::default(),
+            )
+            .err()
+            .unwrap();
+            assert!(error.to_string().contains("permission denied"), "{}", error);
+            assert_eq!(
+                problem(&locked.join("index.db"), DatabaseMode::ReadWriteCreate),
+                Some(DatabasePathProblem::PermissionDenied)
+            );
         }
-        // Contains query gets medium score
-        return 0.7;
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
     }
 
-    // No match
-    0.0
-}
+    #[test]
+    fn test_shared_memory_engine_indexes_then_searches() {
+        memory_engine();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("billing.ts"),
+            "function computeInvoiceTotal(items) { return items.length; }\n",
+        )
+        .unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let path = temp_dir.path().to_string_lossy().to_string();
+        index_codebase(path, Some(1)).unwrap();
 
-    #[test]
-    fn test_init_engine() {
-        let result = init_engine();
-        assert!(result.is_ok());
+        // A separate call sees what the first wrote to the same database
+        let results = search_code("computeInvoiceTotal".to_string(), None).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].file.ends_with("billing.ts"));
     }
 
     #[test]
@@ -364,22 +713,143 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parse_python_with_tree_sitter() {
+        let source = "class Greeter:\n    def greet(self, name):\n        return name\n\n\ndef main():\n    pass\n";
+        let entities = parse_file("app.py".to_string(), source.to_string()).unwrap();
+
+        let summary: Vec<(&str, &str, i32, i32)> = entities
+            .iter()
+            .map(|e| {
+                (
+                    e.name.as_str(),
+                    e.entity_type.as_str(),
+                    e.start_line,
+                    e.end_line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Greeter", "class", 1, 3),
+                ("greet", "function", 2, 3),
+                ("main", "function", 6, 7),
+            ]
+        );
+        assert_eq!(entities[1].id, "app.py:2:greet");
+    }
+
+    #[test]
+    fn test_parse_falls_back_for_unsupported_language() {
+        let entities = parse_file(
+            "widget.vue".to_string(),
+            "function mounted() {}\n".to_string(),
+        )
+        .unwrap();
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].name, "mounted");
+        assert_eq!(entities[0].end_line, 6);
+    }
+
+    #[test]
+    fn test_index_codebase_parses_python() {
+        memory_engine();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file = temp_dir.path().join("tasks.py");
+        fs::write(&file, "def run_task(task):\n    return task()\n").unwrap();
+
+        let path = temp_dir.path().to_string_lossy().to_string();
+        assert!(index_codebase(path, Some(1))
+            .unwrap()
+            .starts_with("Indexed 1 files"));
+
+        let stored = storage()
+            .unwrap()
+            .query_entities(&code_intelligence_storage::EntityQuery {
+                file_path: Some(file.to_string_lossy().to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(
+            (
+                stored[0].name.as_str(),
+                stored[0].entity_type.as_str(),
+                stored[0].end_line
+            ),
+            ("run_task", "function", 2)
+        );
+    }
+
     #[test]
     fn test_search_code() {
+        memory_engine();
         let result = search_code("function".to_string(), None);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_compact_index() {
+        memory_engine();
+        let result = compact_index().unwrap();
+        assert!(!result.incremental);
+        assert!(result.size_after > 0.0);
+    }
+
     #[test]
     fn test_generate_embedding() {
-        let result = generate_embedding("test text".to_string());
+        let result = generate_embedding("test text".to_string(), None);
         assert!(result.is_ok());
         assert_eq!(result.unwrap().len(), 384);
     }
 
+    #[test]
+    fn test_generate_embedding_matches_shared_mock() {
+        for seed in [None, Some(42)] {
+            let mock = MockEmbedding::default().with_seed(u64::from(seed.unwrap_or(0)));
+            assert_eq!(
+                generate_embedding("function parse() {}".to_string(), seed).unwrap(),
+                mock.embed("function parse() {}")
+            );
+        }
+    }
+
     #[test]
     fn test_index_codebase() {
-        let result = index_codebase("/path/to/code".to_string());
+        memory_engine();
+        let result = index_codebase("/path/to/code".to_string(), None);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_index_thread_pool_respects_cap() {
+        let pool = index_thread_pool(Some(2)).unwrap();
+        assert_eq!(pool.current_num_threads(), 2);
+        assert_eq!(pool.install(rayon::current_num_threads), 2);
+
+        assert_eq!(index_thread_pool(Some(0)).unwrap().current_num_threads(), 1);
+        assert_eq!(
+            index_thread_pool(None).unwrap().current_num_threads(),
+            default_index_threads()
+        );
+        assert!(default_index_threads() >= 1);
+    }
+
+    #[test]
+    fn test_index_codebase_with_thread_cap() {
+        memory_engine();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        for i in 0..3 {
+            fs::write(
+                temp_dir.path().join(format!("file_{}.ts", i)),
+                format!("function file_{}() {{}}", i),
+            )
+            .unwrap();
+        }
+
+        let path = temp_dir.path().to_string_lossy().to_string();
+        let result = index_codebase(path, Some(2)).unwrap();
+        assert!(result.starts_with("Indexed 3 files"));
+    }
 }
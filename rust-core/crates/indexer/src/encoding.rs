@@ -0,0 +1,132 @@
+//! Charset detection for source files that are not valid UTF-8
+//!
+//! A byte-order mark settles the encoding outright. Otherwise each legacy
+//! multi-byte encoding is tried strictly, without replacement
+//! characters, and kept only when most of its non-ASCII characters fall in
+//! the scripts that encoding exists to write and form runs of two or more, as
+//! words do. Single-byte Latin text rarely decodes that way: its accented
+//! letters sit alone between ASCII ones. Windows-1252, which decodes nearly
+//! any bytes, is the last resort and must yield printable Latin-1 text.
+
+use encoding_rs::{Encoding, BIG5, EUC_JP, EUC_KR, GBK, SHIFT_JIS, WINDOWS_1252};
+
+/// Share of non-ASCII characters that must look native to the encoding
+const CONFIDENCE: f64 = 0.9;
+
+/// Transcode `bytes` to UTF-8 when their encoding can be told with confidence
+///
+/// Returns the decoded text and the name of the encoding it was read as.
+pub fn detect_and_decode(bytes: &[u8]) -> Option<(String, &'static str)> {
+    if let Some((encoding, bom_length)) = Encoding::for_bom(bytes) {
+        let text =
+            encoding.decode_without_bom_handling_and_without_replacement(&bytes[bom_length..])?;
+        return Some((text.into_owned(), encoding.name()));
+    }
+
+    let mut best: Option<(f64, String, &'static Encoding)> = None;
+    // Multi-byte encodings in order; earlier ones win ties
+    for encoding in [SHIFT_JIS, EUC_JP, GBK, EUC_KR, BIG5] {
+        let Some(text) = encoding.decode_without_bom_handling_and_without_replacement(bytes) else {
+            continue;
+        };
+        let score = native_share(&text, |c| is_native(encoding, c), true);
+        if score >= CONFIDENCE
+            && best
+                .as_ref()
+                .map_or(true, |(best_score, _, _)| score > *best_score)
+        {
+            best = Some((score, text.into_owned(), encoding));
+        }
+    }
+    if let Some((_, text, encoding)) = best {
+        return Some((text, encoding.name()));
+    }
+
+    let text = WINDOWS_1252.decode_without_bom_handling_and_without_replacement(bytes)?;
+    (native_share(&text, is_latin, false) >= CONFIDENCE)
+        .then(|| (text.into_owned(), WINDOWS_1252.name()))
+}
+
+/// Fraction of the non-ASCII characters in `text` accepted by `native`,
+/// counting only those in runs of two or more when `in_runs` is set
+fn native_share(text: &str, native: impl Fn(char) -> bool, in_runs: bool) -> f64 {
+    let chars: Vec<char> = text.chars().collect();
+    let mut non_ascii = 0;
+    let mut accepted = 0;
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii() {
+            continue;
+        }
+        non_ascii += 1;
+        let in_run = (i > 0 && !chars[i - 1].is_ascii())
+            || chars.get(i + 1).is_some_and(|next| !next.is_ascii());
+        if native(c) && (in_run || !in_runs) {
+            accepted += 1;
+        }
+    }
+    if non_ascii == 0 {
+        return 0.0;
+    }
+    f64::from(accepted) / f64::from(non_ascii)
+}
+
+/// Whether `c` belongs to a script `encoding` is used to write
+fn is_native(encoding: &Encoding, c: char) -> bool {
+    let c = u32::from(c);
+    let cjk = (0x4E00..=0x9FFF).contains(&c);
+    let punctuation = (0x3000..=0x303F).contains(&c) || (0xFF00..=0xFFEF).contains(&c);
+    let kana = (0x3040..=0x30FF).contains(&c);
+    let hangul = (0xAC00..=0xD7AF).contains(&c) || (0x3130..=0x318F).contains(&c);
+    if encoding == SHIFT_JIS || encoding == EUC_JP {
+        cjk || punctuation || kana
+    } else if encoding == EUC_KR {
+        hangul || cjk || punctuation
+    } else {
+        cjk || punctuation
+    }
+}
+
+/// Printable Latin-1 letters and symbols, plus Windows-1252's typographic punctuation
+fn is_latin(c: char) -> bool {
+    ('\u{A0}'..='\u{FF}').contains(&c) || "€‚ƒ„…†‡ˆ‰Š‹ŒŽ‘’“”•–—˜™š›œžŸ".contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_shift_jis() {
+        let source = "// 設定を読み込む\nfunction loadConfig() { return \"こんにちは\"; }\n";
+        let (bytes, _, _) = SHIFT_JIS.encode(source);
+        assert!(std::str::from_utf8(&bytes).is_err());
+
+        assert_eq!(
+            detect_and_decode(&bytes),
+            Some((source.to_string(), "Shift_JIS"))
+        );
+    }
+
+    #[test]
+    fn test_detects_latin1_and_boms() {
+        let source = "# café, naïve résumé\ndef greet(): pass\n";
+        let (bytes, _, _) = WINDOWS_1252.encode(source);
+        assert_eq!(
+            detect_and_decode(&bytes),
+            Some((source.to_string(), "windows-1252"))
+        );
+
+        let mut utf16 = vec![0xFF, 0xFE];
+        utf16.extend("fn main() {}".encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!(
+            detect_and_decode(&utf16),
+            Some(("fn main() {}".to_string(), "UTF-16LE"))
+        );
+    }
+
+    #[test]
+    fn test_unrecognizable_bytes_are_not_decoded() {
+        // C1 control bytes are not printable text in any candidate
+        assert_eq!(detect_and_decode(b"let x = \x81\x8d\x8f\x90\x9d;"), None);
+    }
+}
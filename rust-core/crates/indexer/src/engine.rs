@@ -2,62 +2,345 @@
 
 use anyhow::Result;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::{IndexingConfig, IndexingProgress};
+use crate::post_process::{CodeOwners, EntityPostProcessor, FileContext};
+use crate::{EntityLimitAction, IndexingConfig, IndexingProgress};
 use code_intelligence_core::{CodeEntity, EntityType as CoreEntityType};
-use code_intelligence_parser::{CodeEntity as ParserCodeEntity, CodeParser};
+use code_intelligence_parser::{
+    CodeEntity as ParserCodeEntity, CodeParser, Language, ParseOptions,
+};
+
+/// Metadata key holding a file's position in the sorted scan order, set when
+/// [`IndexingConfig::deterministic_order`] is on
+pub const FILE_ORDINAL_KEY: &str = "file_ordinal";
+
+/// Parser options derived from the indexing configuration
+fn parse_options(config: &IndexingConfig) -> ParseOptions {
+    ParseOptions {
+        parse_timeout: config
+            .parse_timeout_ms
+            .map(std::time::Duration::from_millis),
+        merge_declarations: config.merge_declarations,
+        group_overloads: config.group_overloads,
+        max_node_visits: config.max_node_visits,
+        index_anonymous: config.index_anonymous,
+        ..Default::default()
+    }
+}
+
+/// Cut `content` to at most `max_bytes` on a char boundary, returning whether it was cut
+fn truncate_content(content: &mut String, max_bytes: usize) -> bool {
+    if content.len() <= max_bytes {
+        return false;
+    }
+    let mut end = max_bytes;
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    content.truncate(end);
+    true
+}
+
+/// Whether `name` matches `pattern`, where `*` matches any run of characters
+/// and `?` any single character
+pub(crate) fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*` and the name position it was tried against
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((after_star, tried)) => {
+                    backtrack = Some((after_star, tried + 1));
+                    p = after_star;
+                    n = tried + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
 
 /// Core indexing engine
 pub struct Engine {
     parser: CodeParser,
     config: IndexingConfig,
     indexed_entities: RwLock<HashMap<Uuid, CodeEntity>>,
+    /// Whether `process_file` keeps entities in `indexed_entities`, off when
+    /// they are written through to storage instead
+    retain_entities: bool,
     progress: RwLock<IndexingProgress>,
     is_running: RwLock<bool>,
+    /// Root of the codebase being indexed, used for relative paths
+    codebase_root: RwLock<Option<PathBuf>>,
+    /// Warnings raised while processing files, drained by `take_warnings`
+    warnings: RwLock<Vec<String>>,
+    embeddings: RwLock<HashMap<Uuid, Vec<f32>>>,
+    /// Stored path -> position in the sorted file list, for deterministic order
+    file_ordinals: RwLock<HashMap<String, usize>>,
+    /// Rules of the codebase root's `CODEOWNERS`, with `tag_code_owners`
+    code_owners: RwLock<Option<CodeOwners>>,
 }
 
 impl Engine {
     /// Create a new indexing engine
     pub fn new(config: IndexingConfig) -> Self {
         Self {
-            parser: CodeParser::new(),
+            parser: CodeParser::with_options(parse_options(&config)),
             config,
             indexed_entities: RwLock::new(HashMap::new()),
+            retain_entities: true,
             progress: RwLock::new(IndexingProgress {
                 total_files: 0,
                 processed_files: 0,
+                skipped_files: 0,
                 total_entities: 0,
                 current_file: None,
                 errors: Vec::new(),
+                warnings: Vec::new(),
+                timed_out: false,
+                peak_buffered_entities: 0,
                 start_time: std::time::Instant::now(),
                 estimated_time_remaining: None,
             }),
             is_running: RwLock::new(false),
+            codebase_root: RwLock::new(None),
+            warnings: RwLock::new(Vec::new()),
+            embeddings: RwLock::new(HashMap::new()),
+            file_ordinals: RwLock::new(HashMap::new()),
+            code_owners: RwLock::new(None),
+        }
+    }
+
+    /// Record the root that relative file paths are resolved against, and
+    /// load its `CODEOWNERS` when `tag_code_owners` is set
+    pub async fn set_codebase_root(&self, root: &Path) {
+        *self.codebase_root.write().await = Some(root.to_path_buf());
+        *self.code_owners.write().await = self
+            .config
+            .tag_code_owners
+            .then(|| CodeOwners::load(root))
+            .flatten();
+    }
+
+    /// Set whether processed entities are kept in memory for `get_entities`
+    /// and the other lookups
+    ///
+    /// Without them the engine holds no entity beyond the file it returns
+    /// them for, so memory stays flat however large the codebase.
+    pub fn set_retain_entities(&mut self, retain: bool) {
+        self.retain_entities = retain;
+    }
+
+    /// Number the files about to be indexed in the given order
+    pub async fn set_file_order(&self, files: &[PathBuf]) {
+        let mut ordinals = HashMap::with_capacity(files.len());
+        for (ordinal, file) in files.iter().enumerate() {
+            ordinals.insert(self.stored_path(file).await, ordinal);
         }
+        *self.file_ordinals.write().await = ordinals;
+    }
+
+    /// Get the recorded codebase root
+    pub async fn codebase_root(&self) -> Option<PathBuf> {
+        self.codebase_root.read().await.clone()
+    }
+
+    /// The `file_path` stored for a file, relative to the codebase root when configured
+    ///
+    /// Relative paths always use `/` so they compare equal across platforms.
+    pub async fn stored_path(&self, file_path: &Path) -> String {
+        if self.config.store_relative_paths {
+            if let Some(relative) = self.relative_path(file_path).await {
+                return relative;
+            }
+        }
+        file_path.to_string_lossy().to_string()
+    }
+
+    /// `file_path` relative to the codebase root, joined with `/`, when it lies below it
+    async fn relative_path(&self, file_path: &Path) -> Option<String> {
+        let root = self.codebase_root.read().await;
+        let relative = file_path.strip_prefix(root.as_ref()?).ok()?;
+        Some(
+            relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+        )
+    }
+
+    /// Parse a file into the entities indexing would store, without storing them
+    pub async fn parse_entities(&self, file_path: &Path, content: &str) -> Result<Vec<CodeEntity>> {
+        let entities = self.extract_entities(file_path, content).await?;
+        let stored_path = self.stored_path(file_path).await;
+
+        Ok(entities
+            .into_iter()
+            .map(|entity| self.convert_parser_to_core_entity(entity, &stored_path))
+            .collect())
+    }
+
+    /// The parser's entities for a file plus those of the custom extractors
+    /// selecting it, keeping only the ones that pass the configured filters
+    ///
+    /// A file without a grammar is left to its extractors alone.
+    async fn extract_entities(
+        &self,
+        file_path: &Path,
+        content: &str,
+    ) -> Result<Vec<ParserCodeEntity>> {
+        let relative = match self.relative_path(file_path).await {
+            Some(relative) => relative,
+            None => file_path.to_string_lossy().replace('\\', "/"),
+        };
+        let extractors: Vec<_> = self
+            .config
+            .custom_extractors
+            .iter()
+            .filter(|extractor| extractor.matches_file(&relative))
+            .collect();
+
+        let (language, mut entities) =
+            if !extractors.is_empty() && self.parser.detect_language(file_path).is_err() {
+                (None, Vec::new())
+            } else {
+                let parse_result = self.parser.parse_file(file_path, content)?;
+                (Some(parse_result.language), parse_result.entities)
+            };
+        let path = file_path.to_string_lossy();
+        for extractor in extractors {
+            entities.extend(extractor.extract(&path, content));
+        }
+        entities.retain(|entity| self.is_entity_indexed(entity, language.as_ref()));
+        Ok(entities)
     }
 
     /// Process a single file and extract entities
     pub async fn process_file(&self, file_path: &Path, content: &str) -> Result<Vec<CodeEntity>> {
-        let parse_result = self.parser.parse_file(file_path, content)?;
+        let mut parsed = self.extract_entities(file_path, content).await?;
+        let stored_path = self.stored_path(file_path).await;
+
+        if let Some(max_entities) = self.config.max_entities_per_file {
+            let count = parsed.len();
+            if count > max_entities {
+                let warning = match self.config.entity_limit_action {
+                    EntityLimitAction::Truncate => {
+                        parsed.truncate(max_entities);
+                        format!(
+                            "Truncated {} to the first {} of {} entities",
+                            stored_path, max_entities, count
+                        )
+                    }
+                    EntityLimitAction::Skip => {
+                        parsed.clear();
+                        format!(
+                            "Skipped {}: {} entities exceed the limit of {}",
+                            stored_path, count, max_entities
+                        )
+                    }
+                };
+                tracing::warn!("{}", warning);
+                self.warnings.write().await.push(warning);
+            }
+        }
 
+        let ordinal = self.file_ordinals.read().await.get(&stored_path).copied();
+        let relative_path = self.relative_path(file_path).await;
+        let context = FileContext {
+            file_path,
+            relative_path: relative_path.as_deref(),
+            content,
+        };
+        let code_owners = self.code_owners.read().await;
+        let post_processors: Vec<&dyn EntityPostProcessor> = code_owners
+            .iter()
+            .map(|owners| owners as &dyn EntityPostProcessor)
+            .chain(
+                self.config
+                    .post_processors
+                    .iter()
+                    .map(|processor| &**processor),
+            )
+            .chain(
+                self.config
+                    .redact_secrets
+                    .iter()
+                    .map(|redactor| redactor as &dyn EntityPostProcessor),
+            )
+            .collect();
         let mut entities = Vec::new();
-        for parser_entity in parse_result.entities {
+        for parser_entity in parsed {
             // Convert parser entity to core entity
-            let core_entity = self.convert_parser_to_core_entity(parser_entity, file_path);
-
-            // Store the entity
-            let mut indexed_entities = self.indexed_entities.write().await;
-            indexed_entities.insert(core_entity.id, core_entity.clone());
+            let mut core_entity = self.convert_parser_to_core_entity(parser_entity, &stored_path);
+            if let Some(ordinal) = ordinal {
+                core_entity
+                    .metadata
+                    .insert(FILE_ORDINAL_KEY.to_string(), ordinal.to_string());
+            }
+            for processor in &post_processors {
+                processor.process(&mut core_entity, &context);
+            }
 
             entities.push(core_entity);
         }
 
+        if self.retain_entities {
+            let mut indexed_entities = self.indexed_entities.write().await;
+            for entity in &entities {
+                indexed_entities.insert(entity.id, entity.clone());
+            }
+        }
+
         Ok(entities)
     }
 
+    /// Whether an entity passes the type filter for its file's `language`,
+    /// if it has a grammar, and the name filters: `only_entity_names`,
+    /// `ignore_entity_names` and `min_name_length`
+    fn is_entity_indexed(&self, entity: &ParserCodeEntity, language: Option<&Language>) -> bool {
+        let types = language
+            .and_then(|language| self.config.per_language_entity_types.get(language))
+            .unwrap_or(&self.config.entity_types);
+        if !types.is_empty() && !types.contains(&entity.entity_type) {
+            return false;
+        }
+
+        let name = entity.name.as_str();
+        if name.chars().count() < self.config.min_name_length
+            && !self
+                .config
+                .min_name_length_exempt
+                .contains(&entity.entity_type)
+        {
+            return false;
+        }
+
+        let only = &self.config.only_entity_names;
+        (only.is_empty() || only.iter().any(|pattern| glob_matches(pattern, name)))
+            && !self
+                .config
+                .ignore_entity_names
+                .iter()
+                .any(|pattern| glob_matches(pattern, name))
+    }
+
     /// Convert parser entity type to core entity type
     fn convert_entity_type(
         &self,
@@ -72,6 +355,11 @@ impl Engine {
             code_intelligence_parser::EntityType::Module => CoreEntityType::Module,
             code_intelligence_parser::EntityType::Import => CoreEntityType::Import,
             code_intelligence_parser::EntityType::Export => CoreEntityType::Import, // Map Export to Import
+            code_intelligence_parser::EntityType::Endpoint => CoreEntityType::Endpoint,
+            code_intelligence_parser::EntityType::Enum => CoreEntityType::Enum,
+            code_intelligence_parser::EntityType::Struct => CoreEntityType::Struct,
+            code_intelligence_parser::EntityType::TypeAlias => CoreEntityType::TypeAlias,
+            code_intelligence_parser::EntityType::Trait => CoreEntityType::Trait,
         }
     }
 
@@ -79,19 +367,25 @@ impl Engine {
     fn convert_parser_to_core_entity(
         &self,
         parser_entity: ParserCodeEntity,
-        file_path: &Path,
+        file_path: &str,
     ) -> CodeEntity {
+        let mut content = parser_entity.content;
+        let truncated = self
+            .config
+            .max_content_bytes
+            .is_some_and(|max_bytes| truncate_content(&mut content, max_bytes));
+
         // Convert parser entity to core entity using the simpler structure
         CodeEntity {
             id: Uuid::new_v4(),
             name: parser_entity.name,
             entity_type: self.convert_entity_type(parser_entity.entity_type),
-            file_path: file_path.to_string_lossy().to_string(),
+            file_path: file_path.to_string(),
             start_line: parser_entity.start_line,
             end_line: parser_entity.end_line,
-            content: parser_entity.content,
+            content,
             metadata: {
-                let mut metadata = std::collections::HashMap::new();
+                let mut metadata = parser_entity.metadata;
                 if let Some(signature) = parser_entity.signature {
                     metadata.insert("signature".to_string(), signature);
                 }
@@ -106,20 +400,53 @@ impl Engine {
                     "end_column".to_string(),
                     parser_entity.end_column.to_string(),
                 );
+                metadata.insert(
+                    "start_byte".to_string(),
+                    parser_entity.start_byte.to_string(),
+                );
+                metadata.insert("end_byte".to_string(), parser_entity.end_byte.to_string());
+                if truncated {
+                    metadata.insert("truncated".to_string(), "true".to_string());
+                }
                 metadata
             },
         }
     }
 
+    /// Record embeddings for indexed entities
+    pub async fn store_embeddings(&self, embeddings: impl IntoIterator<Item = (Uuid, Vec<f32>)>) {
+        self.embeddings.write().await.extend(embeddings);
+    }
+
+    /// Get the embedding of an entity
+    pub async fn get_embedding(&self, entity_id: &Uuid) -> Option<Vec<f32>> {
+        self.embeddings.read().await.get(entity_id).cloned()
+    }
+
+    /// Record a warning for the current run, returned by `take_warnings`
+    pub async fn add_warning(&self, warning: String) {
+        tracing::warn!("{}", warning);
+        self.warnings.write().await.push(warning);
+    }
+
+    /// Take the warnings recorded since the last call
+    pub async fn take_warnings(&self) -> Vec<String> {
+        std::mem::take(&mut *self.warnings.write().await)
+    }
+
     /// Get current progress
     pub async fn get_progress(&self) -> IndexingProgress {
         let progress = self.progress.read().await;
         IndexingProgress {
             total_files: progress.total_files,
             processed_files: progress.processed_files,
+            skipped_files: progress.skipped_files,
             total_entities: progress.total_entities,
             current_file: progress.current_file.clone(),
             errors: progress.errors.clone(),
+            warnings: progress.warnings.clone(),
+            timed_out: progress.timed_out,
+            peak_buffered_entities: progress.peak_buffered_entities,
             start_time: progress.start_time,
             estimated_time_remaining: progress.estimated_time_remaining,
         }
@@ -136,14 +463,20 @@ impl Engine {
     pub async fn clear(&self) -> Result<()> {
         let mut indexed_entities = self.indexed_entities.write().await;
         indexed_entities.clear();
+        self.embeddings.write().await.clear();
+        self.file_ordinals.write().await.clear();
 
         let mut progress = self.progress.write().await;
         *progress = IndexingProgress {
             total_files: 0,
             processed_files: 0,
+            skipped_files: 0,
             total_entities: 0,
             current_file: None,
             errors: Vec::new(),
+            warnings: Vec::new(),
+            timed_out: false,
+            peak_buffered_entities: 0,
             start_time: std::time::Instant::now(),
             estimated_time_remaining: None,
         };
@@ -153,14 +486,41 @@ impl Engine {
 
     /// Update configuration
     pub async fn update_config(&mut self, new_config: IndexingConfig) -> Result<()> {
+        if new_config.parse_timeout_ms != self.config.parse_timeout_ms
+            || new_config.merge_declarations != self.config.merge_declarations
+            || new_config.group_overloads != self.config.group_overloads
+            || new_config.max_node_visits != self.config.max_node_visits
+        {
+            self.parser = CodeParser::with_options(parse_options(&new_config));
+        }
         self.config = new_config;
         Ok(())
     }
 
     /// Get all indexed entities
+    ///
+    /// With `deterministic_order` they come in file order, then source order.
     pub async fn get_entities(&self) -> Vec<CodeEntity> {
         let indexed_entities = self.indexed_entities.read().await;
-        indexed_entities.values().cloned().collect()
+        let mut entities: Vec<CodeEntity> = indexed_entities.values().cloned().collect();
+        if self.config.deterministic_order {
+            entities.sort_by_cached_key(|entity| {
+                let position = |key: &str| {
+                    entity
+                        .metadata
+                        .get(key)
+                        .and_then(|value| value.parse::<usize>().ok())
+                        .unwrap_or(usize::MAX)
+                };
+                (
+                    position(FILE_ORDINAL_KEY),
+                    entity.file_path.clone(),
+                    position("start_byte"),
+                    entity.name.clone(),
+                )
+            });
+        }
+        entities
     }
 
     /// Search for entities by name
@@ -175,8 +535,9 @@ impl Engine {
             .collect()
     }
 
-    /// Get entities by file path
+    /// Get entities by file path, given as an absolute or root-relative path
     pub async fn get_entities_by_file(&self, file_path: &str) -> Vec<CodeEntity> {
+        let file_path = self.stored_path(Path::new(file_path)).await;
         let indexed_entities = self.indexed_entities.read().await;
 
         indexed_entities
@@ -254,6 +615,306 @@ const testVariable = "test";
         assert!(!indexed_entities.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_engine_without_retained_entities_holds_none() {
+        let temp_dir = TempDir::new().unwrap();
+        let test_file = temp_dir.path().join("test.js");
+        let mut engine = Engine::new(IndexingConfig::default());
+        engine.set_retain_entities(false);
+
+        let entities = engine
+            .process_file(&test_file, "function first() {}\nfunction second() {}\n")
+            .await
+            .unwrap();
+
+        assert_eq!(entities.len(), 2);
+        assert!(engine.indexed_entities.read().await.is_empty());
+        assert!(engine.get_entities().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_engine_parse_timeout_from_config() {
+        let mut engine = Engine::new(IndexingConfig::default());
+        assert_eq!(engine.parser.options().parse_timeout, None);
+
+        let config = IndexingConfig {
+            parse_timeout_ms: Some(250),
+            ..Default::default()
+        };
+        engine.update_config(config).await.unwrap();
+        assert_eq!(
+            engine.parser.options().parse_timeout,
+            Some(std::time::Duration::from_millis(250))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_engine_truncates_large_content() {
+        let config = IndexingConfig {
+            max_content_bytes: Some(40),
+            ..Default::default()
+        };
+        let engine = Engine::new(config);
+        let body: String = (0..20).map(|i| format!("    total += {};\n", i)).collect();
+        let content = format!(
+            "const small = 1;\nfunction big() {{\n    let total = 0;\n{}    return total;\n}}\n",
+            body
+        );
+
+        let entities = engine
+            .process_file(Path::new("big.ts"), &content)
+            .await
+            .unwrap();
+
+        let big = entities.iter().find(|e| e.name == "big").unwrap();
+        assert_eq!(big.content.len(), 40);
+        assert_eq!(
+            big.metadata.get("truncated").map(String::as_str),
+            Some("true")
+        );
+        let start: usize = big.metadata["start_byte"].parse().unwrap();
+        let end: usize = big.metadata["end_byte"].parse().unwrap();
+        let full = &content[start..end];
+        assert!(full.starts_with(&big.content));
+        assert!(full.ends_with("return total;\n}"));
+
+        let small = entities.iter().find(|e| e.name == "small").unwrap();
+        assert!(!small.metadata.contains_key("truncated"));
+
+        // The stored prefix is still searchable
+        assert!(engine
+            .get_entities()
+            .await
+            .iter()
+            .any(|e| e.content.contains("let total = 0")));
+    }
+
+    #[tokio::test]
+    async fn test_engine_caps_entities_per_file() {
+        let content: String = (0..50)
+            .map(|i| format!("const v{} = {};\n", i, i))
+            .collect();
+
+        let truncating = Engine::new(IndexingConfig {
+            max_entities_per_file: Some(10),
+            ..Default::default()
+        });
+        let entities = truncating
+            .process_file(Path::new("generated.ts"), &content)
+            .await
+            .unwrap();
+        assert_eq!(entities.len(), 10);
+        assert_eq!(entities[0].name, "v0");
+        assert_eq!(
+            truncating.take_warnings().await,
+            vec!["Truncated generated.ts to the first 10 of 50 entities".to_string()]
+        );
+        assert!(truncating.take_warnings().await.is_empty());
+
+        let skipping = Engine::new(IndexingConfig {
+            max_entities_per_file: Some(10),
+            entity_limit_action: EntityLimitAction::Skip,
+            ..Default::default()
+        });
+        let entities = skipping
+            .process_file(Path::new("generated.ts"), &content)
+            .await
+            .unwrap();
+        assert!(entities.is_empty());
+        assert!(skipping.get_entities().await.is_empty());
+        assert_eq!(
+            skipping.take_warnings().await,
+            vec!["Skipped generated.ts: 50 entities exceed the limit of 10".to_string()]
+        );
+
+        // Files under the cap are untouched
+        let small = skipping
+            .process_file(Path::new("small.ts"), "const a = 1;\n")
+            .await
+            .unwrap();
+        assert_eq!(small.len(), 1);
+        assert!(skipping.take_warnings().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_engine_ignores_entity_names() {
+        let content = r#"
+class Greeter:
+    def __init__(self):
+        self.name = "world"
+
+    def greet(self):
+        return self.name
+
+def test_greet():
+    pass
+"#;
+        let names = |engine: Engine| async move {
+            let mut names: Vec<String> = engine
+                .process_file(Path::new("greeter.py"), content)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|e| e.name)
+                .collect();
+            names.sort();
+            names
+        };
+
+        let ignoring = Engine::new(IndexingConfig {
+            ignore_entity_names: vec!["__init__".to_string(), "test_*".to_string()],
+            ..Default::default()
+        });
+        let kept = names(ignoring).await;
+        assert!(!kept.iter().any(|name| name == "__init__"));
+        assert!(!kept.iter().any(|name| name == "test_greet"));
+        assert!(kept.iter().any(|name| name == "greet"));
+        assert!(kept.iter().any(|name| name == "Greeter"));
+
+        let targeted = Engine::new(IndexingConfig {
+            only_entity_names: vec!["gr*".to_string(), "Greeter".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(names(targeted).await, vec!["Greeter", "greet"]);
+    }
+
+    #[tokio::test]
+    async fn test_engine_drops_short_names() {
+        let content = "let i = 0;\nconst ok = 1;\nfunction go() {}\nfunction f() {}\n";
+        let engine = Engine::new(IndexingConfig {
+            min_name_length: 2,
+            ..Default::default()
+        });
+
+        let mut names: Vec<String> = engine
+            .process_file(Path::new("loop.ts"), content)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        names.sort();
+        // `f` is kept because functions are exempt by default
+        assert_eq!(names, vec!["f", "go", "ok"]);
+
+        let strict = Engine::new(IndexingConfig {
+            min_name_length: 2,
+            min_name_length_exempt: Vec::new(),
+            ..Default::default()
+        });
+        let entities = strict
+            .process_file(Path::new("loop.ts"), content)
+            .await
+            .unwrap();
+        assert!(!entities.iter().any(|e| e.name == "i" || e.name == "f"));
+        assert!(entities.iter().any(|e| e.name == "go"));
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_entities_are_opt_in() {
+        let source = "const handler = () => 1;\n\nitems.map((item) => item.id);\n";
+        let names = |engine: Engine| async move {
+            let entities = engine
+                .process_file(Path::new("app.ts"), source)
+                .await
+                .unwrap();
+            entities.into_iter().map(|e| e.name).collect::<Vec<_>>()
+        };
+
+        let default = names(Engine::new(IndexingConfig::default())).await;
+        assert_eq!(default, vec!["handler"]);
+
+        let anonymous = names(Engine::new(IndexingConfig {
+            index_anonymous: true,
+            ..Default::default()
+        }))
+        .await;
+        assert_eq!(anonymous, vec!["handler", "<anonymous@3>"]);
+    }
+
+    #[tokio::test]
+    async fn test_engine_filters_entity_types_per_language() {
+        use code_intelligence_parser::EntityType;
+
+        let python_source = "count = 0\n\nclass Job:\n    pass\n\ndef run():\n    pass\n";
+        let rust_source = "struct Job;\n\nfn run() {\n    let total = 0;\n}\n";
+        let types = |entities: Vec<CodeEntity>| -> Vec<CoreEntityType> {
+            entities.into_iter().map(|e| e.entity_type).collect()
+        };
+
+        let engine = Engine::new(IndexingConfig {
+            per_language_entity_types: HashMap::from([(
+                Language::Python,
+                vec![EntityType::Function, EntityType::Class],
+            )]),
+            ..Default::default()
+        });
+        let python = types(
+            engine
+                .process_file(Path::new("app.py"), python_source)
+                .await
+                .unwrap(),
+        );
+        assert!(python.contains(&CoreEntityType::Class));
+        assert!(python.contains(&CoreEntityType::Function));
+        assert!(!python.contains(&CoreEntityType::Variable));
+
+        // Rust has no entry, so it keeps every type
+        let rust = types(
+            engine
+                .process_file(Path::new("lib.rs"), rust_source)
+                .await
+                .unwrap(),
+        );
+        assert!(rust.contains(&CoreEntityType::Variable));
+
+        // The global list applies to languages without their own entry
+        let functions_only = Engine::new(IndexingConfig {
+            entity_types: vec![EntityType::Function],
+            per_language_entity_types: HashMap::from([(Language::Rust, Vec::new())]),
+            ..Default::default()
+        });
+        let python = types(
+            functions_only
+                .process_file(Path::new("app.py"), python_source)
+                .await
+                .unwrap(),
+        );
+        assert_eq!(python, vec![CoreEntityType::Function]);
+        let rust = types(
+            functions_only
+                .process_file(Path::new("lib.rs"), rust_source)
+                .await
+                .unwrap(),
+        );
+        assert!(rust.contains(&CoreEntityType::Variable));
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("__init__", "__init__"));
+        assert!(!glob_matches("__init__", "__init__s"));
+        assert!(glob_matches("test_*", "test_"));
+        assert!(glob_matches("test_*", "test_login"));
+        assert!(!glob_matches("test_*", "my_test_login"));
+        assert!(glob_matches("*_helper", "setup_db_helper"));
+        assert!(glob_matches("a*b*c", "aXbYbZc"));
+        assert!(glob_matches("v?", "v1"));
+        assert!(!glob_matches("v?", "v10"));
+        assert!(glob_matches("*", ""));
+    }
+
+    #[test]
+    fn test_truncate_content_respects_char_boundaries() {
+        let mut content = "fn é() {}".to_string();
+        assert!(truncate_content(&mut content, 4));
+        assert_eq!(content, "fn ");
+
+        let mut short = "fn a() {}".to_string();
+        assert!(!truncate_content(&mut short, 100));
+        assert_eq!(short, "fn a() {}");
+    }
+
     #[tokio::test]
     async fn test_engine_search() {
         let config = IndexingConfig::default();
@@ -0,0 +1,150 @@
+//! Index size and duration estimates from a sample of files
+//!
+//! Parsing a few files measures how many entities, stored bytes and seconds
+//! each kilobyte of source costs. Those rates are scaled to the total size of
+//! every candidate file with a ratio estimator, whose spread across the sample
+//! gives the confidence bounds.
+
+use serde::{Deserialize, Serialize};
+
+/// z-score of a two-sided 95% interval
+const Z_95: f64 = 1.96;
+
+/// An extrapolated total with an approximate 95% confidence interval
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EstimateRange {
+    pub expected: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+/// Projected cost of indexing a codebase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEstimate {
+    /// Files that indexing would parse
+    pub candidate_files: usize,
+    /// Combined size of the candidate files
+    pub total_bytes: u64,
+    /// Files actually parsed for the estimate
+    pub sampled_files: usize,
+    pub entities: EstimateRange,
+    /// Serialized size of the stored entities
+    pub db_bytes: EstimateRange,
+    pub duration_seconds: EstimateRange,
+}
+
+/// Measurements from one sampled file
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileSample {
+    pub bytes: u64,
+    pub entities: usize,
+    pub stored_bytes: usize,
+    pub seconds: f64,
+}
+
+impl IndexEstimate {
+    /// Extrapolate the samples to `candidate_files` files totalling `total_bytes`
+    pub fn from_samples(samples: &[FileSample], candidate_files: usize, total_bytes: u64) -> Self {
+        let extrapolate = |value: fn(&FileSample) -> f64| {
+            ratio_estimate(samples, value, candidate_files, total_bytes)
+        };
+
+        Self {
+            candidate_files,
+            total_bytes,
+            sampled_files: samples.len(),
+            entities: extrapolate(|sample| sample.entities as f64),
+            db_bytes: extrapolate(|sample| sample.stored_bytes as f64),
+            duration_seconds: extrapolate(|sample| sample.seconds),
+        }
+    }
+}
+
+/// Ratio estimate of the population total of `value`, using file size as the auxiliary variable
+///
+/// The interval applies the finite-population correction, so it collapses to
+/// the sampled total once every file is sampled. The low bound never drops
+/// below what the sample itself measured.
+fn ratio_estimate(
+    samples: &[FileSample],
+    value: fn(&FileSample) -> f64,
+    population: usize,
+    total_bytes: u64,
+) -> EstimateRange {
+    let sampled_bytes: f64 = samples.iter().map(|sample| sample.bytes as f64).sum();
+    let sampled_value: f64 = samples.iter().map(value).sum();
+    if sampled_bytes == 0.0 {
+        return EstimateRange {
+            expected: sampled_value,
+            low: sampled_value,
+            high: sampled_value,
+        };
+    }
+
+    let ratio = sampled_value / sampled_bytes;
+    let expected = ratio * total_bytes as f64;
+
+    let n = samples.len() as f64;
+    let margin = if samples.len() > 1 && population > samples.len() {
+        let residual_variance = samples
+            .iter()
+            .map(|sample| (value(sample) - ratio * sample.bytes as f64).powi(2))
+            .sum::<f64>()
+            / (n - 1.0);
+        let big_n = population as f64;
+        let variance = big_n * big_n * (1.0 - n / big_n) * residual_variance / n;
+        Z_95 * variance.sqrt()
+    } else {
+        0.0
+    };
+
+    EstimateRange {
+        expected,
+        low: (expected - margin).max(sampled_value),
+        high: expected + margin,
+    }
+}
+
+/// Indices of `count` files spread evenly over `total`
+pub fn sample_indices(total: usize, count: usize) -> Vec<usize> {
+    let count = count.min(total);
+    (0..count).map(|i| i * total / count).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(bytes: u64, entities: usize) -> FileSample {
+        FileSample {
+            bytes,
+            entities,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_full_sample_is_exact() {
+        let samples = [sample(1000, 10), sample(2000, 20)];
+        let estimate = IndexEstimate::from_samples(&samples, 2, 3000);
+        assert_eq!(estimate.entities.expected, 30.0);
+        assert_eq!(estimate.entities.low, 30.0);
+        assert_eq!(estimate.entities.high, 30.0);
+    }
+
+    #[test]
+    fn test_partial_sample_extrapolates_with_bounds() {
+        let samples = [sample(1000, 8), sample(1000, 12), sample(2000, 20)];
+        let estimate = IndexEstimate::from_samples(&samples, 30, 40_000);
+        assert_eq!(estimate.entities.expected, 400.0);
+        assert!(estimate.entities.low < 400.0 && estimate.entities.low >= 40.0);
+        assert!(estimate.entities.high > 400.0);
+    }
+
+    #[test]
+    fn test_sample_indices_spread() {
+        assert_eq!(sample_indices(10, 3), vec![0, 3, 6]);
+        assert_eq!(sample_indices(2, 5), vec![0, 1]);
+        assert!(sample_indices(0, 5).is_empty());
+    }
+}
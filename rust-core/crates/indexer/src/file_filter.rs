@@ -0,0 +1,150 @@
+//! Path globs and `.gitignore` rules for choosing the files to index
+//!
+//! Paths are matched relative to the indexed root, with `/` separators. In a
+//! glob, `**` matches any number of directories, `*` any run of characters
+//! within one path component and `?` a single character. A glob without a
+//! `/` matches the file name at any depth, so `*.test.ts` and `**/*.test.ts`
+//! select the same files.
+
+use crate::engine::glob_matches;
+use std::path::Path;
+
+/// Whether the root-relative `path` matches `pattern`
+pub fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim_start_matches('/');
+    let components: Vec<&str> = path.split('/').collect();
+    if !pattern.contains('/') {
+        return components
+            .last()
+            .is_some_and(|name| glob_matches(pattern, name));
+    }
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    components_match(&pattern, &components)
+}
+
+fn components_match(pattern: &[&str], components: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => components.is_empty(),
+        Some((&"**", rest)) => {
+            (0..=components.len()).any(|skip| components_match(rest, &components[skip..]))
+        }
+        Some((first, rest)) => components.split_first().is_some_and(|(component, others)| {
+            glob_matches(first, component) && components_match(rest, others)
+        }),
+    }
+}
+
+/// Rules of the `.gitignore` at the indexed root
+///
+/// Nested `.gitignore` files and global excludes are not read. Later rules
+/// win, so a `!pattern` re-includes what an earlier rule ignored.
+#[derive(Debug, Clone, Default)]
+pub struct Gitignore {
+    rules: Vec<GitignoreRule>,
+}
+
+#[derive(Debug, Clone)]
+struct GitignoreRule {
+    pattern: String,
+    negated: bool,
+    /// Written with a trailing `/`, so only directories match
+    dir_only: bool,
+    /// Written with a `/` before its end, so it matches from the root
+    anchored: bool,
+}
+
+impl Gitignore {
+    /// The rules in `root/.gitignore`; none when it is missing or unreadable
+    pub fn load(root: &Path) -> Self {
+        std::fs::read_to_string(root.join(".gitignore"))
+            .map(|text| Self::parse(&text))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let rules = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(line) => (true, line),
+                    None => (false, line),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(line) => (true, line),
+                    None => (false, line),
+                };
+                GitignoreRule {
+                    anchored: line.contains('/'),
+                    pattern: line.trim_start_matches('/').to_string(),
+                    negated,
+                    dir_only,
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Whether the root-relative file `path`, or a directory above it, is ignored
+    pub fn is_ignored(&self, path: &str) -> bool {
+        let components: Vec<&str> = path.split('/').collect();
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.matches(&components) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+impl GitignoreRule {
+    fn matches(&self, components: &[&str]) -> bool {
+        let pattern: Vec<&str> = self.pattern.split('/').collect();
+        // Each ancestor directory, then the file itself
+        (1..=components.len()).any(|depth| {
+            let is_dir = depth < components.len();
+            if self.dir_only && !is_dir {
+                return false;
+            }
+            if self.anchored {
+                components_match(&pattern, &components[..depth])
+            } else {
+                glob_matches(&self.pattern, components[depth - 1])
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_globs() {
+        assert!(path_matches("src/**", "src/a/b.ts"));
+        assert!(path_matches("src/**/*.ts", "src/b.ts"));
+        assert!(path_matches("**/*.test.ts", "src/ui/app.test.ts"));
+        assert!(path_matches("*.test.ts", "src/ui/app.test.ts"));
+        assert!(path_matches("/lib/?.py", "lib/a.py"));
+
+        assert!(!path_matches("src/**", "lib/src/a.ts"));
+        assert!(!path_matches("src/*.ts", "src/a/b.ts"));
+        assert!(!path_matches("*.test.ts", "src/app.ts"));
+    }
+
+    #[test]
+    fn test_gitignore_rules() {
+        let gitignore =
+            Gitignore::parse("# build output\nout/\n*.gen.ts\n/docs/*.py\n!keep.gen.ts\n");
+
+        assert!(gitignore.is_ignored("out/main.js"));
+        assert!(gitignore.is_ignored("pkg/out/main.js"));
+        assert!(!gitignore.is_ignored("src/out"));
+        assert!(gitignore.is_ignored("src/api.gen.ts"));
+        assert!(!gitignore.is_ignored("src/keep.gen.ts"));
+        assert!(gitignore.is_ignored("docs/conf.py"));
+        assert!(!gitignore.is_ignored("pkg/docs/conf.py"));
+    }
+}
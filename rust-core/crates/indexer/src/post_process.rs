@@ -0,0 +1,125 @@
+//! Hooks that enrich each indexed entity before it is stored
+//!
+//! An [`EntityPostProcessor`] sees every entity of a file after parsing and
+//! filtering, and may rewrite it, most usefully by adding `metadata` keys.
+//! Processors listed in [`crate::IndexingConfig::post_processors`] run in
+//! order, after the built-in [`CodeOwners`] tagging, and a configured
+//! [`SecretRedactor`] runs last of all.
+
+use crate::file_filter::Gitignore;
+use code_intelligence_core::CodeEntity;
+pub use code_intelligence_parser::secrets::{SecretRedactor, REDACTED};
+use std::path::Path;
+
+/// Metadata key holding the owners `CODEOWNERS` assigns an entity's file,
+/// separated by spaces, such as `@org/payments @alice`
+pub const OWNERS_KEY: &str = "owners";
+
+/// The file an entity was extracted from
+#[derive(Debug, Clone, Copy)]
+pub struct FileContext<'a> {
+    /// Path the file was read from
+    pub file_path: &'a Path,
+    /// Path relative to the indexed root, joined with `/`, when it lies below it
+    pub relative_path: Option<&'a str>,
+    /// The file's full source
+    pub content: &'a str,
+}
+
+/// Rewrites an entity before it is indexed
+pub trait EntityPostProcessor: std::fmt::Debug + Send + Sync {
+    fn process(&self, entity: &mut CodeEntity, context: &FileContext);
+}
+
+/// Owner rules of a `CODEOWNERS` file, tagging entities under [`OWNERS_KEY`]
+///
+/// Patterns follow `.gitignore` syntax and the last matching rule wins; a
+/// rule without owners leaves its files unowned.
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    rules: Vec<(Gitignore, Vec<String>)>,
+}
+
+impl CodeOwners {
+    /// Places GitHub and GitLab look for the file, in the order they do
+    const LOCATIONS: [&'static str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+    /// The first `CODEOWNERS` found under `root`, if any
+    pub fn load(root: &Path) -> Option<Self> {
+        Self::LOCATIONS
+            .iter()
+            .find_map(|location| std::fs::read_to_string(root.join(location)).ok())
+            .map(|text| Self::parse(&text))
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let rules = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let pattern = fields.next()?;
+                let owners = fields
+                    .take_while(|field| !field.starts_with('#'))
+                    .map(str::to_string)
+                    .collect();
+                Some((Gitignore::parse(pattern), owners))
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Owners of the root-relative `path`, empty when no rule assigns any
+    pub fn owners_of(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(pattern, _)| pattern.is_ignored(path))
+            .map_or(&[], |(_, owners)| owners.as_slice())
+    }
+}
+
+impl EntityPostProcessor for CodeOwners {
+    fn process(&self, entity: &mut CodeEntity, context: &FileContext) {
+        let Some(path) = context.relative_path else {
+            return;
+        };
+        let owners = self.owners_of(path);
+        if !owners.is_empty() {
+            entity
+                .metadata
+                .insert(OWNERS_KEY.to_string(), owners.join(" "));
+        }
+    }
+}
+
+/// Redacts entity `content`, leaving its name and location untouched
+impl EntityPostProcessor for SecretRedactor {
+    fn process(&self, entity: &mut CodeEntity, _context: &FileContext) {
+        entity.content = self.redact(&entity.content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_owners_last_match_wins() {
+        let owners = CodeOwners::parse(
+            "# Default owners\n* @org/core\n/src/payments/ @org/payments @alice # money\n*.md\n",
+        );
+
+        assert_eq!(owners.owners_of("lib/util.ts"), ["@org/core"]);
+        assert_eq!(
+            owners.owners_of("src/payments/charge.ts"),
+            ["@org/payments", "@alice"]
+        );
+        assert_eq!(
+            owners.owners_of("pkg/src/payments/charge.ts"),
+            ["@org/core"]
+        );
+        assert!(owners.owners_of("README.md").is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+//! User-configured regex extraction for files without a Tree-sitter grammar
+//!
+//! Each [`RegexExtractor`] applies to the files its glob selects, with globs
+//! matched against root-relative paths as described in
+//! [`crate::file_filter`]. Every match of its pattern becomes one entity
+//! named by the pattern's `name` capture and spanning the whole match.
+
+use anyhow::Result;
+use code_intelligence_parser::{CodeEntity, EntityType};
+use regex::Regex;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::file_filter::path_matches;
+
+/// A pattern extracting entities of one type from the files matching a glob
+#[derive(Debug, Clone)]
+pub struct RegexExtractor {
+    file_glob: String,
+    pattern: Regex,
+    entity_type: EntityType,
+}
+
+impl RegexExtractor {
+    /// Compile `pattern`, which must have a capture group named `name`
+    pub fn new(file_glob: &str, pattern: &str, entity_type: EntityType) -> Result<Self> {
+        let pattern = Regex::new(pattern)
+            .map_err(|e| anyhow::anyhow!("Invalid extractor pattern for {}: {}", file_glob, e))?;
+        if !pattern.capture_names().any(|name| name == Some("name")) {
+            anyhow::bail!(
+                "Extractor pattern for {} has no `name` capture group: {}",
+                file_glob,
+                pattern.as_str()
+            );
+        }
+        Ok(Self {
+            file_glob: file_glob.to_string(),
+            pattern,
+            entity_type,
+        })
+    }
+
+    /// Whether the extractor applies to the root-relative `path`
+    pub fn matches_file(&self, path: &str) -> bool {
+        path_matches(&self.file_glob, path)
+    }
+
+    /// An entity for every match in `source` whose `name` capture is non-empty
+    pub fn extract(&self, file_path: &str, source: &str) -> Vec<CodeEntity> {
+        self.pattern
+            .captures_iter(source)
+            .filter_map(|captures| {
+                let name = captures.name("name")?.as_str().trim();
+                let whole = captures.get(0)?;
+                if name.is_empty() {
+                    return None;
+                }
+                let (start_line, start_column) = position(source, whole.start());
+                let (end_line, end_column) = position(source, whole.end());
+                Some(CodeEntity {
+                    id: Uuid::new_v4(),
+                    parent_id: None,
+                    name: name.to_string(),
+                    entity_type: self.entity_type.clone(),
+                    file_path: file_path.to_string(),
+                    start_line,
+                    end_line,
+                    start_column,
+                    end_column,
+                    start_byte: whole.start() as u32,
+                    end_byte: whole.end() as u32,
+                    content: whole.as_str().to_string(),
+                    signature: None,
+                    documentation: None,
+                    visibility: None,
+                    parameters: Vec::new(),
+                    type_parameters: Vec::new(),
+                    return_type: None,
+                    dependencies: Vec::new(),
+                    metadata: HashMap::new(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// The 1-based line and 0-based byte column of `offset` in `source`
+fn position(source: &str, offset: usize) -> (u32, u32) {
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = offset - before.rfind('\n').map_or(0, |newline| newline + 1);
+    (line as u32, column as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns_are_validated() {
+        assert!(
+            RegexExtractor::new("*.toml", r"^\[(?P<name>[^\]]+)\]", EntityType::Module).is_ok()
+        );
+        assert!(RegexExtractor::new("*.toml", r"^\[([^\]]+)\]", EntityType::Module).is_err());
+        assert!(RegexExtractor::new("*.toml", r"(?P<name>[", EntityType::Module).is_err());
+    }
+
+    #[test]
+    fn test_match_positions() {
+        let extractor =
+            RegexExtractor::new("*.ini", r"(?m)^\[(?P<name>[^\]]*)\]", EntityType::Module).unwrap();
+        let entities = extractor.extract("app.ini", "a = 1\n[server]\nport = 80\n  \n[]\n[db]\n");
+        let spans: Vec<(&str, u32, u32)> = entities
+            .iter()
+            .map(|e| (e.name.as_str(), e.start_line, e.end_column))
+            .collect();
+        assert_eq!(spans, vec![("server", 2, 8), ("db", 6, 4)]);
+        assert_eq!(entities[0].start_byte, 6);
+        assert_eq!(entities[0].content, "[server]");
+    }
+}
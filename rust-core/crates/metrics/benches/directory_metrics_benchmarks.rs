@@ -0,0 +1,60 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+
+use code_intelligence_metrics::{DefaultMetricsService, MetricsConfig, MetricsService};
+
+fn create_source_tree(temp_dir: &TempDir, count: usize) {
+    let body = "    if x > 0 { x -= 1; } else { x += 1; }\n".repeat(200);
+    for i in 0..count {
+        let module = temp_dir.path().join(format!("module_{}", i % 10));
+        std::fs::create_dir_all(&module).unwrap();
+        std::fs::write(
+            module.join(format!("file_{}.rs", i)),
+            format!("fn f(mut x: i32) {{\n{}}}\n", body),
+        )
+        .unwrap();
+    }
+}
+
+fn benchmark_directory_concurrency(c: &mut Criterion) {
+    let mut group = c.benchmark_group("directory_metrics");
+
+    let rt = Runtime::new().unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    create_source_tree(&temp_dir, 500);
+    let directory = temp_dir.path().to_string_lossy().to_string();
+
+    for concurrency in [1, 2, 4, 8].iter() {
+        group.bench_with_input(
+            BenchmarkId::new("concurrency", concurrency),
+            concurrency,
+            |b, &concurrency| {
+                b.iter(|| {
+                    rt.block_on(async {
+                        // Caching is off so every iteration measures every file
+                        let mut service = DefaultMetricsService::new();
+                        service
+                            .initialize(MetricsConfig {
+                                directory_concurrency: concurrency,
+                                cache_results: false,
+                                ..Default::default()
+                            })
+                            .await
+                            .unwrap();
+                        service
+                            .calculate_directory_metrics(&directory)
+                            .await
+                            .unwrap()
+                    })
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_directory_concurrency);
+
+criterion_main!(benches);
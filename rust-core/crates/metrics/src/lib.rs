@@ -1,10 +1,24 @@
-use crate::{Result, Error};
-use crate::models::{CodeMetric, MetricType, MetricThreshold, MetricSummary, MetricIssue, IssueSeverity};
-use serde::{Deserialize, Serialize};
+//! Code quality metrics for Code Intelligence MCP Server
+//!
+//! [`DefaultMetricsService`] measures source files for complexity,
+//! maintainability, Halstead counts and test coverage, reading coverage from
+//! an LCOV, Cobertura or coverage.py report, and aggregates files per directory.
+
+pub mod models;
+
+pub use models::{
+    CodeMetric, IssueSeverity, MetricIssue, MetricSummary, MetricThreshold, MetricType,
+};
+
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use code_intelligence_parser::halstead::{self, EntityHalstead, HalsteadMetrics};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsConfig {
@@ -16,6 +30,43 @@ pub struct MetricsConfig {
     pub enable_trending: bool,
     pub trend_period_days: u32,
     pub custom_calculators: Vec<CustomMetricCalculator>,
+    /// Coverage report used for `TestCoverage`; without one a placeholder is reported
+    pub coverage_source: Option<CoverageSource>,
+    /// Files measured at once by `calculate_directory_metrics`; 1 measures them in turn
+    #[serde(default = "default_directory_concurrency")]
+    pub directory_concurrency: usize,
+}
+
+fn default_directory_concurrency() -> usize {
+    num_cpus::get()
+}
+
+/// A test coverage report on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CoverageSource {
+    /// LCOV tracefile (`lcov.info`)
+    Lcov(PathBuf),
+    /// Cobertura XML (`coverage.xml`)
+    Cobertura(PathBuf),
+    /// `coverage.py` JSON (`coverage json`)
+    CoveragePy(PathBuf),
+}
+
+/// Parses a coverage report into per-file line coverage percentages
+type CoverageParser = fn(&str) -> Result<HashMap<String, f64>>;
+
+impl CoverageSource {
+    /// Read the report into per-file line coverage percentages
+    pub fn load(&self) -> Result<HashMap<String, f64>> {
+        let (path, parse): (&Path, CoverageParser) = match self {
+            CoverageSource::Lcov(path) => (path, parse_lcov),
+            CoverageSource::Cobertura(path) => (path, parse_cobertura),
+            CoverageSource::CoveragePy(path) => (path, parse_coverage_py),
+        };
+        let report = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read coverage report {}: {}", path.display(), e))?;
+        parse(&report)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,6 +144,15 @@ pub struct FileMetrics {
     pub overall_score: f64,
     pub issues: Vec<MetricIssue>,
     pub last_modified: DateTime<Utc>,
+    /// Line coverage percentage
+    pub test_coverage: f64,
+    /// Halstead metrics of the whole file, when `HalsteadComplexity` is an
+    /// enabled metric and the file's language has a grammar
+    #[serde(default)]
+    pub halstead: Option<HalsteadMetrics>,
+    /// Halstead metrics of each function and method, in source order
+    #[serde(default)]
+    pub entity_halstead: Vec<EntityHalstead>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -117,7 +177,7 @@ pub struct TrendAnalysis {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrendData {
     pub direction: TrendDirection,
-    pub magnitude: f64, // Percentage change
+    pub magnitude: f64,  // Percentage change
     pub confidence: f64, // Statistical confidence 0-1
     pub data_points: Vec<DataPoint>,
 }
@@ -232,9 +292,9 @@ pub struct ProjectMetric {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProjectSize {
-    Small,   // < 10K LOC
-    Medium,  // 10K-100K LOC
-    Large,   // 100K-1M LOC
+    Small,      // < 10K LOC
+    Medium,     // 10K-100K LOC
+    Large,      // 100K-1M LOC
     Enterprise, // > 1M LOC
 }
 
@@ -311,7 +371,12 @@ pub trait MetricsService: Send + Sync {
     async fn generate_report(&self, codebase_id: &str, period_days: u32) -> Result<MetricsReport>;
 
     /// Get historical metrics data
-    async fn get_historical_metrics(&self, codebase_id: &str, metric_type: MetricType, days: u32) -> Result<Vec<DataPoint>>;
+    async fn get_historical_metrics(
+        &self,
+        codebase_id: &str,
+        metric_type: MetricType,
+        days: u32,
+    ) -> Result<Vec<DataPoint>>;
 
     /// Get metrics trends
     async fn get_metrics_trends(&self, codebase_id: &str, days: u32) -> Result<TrendAnalysis>;
@@ -334,190 +399,93 @@ pub trait MetricsService: Send + Sync {
 
 pub struct DefaultMetricsService {
     config: Option<MetricsConfig>,
-    metrics_cache: HashMap<String, FileMetrics>,
+    /// Measured files by path, filled when `MetricsConfig::cache_results` is set
+    metrics_cache: RwLock<HashMap<String, FileMetrics>>,
     benchmarks: HashMap<MetricType, BenchmarkData>,
+    /// Per-file coverage from `MetricsConfig::coverage_source`, loaded on initialize
+    coverage: Option<HashMap<String, f64>>,
 }
 
 impl DefaultMetricsService {
     pub fn new() -> Self {
         Self {
             config: None,
-            metrics_cache: HashMap::new(),
+            metrics_cache: RwLock::new(HashMap::new()),
             benchmarks: Self::load_default_benchmarks(),
+            coverage: None,
         }
     }
 
-    fn load_default_benchmarks() -> HashMap<MetricType, BenchmarkData> {
-        let mut benchmarks = HashMap::new();
-
-        // Industry benchmarks for various metrics
-        benchmarks.insert(MetricType::CyclomaticComplexity, BenchmarkData {
-            industry_average: 10.0,
-            industry_best: 3.0,
-            percentile_25: 5.0,
-            percentile_75: 15.0,
-            source: "Software Engineering Institute".to_string(),
-            last_updated: Utc::now(),
-        });
-
-        benchmarks.insert(MetricType::MaintainabilityIndex, BenchmarkData {
-            industry_average: 70.0,
-            industry_best: 90.0,
-            percentile_25: 60.0,
-            percentile_75: 80.0,
-            source: "Microsoft DevLabs".to_string(),
-            last_updated: Utc::now(),
-        });
-
-        benchmarks.insert(MetricType::TestCoverage, BenchmarkData {
-            industry_average: 80.0,
-            industry_best: 95.0,
-            percentile_25: 70.0,
-            percentile_75: 90.0,
-            source: "Code Climate".to_string(),
-            last_updated: Utc::now(),
-        });
-
-        benchmarks
-    }
-
-    fn calculate_cyclomatic_complexity(&self, content: &str) -> f64 {
-        let mut complexity = 1.0; // Base complexity
-        
-        // Count decision points
-        complexity += content.matches("if").count() as f64;
-        complexity += content.matches("else").count() as f64;
-        complexity += content.matches("for").count() as f64;
-        complexity += content.matches("while").count() as f64;
-        complexity += content.matches("do").count() as f64;
-        complexity += content.matches("switch").count() as f64;
-        complexity += content.matches("case").count() as f64;
-        complexity += content.matches("catch").count() as f64;
-        complexity += content.matches("&&").count() as f64;
-        complexity += content.matches("||").count() as f64;
-
-        complexity
-    }
-
-    fn calculate_maintainability_index(&self, complexity: f64, loc: u32, comments: u32) -> f64 {
-        let volume = loc as f64 * 2.0f64.log2();
-        let comment_ratio = if loc > 0 { comments as f64 / loc as f64 } else { 0.0 };
-        
-        // Maintainability Index formula (simplified)
-        let mi = 171.0 
-            - 5.2 * volume.ln()
-            - 0.23 * complexity
-            - 16.2 * volume.ln()
-            + 50.0 * comment_ratio.sqrt();
-
-        mi.max(0.0).min(100.0)
-    }
-
-    fn calculate_test_coverage(&self, _content: &str) -> f64 {
-        // This would require test execution coverage data
-        // For now, return a placeholder
-        75.0
-    }
-
-    fn calculate_technical_debt(&self, complexity: f64, loc: u32, issues: &[MetricIssue]) -> f64 {
-        let base_debt = complexity * 0.5; // Hours per complexity point
-        let loc_debt = loc as f64 * 0.01; // 0.01 hours per line of code
-        let issue_debt = issues.len() as f64 * 2.0; // 2 hours per issue
-        
-        base_debt + loc_debt + issue_debt
-    }
-
-    fn generate_recommendations(&self, file_metrics: &[FileMetrics]) -> Vec<Recommendation> {
-        let mut recommendations = Vec::new();
-
-        // Analyze all files for common issues
-        let high_complexity_files: Vec<_> = file_metrics
-            .iter()
-            .filter(|f| {
-                f.metrics
-                    .iter()
-                    .any(|m| matches!(m.metric_type, MetricType::CyclomaticComplexity) && m.value > 15.0)
-            })
-            .collect();
-
-        if !high_complexity_files.is_empty() {
-            recommendations.push(Recommendation {
-                id: "refactor-complexity".to_string(),
-                priority: Priority::High,
-                category: RecommendationCategory::Complexity,
-                title: "Reduce Cyclomatic Complexity".to_string(),
-                description: format!(
-                    "Found {} files with high cyclomatic complexity (>15). Consider refactoring these files to improve maintainability.",
-                    high_complexity_files.len()
-                ),
-                affected_files: high_complexity_files.iter().map(|f| f.file_path.clone()).collect(),
-                estimated_effort: EffortEstimate {
-                    hours_min: 8,
-                    hours_max: 40,
-                    confidence: 0.7,
-                    factors: vec!["File size".to_string(), "Complexity level".to_string()],
-                },
-                impact_assessment: ImpactAssessment {
-                    quality_improvement: 0.8,
-                    maintainability_improvement: 0.9,
-                    risk_reduction: 0.6,
-                    developer_productivity_gain: 0.7,
-                },
-                action_steps: vec![
-                    "Extract complex methods into smaller functions".to_string(),
-                    "Use design patterns to simplify control flow".to_string(),
-                    "Consider state machines for complex state logic".to_string(),
-                    "Add unit tests before refactoring".to_string(),
-                ],
-                resources: vec![
-                    "Refactoring: Improving the Design of Existing Code".to_string(),
-                    "Clean Code: A Handbook of Agile Software Craftsmanship".to_string(),
-                ],
-            });
+    /// Metrics for one file, from the cache when it holds them
+    ///
+    /// Synchronous so that `calculate_directory_metrics` can measure files on
+    /// several threads; the cache lock is not held while a file is measured.
+    fn file_metrics(&self, file_path: &str) -> Result<FileMetrics> {
+        if let Some(cached) = self
+            .metrics_cache
+            .read()
+            .map_err(|_| anyhow!("Metrics cache lock poisoned"))?
+            .get(file_path)
+        {
+            return Ok(cached.clone());
         }
 
-        // Add more recommendation types based on other metrics
-        recommendations
-    }
-}
-
-#[async_trait]
-impl MetricsService for DefaultMetricsService {
-    async fn initialize(&mut self, config: MetricsConfig) -> Result<()> {
-        self.validate_config(&config)?;
-        self.config = Some(config);
-        Ok(())
-    }
-
-    async fn calculate_file_metrics(&self, file_path: &str) -> Result<FileMetrics> {
-        // Check cache first
-        if let Some(cached) = self.metrics_cache.get(file_path) {
-            return Ok(cached.clone());
+        let file_metrics = self.measure_file(file_path)?;
+        if self
+            .config
+            .as_ref()
+            .is_some_and(|config| config.cache_results)
+        {
+            self.metrics_cache
+                .write()
+                .map_err(|_| anyhow!("Metrics cache lock poisoned"))?
+                .insert(file_path.to_string(), file_metrics.clone());
         }
+        Ok(file_metrics)
+    }
 
+    fn measure_file(&self, file_path: &str) -> Result<FileMetrics> {
         let content = std::fs::read_to_string(file_path)
-            .map_err(|e| Error::msg(format!("Failed to read file: {}", e)))?;
+            .map_err(|e| anyhow!("Failed to read file: {}", e))?;
 
         let metadata = std::fs::metadata(file_path)
-            .map_err(|e| Error::msg(format!("Failed to read file metadata: {}", e)))?;
+            .map_err(|e| anyhow!("Failed to read file metadata: {}", e))?;
 
         let lines: Vec<&str> = content.lines().collect();
         let total_lines = lines.len() as u32;
-        let comment_lines = lines.iter()
-            .filter(|line| line.trim().starts_with("//") || line.trim().starts_with("/*") || line.trim().starts_with("*"))
-            .count() as u32;
-        let blank_lines = lines.iter()
-            .filter(|line| line.trim().is_empty())
+        let comment_lines = lines
+            .iter()
+            .filter(|line| {
+                line.trim().starts_with("//")
+                    || line.trim().starts_with("/*")
+                    || line.trim().starts_with("*")
+            })
             .count() as u32;
+        let blank_lines = lines.iter().filter(|line| line.trim().is_empty()).count() as u32;
         let code_lines = total_lines - comment_lines - blank_lines;
 
         // Calculate metrics
         let complexity = self.calculate_cyclomatic_complexity(&content);
-        let maintainability = self.calculate_maintainability_index(complexity, total_lines, comment_lines);
-        let test_coverage = self.calculate_test_coverage(&content);
+        let tree = halstead::parse(file_path, &content);
+        let file_halstead = tree
+            .as_ref()
+            .map(|tree| halstead::measure(tree.root_node(), &content));
+        // Files without a grammar fall back to one unit of volume per line
+        let volume = file_halstead
+            .as_ref()
+            .map_or(total_lines as f64, |halstead| halstead.volume);
+        let maintainability =
+            self.calculate_maintainability_index(volume, complexity, code_lines, comment_lines);
+        let test_coverage = self.calculate_test_coverage(file_path);
+        let halstead_enabled = self.config.as_ref().is_some_and(|config| {
+            config
+                .enabled_metrics
+                .iter()
+                .any(|metric| matches!(metric, MetricType::HalsteadComplexity))
+        });
 
         let mut metrics = Vec::new();
-        
+
         metrics.push(CodeMetric::new(
             format!("metric-{}-complexity", file_path),
             format!("file-{}", file_path),
@@ -548,13 +516,32 @@ impl MetricsService for DefaultMetricsService {
             "percentage".to_string(),
         ));
 
+        let (halstead, entity_halstead) = match (&tree, file_halstead) {
+            (Some(tree), Some(file_halstead)) if halstead_enabled => {
+                metrics.push(CodeMetric::new(
+                    format!("metric-{}-halstead", file_path),
+                    format!("file-{}", file_path),
+                    "file".to_string(),
+                    file_path.to_string(),
+                    MetricType::HalsteadComplexity,
+                    file_halstead.volume,
+                    "volume".to_string(),
+                ));
+                (
+                    Some(file_halstead),
+                    halstead::measure_entities(tree, &content),
+                )
+            }
+            _ => (None, Vec::new()),
+        };
+
         // Generate issues based on thresholds
         let thresholds = MetricThreshold::standard_defaults();
         let mut issues = Vec::new();
 
         for metric in &metrics {
             for threshold in &thresholds {
-                if matches!(metric.metric_type, threshold.metric_type) {
+                if metric.metric_type == threshold.metric_type {
                     if metric.value > threshold.error_threshold {
                         issues.push(MetricIssue {
                             metric_type: metric.metric_type.clone(),
@@ -562,8 +549,8 @@ impl MetricsService for DefaultMetricsService {
                             value: metric.value,
                             threshold: threshold.error_threshold,
                             message: format!(
-                                "{} exceeds error threshold: {:.2} > {:.2} {}",
-                                format!("{:?}", metric.metric_type),
+                                "{:?} exceeds error threshold: {:.2} > {:.2} {}",
+                                metric.metric_type,
                                 metric.value,
                                 threshold.error_threshold,
                                 threshold.unit
@@ -577,8 +564,8 @@ impl MetricsService for DefaultMetricsService {
                             value: metric.value,
                             threshold: threshold.warning_threshold,
                             message: format!(
-                                "{} exceeds warning threshold: {:.2} > {:.2} {}",
-                                format!("{:?}", metric.metric_type),
+                                "{:?} exceeds warning threshold: {:.2} > {:.2} {}",
+                                metric.metric_type,
                                 metric.value,
                                 threshold.warning_threshold,
                                 threshold.unit
@@ -591,7 +578,7 @@ impl MetricsService for DefaultMetricsService {
         }
 
         let technical_debt = self.calculate_technical_debt(complexity, total_lines, &issues);
-        
+
         metrics.push(CodeMetric::new(
             format!("metric-{}-debt", file_path),
             format!("file-{}", file_path),
@@ -614,45 +601,262 @@ impl MetricsService for DefaultMetricsService {
             metrics,
             overall_score,
             issues,
-            last_modified: metadata.modified()
-                .map_err(|e| Error::msg(format!("Failed to get modification time: {}", e)))?
+            last_modified: metadata
+                .modified()
+                .map_err(|e| anyhow!("Failed to get modification time: {}", e))?
                 .into(),
+            test_coverage,
+            halstead,
+            entity_halstead,
         };
 
         Ok(file_metrics)
     }
 
+    fn load_default_benchmarks() -> HashMap<MetricType, BenchmarkData> {
+        let mut benchmarks = HashMap::new();
+
+        // Industry benchmarks for various metrics
+        benchmarks.insert(
+            MetricType::CyclomaticComplexity,
+            BenchmarkData {
+                industry_average: 10.0,
+                industry_best: 3.0,
+                percentile_25: 5.0,
+                percentile_75: 15.0,
+                source: "Software Engineering Institute".to_string(),
+                last_updated: Utc::now(),
+            },
+        );
+
+        benchmarks.insert(
+            MetricType::MaintainabilityIndex,
+            BenchmarkData {
+                industry_average: 70.0,
+                industry_best: 90.0,
+                percentile_25: 60.0,
+                percentile_75: 80.0,
+                source: "Microsoft DevLabs".to_string(),
+                last_updated: Utc::now(),
+            },
+        );
+
+        benchmarks.insert(
+            MetricType::TestCoverage,
+            BenchmarkData {
+                industry_average: 80.0,
+                industry_best: 95.0,
+                percentile_25: 70.0,
+                percentile_75: 90.0,
+                source: "Code Climate".to_string(),
+                last_updated: Utc::now(),
+            },
+        );
+
+        benchmarks
+    }
+
+    fn calculate_cyclomatic_complexity(&self, content: &str) -> f64 {
+        let mut complexity = 1.0; // Base complexity
+
+        // Count decision points
+        complexity += content.matches("if").count() as f64;
+        complexity += content.matches("else").count() as f64;
+        complexity += content.matches("for").count() as f64;
+        complexity += content.matches("while").count() as f64;
+        complexity += content.matches("do").count() as f64;
+        complexity += content.matches("switch").count() as f64;
+        complexity += content.matches("case").count() as f64;
+        complexity += content.matches("catch").count() as f64;
+        complexity += content.matches("&&").count() as f64;
+        complexity += content.matches("||").count() as f64;
+
+        complexity
+    }
+
+    /// The SEI maintainability index from Halstead `volume`, cyclomatic
+    /// complexity, lines of code and comment lines, clamped to 0-100
+    fn calculate_maintainability_index(
+        &self,
+        volume: f64,
+        complexity: f64,
+        loc: u32,
+        comments: u32,
+    ) -> f64 {
+        let comment_ratio = if loc > 0 {
+            comments as f64 / loc as f64
+        } else {
+            0.0
+        };
+
+        let mi = 171.0
+            - 5.2 * volume.max(1.0).ln()
+            - 0.23 * complexity
+            - 16.2 * (loc.max(1) as f64).ln()
+            + 50.0 * (2.4 * comment_ratio).sqrt().sin();
+
+        mi.clamp(0.0, 100.0)
+    }
+
+    fn calculate_test_coverage(&self, file_path: &str) -> f64 {
+        let Some(coverage) = &self.coverage else {
+            // No coverage report configured; report a placeholder
+            return 75.0;
+        };
+
+        // Reports usually hold paths relative to the project root
+        coverage
+            .get(file_path)
+            .or_else(|| {
+                coverage
+                    .iter()
+                    .find(|(reported, _)| Path::new(file_path).ends_with(reported.as_str()))
+                    .map(|(_, percentage)| percentage)
+            })
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    fn calculate_technical_debt(&self, complexity: f64, loc: u32, issues: &[MetricIssue]) -> f64 {
+        let base_debt = complexity * 0.5; // Hours per complexity point
+        let loc_debt = loc as f64 * 0.01; // 0.01 hours per line of code
+        let issue_debt = issues.len() as f64 * 2.0; // 2 hours per issue
+
+        base_debt + loc_debt + issue_debt
+    }
+
+    fn generate_recommendations(&self, file_metrics: &[FileMetrics]) -> Vec<Recommendation> {
+        let mut recommendations = Vec::new();
+
+        // Analyze all files for common issues
+        let high_complexity_files: Vec<_> = file_metrics
+            .iter()
+            .filter(|f| {
+                f.metrics.iter().any(|m| {
+                    matches!(m.metric_type, MetricType::CyclomaticComplexity) && m.value > 15.0
+                })
+            })
+            .collect();
+
+        if !high_complexity_files.is_empty() {
+            recommendations.push(Recommendation {
+                id: "refactor-complexity".to_string(),
+                priority: Priority::High,
+                category: RecommendationCategory::Complexity,
+                title: "Reduce Cyclomatic Complexity".to_string(),
+                description: format!(
+                    "Found {} files with high cyclomatic complexity (>15). Consider refactoring these files to improve maintainability.",
+                    high_complexity_files.len()
+                ),
+                affected_files: high_complexity_files.iter().map(|f| f.file_path.clone()).collect(),
+                estimated_effort: EffortEstimate {
+                    hours_min: 8,
+                    hours_max: 40,
+                    confidence: 0.7,
+                    factors: vec!["File size".to_string(), "Complexity level".to_string()],
+                },
+                impact_assessment: ImpactAssessment {
+                    quality_improvement: 0.8,
+                    maintainability_improvement: 0.9,
+                    risk_reduction: 0.6,
+                    developer_productivity_gain: 0.7,
+                },
+                action_steps: vec![
+                    "Extract complex methods into smaller functions".to_string(),
+                    "Use design patterns to simplify control flow".to_string(),
+                    "Consider state machines for complex state logic".to_string(),
+                    "Add unit tests before refactoring".to_string(),
+                ],
+                resources: vec![
+                    "Refactoring: Improving the Design of Existing Code".to_string(),
+                    "Clean Code: A Handbook of Agile Software Craftsmanship".to_string(),
+                ],
+            });
+        }
+
+        // Add more recommendation types based on other metrics
+        recommendations
+    }
+}
+
+impl Default for DefaultMetricsService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl MetricsService for DefaultMetricsService {
+    async fn initialize(&mut self, config: MetricsConfig) -> Result<()> {
+        self.validate_config(&config)?;
+        self.coverage = config
+            .coverage_source
+            .as_ref()
+            .map(CoverageSource::load)
+            .transpose()?;
+        self.config = Some(config);
+        Ok(())
+    }
+
+    async fn calculate_file_metrics(&self, file_path: &str) -> Result<FileMetrics> {
+        self.file_metrics(file_path)
+    }
+
     async fn calculate_directory_metrics(&self, directory_path: &str) -> Result<DirectoryMetrics> {
+        // Only source code files are measured
+        let files: Vec<String> = walkdir::WalkDir::new(directory_path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|entry| entry.path().to_string_lossy().to_string())
+            .filter(|file_path| self.is_source_code_file(file_path))
+            .collect();
+
+        // Results stay in walk order, so the aggregates below do not depend
+        // on the concurrency
+        let concurrency = self
+            .config
+            .as_ref()
+            .map_or_else(default_directory_concurrency, |config| {
+                config.directory_concurrency
+            })
+            .max(1);
+        let measured: Vec<Result<FileMetrics>> = if concurrency == 1 {
+            files
+                .iter()
+                .map(|file_path| self.file_metrics(file_path))
+                .collect()
+        } else {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(concurrency)
+                .build()
+                .map_err(|e| anyhow!("Failed to start metrics threads: {}", e))?
+                .install(|| {
+                    files
+                        .par_iter()
+                        .map(|file_path| self.file_metrics(file_path))
+                        .collect()
+                })
+        };
+
         let mut file_count = 0;
         let mut total_lines = 0;
         let mut total_entities = 0;
         let mut file_scores = Vec::new();
         let mut average_metrics: HashMap<MetricType, Vec<f64>> = HashMap::new();
 
-        // Walk through directory
-        for entry in walkdir::WalkDir::new(directory_path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
-        {
-            let file_path = entry.path().to_string_lossy().to_string();
-            
-            // Only process source code files
-            if self.is_source_code_file(&file_path) {
-                if let Ok(file_metrics) = self.calculate_file_metrics(&file_path).await {
-                    file_count += 1;
-                    total_lines += file_metrics.lines_of_code as u64;
-                    total_entities += file_metrics.entities_count as u64;
-                    file_scores.push((file_metrics.file_path.clone(), file_metrics.overall_score));
-
-                    // Collect metric values for averaging
-                    for metric in &file_metrics.metrics {
-                        average_metrics
-                            .entry(metric.metric_type.clone())
-                            .or_insert_with(Vec::new)
-                            .push(metric.value);
-                    }
-                }
+        for file_metrics in measured.into_iter().flatten() {
+            file_count += 1;
+            total_lines += file_metrics.lines_of_code as u64;
+            total_entities += file_metrics.entities_count as u64;
+            file_scores.push((file_metrics.file_path.clone(), file_metrics.overall_score));
+
+            // Collect metric values for averaging
+            for metric in &file_metrics.metrics {
+                average_metrics
+                    .entry(metric.metric_type.clone())
+                    .or_default()
+                    .push(metric.value);
             }
         }
 
@@ -668,8 +872,17 @@ impl MetricsService for DefaultMetricsService {
 
         // Sort files by score
         file_scores.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-        let worst_files = file_scores.iter().take(10).map(|(path, _)| path.clone()).collect();
-        let best_files = file_scores.iter().rev().take(10).map(|(path, _)| path.clone()).collect();
+        let worst_files = file_scores
+            .iter()
+            .take(10)
+            .map(|(path, _)| path.clone())
+            .collect();
+        let best_files = file_scores
+            .iter()
+            .rev()
+            .take(10)
+            .map(|(path, _)| path.clone())
+            .collect();
 
         Ok(DirectoryMetrics {
             directory_path: directory_path.to_string(),
@@ -713,7 +926,12 @@ impl MetricsService for DefaultMetricsService {
         })
     }
 
-    async fn get_historical_metrics(&self, _codebase_id: &str, _metric_type: MetricType, _days: u32) -> Result<Vec<DataPoint>> {
+    async fn get_historical_metrics(
+        &self,
+        _codebase_id: &str,
+        _metric_type: MetricType,
+        _days: u32,
+    ) -> Result<Vec<DataPoint>> {
         // This would typically fetch from a database
         Ok(Vec::new())
     }
@@ -728,10 +946,14 @@ impl MetricsService for DefaultMetricsService {
         })
     }
 
-    async fn get_recommendations(&self, codebase_id: &str) -> Result<Vec<Recommendation>> {
-        // Generate recommendations based on codebase metrics
-        // For now, return empty recommendations
-        Ok(Vec::new())
+    async fn get_recommendations(&self, _codebase_id: &str) -> Result<Vec<Recommendation>> {
+        // Based on the files measured so far
+        let cache = self
+            .metrics_cache
+            .read()
+            .map_err(|_| anyhow!("Metrics cache lock poisoned"))?;
+        let file_metrics: Vec<FileMetrics> = cache.values().cloned().collect();
+        Ok(self.generate_recommendations(&file_metrics))
     }
 
     async fn benchmark_comparison(&self, _codebase_id: &str) -> Result<BenchmarkComparison> {
@@ -769,11 +991,11 @@ impl MetricsService for DefaultMetricsService {
 
     fn validate_config(&self, config: &MetricsConfig) -> Result<()> {
         if config.enabled_metrics.is_empty() {
-            return Err(Error::msg("At least one metric must be enabled"));
+            return Err(anyhow!("At least one metric must be enabled"));
         }
 
         if config.cache_ttl_hours == 0 {
-            return Err(Error::msg("Cache TTL must be greater than 0"));
+            return Err(anyhow!("Cache TTL must be greater than 0"));
         }
 
         Ok(())
@@ -782,16 +1004,20 @@ impl MetricsService for DefaultMetricsService {
 
 impl DefaultMetricsService {
     fn is_source_code_file(&self, file_path: &str) -> bool {
-        let extensions = [".js", ".ts", ".py", ".rs", ".cpp", ".hpp", ".c", ".h", ".java", ".go"];
+        let extensions = ["js", "ts", "py", "rs", "cpp", "hpp", "c", "h", "java", "go"];
         let path = Path::new(file_path);
-        
+
         path.extension()
             .and_then(|ext| ext.to_str())
             .map(|ext| extensions.contains(&ext))
             .unwrap_or(false)
     }
 
-    fn calculate_overall_score(&self, metrics: &[CodeMetric], thresholds: &[MetricThreshold]) -> f64 {
+    fn calculate_overall_score(
+        &self,
+        metrics: &[CodeMetric],
+        thresholds: &[MetricThreshold],
+    ) -> f64 {
         if metrics.is_empty() {
             return 0.0;
         }
@@ -800,9 +1026,10 @@ impl DefaultMetricsService {
         let mut weight_sum = 0.0;
 
         for metric in metrics {
-            let threshold = thresholds.iter()
-                .find(|t| matches!(metric.metric_type, t.metric_type));
-            
+            let threshold = thresholds
+                .iter()
+                .find(|t| metric.metric_type == t.metric_type);
+
             if let Some(threshold) = threshold {
                 let weight = match metric.metric_type {
                     MetricType::MaintainabilityIndex | MetricType::TestCoverage => 2.0,
@@ -834,7 +1061,7 @@ impl DefaultMetricsService {
                 } else {
                     30.0
                 }
-            },
+            }
             _ => {
                 // Lower is better (complexity metrics)
                 if metric.value <= threshold.warning_threshold {
@@ -851,16 +1078,18 @@ impl DefaultMetricsService {
     fn get_suggestion_for_metric(&self, metric_type: &MetricType) -> Option<String> {
         match metric_type {
             MetricType::CyclomaticComplexity => Some(
-                "Consider breaking down complex functions into smaller, more focused functions.".to_string()
-            ),
-            MetricType::MaintainabilityIndex => Some(
-                "Refactor code to improve structure and reduce complexity.".to_string()
-            ),
-            MetricType::TestCoverage => Some(
-                "Add unit tests to increase code coverage and ensure reliability.".to_string()
+                "Consider breaking down complex functions into smaller, more focused functions."
+                    .to_string(),
             ),
+            MetricType::MaintainabilityIndex => {
+                Some("Refactor code to improve structure and reduce complexity.".to_string())
+            }
+            MetricType::TestCoverage => {
+                Some("Add unit tests to increase code coverage and ensure reliability.".to_string())
+            }
             MetricType::TechnicalDebt => Some(
-                "Address technical debt items to improve code quality and maintainability.".to_string()
+                "Address technical debt items to improve code quality and maintainability."
+                    .to_string(),
             ),
             _ => None,
         }
@@ -883,10 +1112,123 @@ impl Default for MetricsConfig {
             enable_trending: true,
             trend_period_days: 30,
             custom_calculators: Vec::new(),
+            coverage_source: None,
+            directory_concurrency: default_directory_concurrency(),
         }
     }
 }
 
+fn percentage(covered: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        covered / total * 100.0
+    } else {
+        100.0
+    }
+}
+
+/// Parse an LCOV tracefile, using `LH`/`LF` totals or counting `DA` records
+fn parse_lcov(report: &str) -> Result<HashMap<String, f64>> {
+    let mut coverage = HashMap::new();
+    let mut file: Option<String> = None;
+    let (mut hit, mut found) = (0u32, 0u32);
+    let (mut da_hit, mut da_found) = (0u32, 0u32);
+
+    for line in report.lines().map(str::trim) {
+        if let Some(path) = line.strip_prefix("SF:") {
+            file = Some(path.to_string());
+            (hit, found, da_hit, da_found) = (0, 0, 0, 0);
+        } else if let Some(count) = line.strip_prefix("LH:") {
+            hit = count.parse().unwrap_or(0);
+        } else if let Some(count) = line.strip_prefix("LF:") {
+            found = count.parse().unwrap_or(0);
+        } else if let Some(record) = line.strip_prefix("DA:") {
+            da_found += 1;
+            let executions = record.split(',').nth(1).unwrap_or("0");
+            if executions.parse::<u64>().unwrap_or(0) > 0 {
+                da_hit += 1;
+            }
+        } else if line == "end_of_record" {
+            let path = file
+                .take()
+                .ok_or_else(|| anyhow!("LCOV record ended before its SF line"))?;
+            let (hit, found) = if found > 0 {
+                (hit, found)
+            } else {
+                (da_hit, da_found)
+            };
+            coverage.insert(path, percentage(hit as f64, found as f64));
+        }
+    }
+
+    Ok(coverage)
+}
+
+/// Parse Cobertura XML from the `filename` and `line-rate` of each `<class>`
+///
+/// Classes of the same file are averaged, which matches the per-file rate
+/// when the report has one class per file, as most generators emit.
+fn parse_cobertura(report: &str) -> Result<HashMap<String, f64>> {
+    let mut rates: HashMap<String, Vec<f64>> = HashMap::new();
+
+    for element in report.split("<class ").skip(1) {
+        let tag = element.split('>').next().unwrap_or("");
+        let (Some(filename), Some(line_rate)) = (
+            xml_attribute(tag, "filename"),
+            xml_attribute(tag, "line-rate"),
+        ) else {
+            continue;
+        };
+        let line_rate: f64 = line_rate.parse().map_err(|_| {
+            anyhow!(
+                "Invalid Cobertura line-rate for {}: {}",
+                filename,
+                line_rate
+            )
+        })?;
+        rates
+            .entry(filename.to_string())
+            .or_default()
+            .push(line_rate * 100.0);
+    }
+
+    Ok(rates
+        .into_iter()
+        .map(|(file, rates)| (file, rates.iter().sum::<f64>() / rates.len() as f64))
+        .collect())
+}
+
+fn xml_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let needle = format!("{}=\"", name);
+    let mut rest = tag;
+    while let Some(index) = rest.find(&needle) {
+        // `rate=` must not match inside `line-rate=`
+        let whole_name = index == 0 || rest[..index].ends_with(char::is_whitespace);
+        let value = &rest[index + needle.len()..];
+        if whole_name {
+            return value.find('"').map(|end| &value[..end]);
+        }
+        rest = value;
+    }
+    None
+}
+
+/// Parse `coverage.py` JSON from `files.<path>.summary.percent_covered`
+fn parse_coverage_py(report: &str) -> Result<HashMap<String, f64>> {
+    let report: serde_json::Value =
+        serde_json::from_str(report).map_err(|e| anyhow!("Invalid coverage.py report: {}", e))?;
+    let files = report["files"]
+        .as_object()
+        .ok_or_else(|| anyhow!("coverage.py report has no files section"))?;
+
+    Ok(files
+        .iter()
+        .filter_map(|(path, file)| {
+            let percent = file["summary"]["percent_covered"].as_f64()?;
+            Some((path.clone(), percent))
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -903,10 +1245,10 @@ mod tests {
     #[test]
     fn test_complexity_calculation() {
         let service = DefaultMetricsService::new();
-        
+
         let simple_code = "function test() { return 42; }";
         assert_eq!(service.calculate_cyclomatic_complexity(simple_code), 1.0);
-        
+
         let complex_code = "if (x) { for (let i = 0; i < 10; i++) { if (y) { doSomething(); } } }";
         assert!(service.calculate_cyclomatic_complexity(complex_code) > 1.0);
     }
@@ -914,9 +1256,56 @@ mod tests {
     #[test]
     fn test_maintainability_index() {
         let service = DefaultMetricsService::new();
-        
-        let mi = service.calculate_maintainability_index(5.0, 100, 20);
-        assert!(mi >= 0.0 && mi <= 100.0);
+
+        let mi = service.calculate_maintainability_index(1500.0, 5.0, 100, 20);
+        assert!((0.0..=100.0).contains(&mi));
+
+        // More volume means less maintainable
+        let bulkier = service.calculate_maintainability_index(6000.0, 5.0, 100, 20);
+        assert!(bulkier < mi);
+    }
+
+    #[tokio::test]
+    async fn test_halstead_metrics_are_opt_in() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("add.rs");
+        std::fs::write(&file_path, "fn add(a: i32, b: i32) -> i32 { a + b }\n").unwrap();
+        let file_path = file_path.to_string_lossy().to_string();
+
+        let mut service = DefaultMetricsService::new();
+        service
+            .initialize(MetricsConfig {
+                cache_results: false,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let measured = service.calculate_file_metrics(&file_path).await.unwrap();
+        assert!(measured.halstead.is_none());
+        assert!(measured.entity_halstead.is_empty());
+
+        let mut config = MetricsConfig {
+            cache_results: false,
+            ..Default::default()
+        };
+        config.enabled_metrics.push(MetricType::HalsteadComplexity);
+        service.initialize(config).await.unwrap();
+        let measured = service.calculate_file_metrics(&file_path).await.unwrap();
+        let add = &measured.entity_halstead[0];
+        assert_eq!(add.name, "add");
+        assert_eq!(
+            (
+                add.metrics.distinct_operators,
+                add.metrics.distinct_operands,
+                add.metrics.total_operators,
+                add.metrics.total_operands
+            ),
+            (7, 4, 8, 8)
+        );
+        assert!(measured
+            .metrics
+            .iter()
+            .any(|m| matches!(m.metric_type, MetricType::HalsteadComplexity)));
     }
 
     #[test]
@@ -928,17 +1317,153 @@ mod tests {
         assert!(matches!(Grade::from_score(55.0), Grade::F));
     }
 
+    const LCOV_FIXTURE: &str = "TN:
+SF:src/lib.rs
+DA:1,4
+DA:2,0
+DA:3,1
+DA:4,1
+LF:4
+LH:3
+end_of_record
+SF:src/util.rs
+DA:1,0
+DA:2,2
+end_of_record
+";
+
+    #[test]
+    fn test_parse_lcov() {
+        let coverage = parse_lcov(LCOV_FIXTURE).unwrap();
+
+        assert_eq!(coverage.len(), 2);
+        assert_eq!(coverage["src/lib.rs"], 75.0);
+        // Without LH/LF totals the DA records are counted
+        assert_eq!(coverage["src/util.rs"], 50.0);
+
+        assert!(parse_lcov("DA:1,1\nend_of_record\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_cobertura_and_coverage_py() {
+        let xml = r#"<coverage><packages><package name="app"><classes>
+            <class name="app.py" filename="app/app.py" line-rate="0.8" branch-rate="0">
+            </class>
+        </classes></package></packages></coverage>"#;
+        assert_eq!(parse_cobertura(xml).unwrap()["app/app.py"], 80.0);
+
+        let json = r#"{"files": {"app/app.py": {"summary": {"percent_covered": 62.5}}}}"#;
+        assert_eq!(parse_coverage_py(json).unwrap()["app/app.py"], 62.5);
+    }
+
+    #[tokio::test]
+    async fn test_file_metrics_use_coverage_report() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let report = dir.path().join("lcov.info");
+        std::fs::write(&report, LCOV_FIXTURE).unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        let source = dir.path().join("src/lib.rs");
+        std::fs::write(&source, "fn main() {}\n").unwrap();
+
+        let mut service = DefaultMetricsService::new();
+        service
+            .initialize(MetricsConfig {
+                coverage_source: Some(CoverageSource::Lcov(report)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let metrics = service
+            .calculate_file_metrics(source.to_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(metrics.test_coverage, 75.0);
+        assert_eq!(service.calculate_test_coverage("src/unlisted.rs"), 0.0);
+        assert_eq!(
+            DefaultMetricsService::new().calculate_test_coverage("src/lib.rs"),
+            75.0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parallel_directory_metrics_match_sequential() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for i in 0..24 {
+            let module = dir.path().join(format!("mod{}", i % 3));
+            std::fs::create_dir_all(&module).unwrap();
+            let branches = "    if x > 0 { x -= 1; }\n".repeat(i);
+            std::fs::write(
+                module.join(format!("file{}.rs", i)),
+                format!("// file {}\nfn f(mut x: i32) {{\n{}}}\n", i, branches),
+            )
+            .unwrap();
+        }
+        std::fs::write(dir.path().join("notes.txt"), "not source").unwrap();
+
+        let mut aggregates = Vec::new();
+        for directory_concurrency in [1, 4] {
+            let mut service = DefaultMetricsService::new();
+            service
+                .initialize(MetricsConfig {
+                    directory_concurrency,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            aggregates.push(
+                service
+                    .calculate_directory_metrics(dir.path().to_str().unwrap())
+                    .await
+                    .unwrap(),
+            );
+            // Every measured file is cached for the next call
+            assert_eq!(service.metrics_cache.read().unwrap().len(), 24);
+        }
+
+        let (sequential, parallel) = (&aggregates[0], &aggregates[1]);
+        assert_eq!(sequential.file_count, 24);
+        assert_eq!(parallel.file_count, sequential.file_count);
+        assert_eq!(parallel.total_lines, sequential.total_lines);
+        assert_eq!(parallel.total_entities, sequential.total_entities);
+        assert_eq!(parallel.average_metrics, sequential.average_metrics);
+        assert_eq!(parallel.worst_files, sequential.worst_files);
+        assert_eq!(parallel.best_files, sequential.best_files);
+    }
+
+    #[tokio::test]
+    async fn test_recommendations_cover_measured_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let tangled = dir.path().join("tangled.rs");
+        let branches = "    if x > 0 { x -= 1; }\n".repeat(20);
+        std::fs::write(&tangled, format!("fn f(mut x: i32) {{\n{}}}\n", branches)).unwrap();
+        let tangled = tangled.to_string_lossy().to_string();
+
+        let mut service = DefaultMetricsService::new();
+        service.initialize(MetricsConfig::default()).await.unwrap();
+        assert!(service
+            .get_recommendations("repo")
+            .await
+            .unwrap()
+            .is_empty());
+
+        service.calculate_file_metrics(&tangled).await.unwrap();
+        let recommendations = service.get_recommendations("repo").await.unwrap();
+        assert_eq!(recommendations.len(), 1);
+        assert_eq!(recommendations[0].affected_files, vec![tangled]);
+    }
+
     #[test]
     fn test_config_validation() {
         let service = DefaultMetricsService::new();
-        
+
         let valid_config = MetricsConfig::default();
         assert!(service.validate_config(&valid_config).is_ok());
-        
+
         let invalid_config = MetricsConfig {
             enabled_metrics: vec![],
             ..Default::default()
         };
         assert!(service.validate_config(&invalid_config).is_err());
     }
-}
\ No newline at end of file
+}
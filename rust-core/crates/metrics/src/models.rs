@@ -1,5 +1,7 @@
-use serde::{Deserialize, Serialize};
+//! Metric values, thresholds and the issues they raise
+
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeMetric {
@@ -14,7 +16,7 @@ pub struct CodeMetric {
     pub computed_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MetricType {
     /// Cyclomatic complexity - measures code complexity
     CyclomaticComplexity,
@@ -104,27 +106,27 @@ impl CodeMetric {
     }
 
     pub fn is_critical(&self, threshold: &MetricThreshold) -> bool {
-        matches!(self.metric_type, threshold.metric_type) && self.value > threshold.error_threshold
+        self.metric_type == threshold.metric_type && self.value > threshold.error_threshold
     }
 
     pub fn is_warning(&self, threshold: &MetricThreshold) -> bool {
-        matches!(self.metric_type, threshold.metric_type) && 
-        self.value > threshold.warning_threshold && 
-        self.value <= threshold.error_threshold
+        self.metric_type == threshold.metric_type
+            && self.value > threshold.warning_threshold
+            && self.value <= threshold.error_threshold
     }
 }
 
-impl Default for MetricThreshold {
-    fn default() -> Self {
+impl MetricThreshold {
+    /// The threshold set named by `CODE_QUALITY_MODE` (`strict` or `lenient`),
+    /// standard otherwise
+    pub fn from_env() -> Vec<Self> {
         match std::env::var("CODE_QUALITY_MODE").as_deref() {
-            Some("strict") => Self::strict_defaults(),
-            Some("lenient") => Self::lenient_defaults(),
+            Ok("strict") => Self::strict_defaults(),
+            Ok("lenient") => Self::lenient_defaults(),
             _ => Self::standard_defaults(),
         }
     }
-}
 
-impl MetricThreshold {
     pub fn standard_defaults() -> Vec<Self> {
         vec![
             Self {
@@ -238,11 +240,7 @@ impl MetricThreshold {
 }
 
 impl MetricSummary {
-    pub fn new(
-        entity_id: String,
-        entity_type: String,
-        file_path: String,
-    ) -> Self {
+    pub fn new(entity_id: String, entity_type: String, file_path: String) -> Self {
         Self {
             entity_id,
             entity_type,
@@ -267,9 +265,10 @@ impl MetricSummary {
         let mut weight_sum = 0.0;
 
         for metric in &self.metrics {
-            let threshold = thresholds.iter()
-                .find(|t| matches!(metric.metric_type, t.metric_type));
-            
+            let threshold = thresholds
+                .iter()
+                .find(|t| metric.metric_type == t.metric_type);
+
             if let Some(threshold) = threshold {
                 let weight = match metric.metric_type {
                     MetricType::MaintainabilityIndex | MetricType::TestCoverage => 2.0,
@@ -303,7 +302,7 @@ impl MetricSummary {
                 } else {
                     30.0
                 }
-            },
+            }
             _ => {
                 // Lower is better (complexity metrics)
                 if metric.value <= threshold.warning_threshold {
@@ -319,11 +318,12 @@ impl MetricSummary {
 
     fn generate_issues(&mut self, thresholds: &[MetricThreshold]) {
         self.issues.clear();
-        
+
         for metric in &self.metrics {
-            if let Some(threshold) = thresholds.iter()
-                .find(|t| matches!(metric.metric_type, t.metric_type)) {
-                
+            if let Some(threshold) = thresholds
+                .iter()
+                .find(|t| metric.metric_type == t.metric_type)
+            {
                 if metric.is_critical(threshold) {
                     self.issues.push(MetricIssue {
                         metric_type: metric.metric_type.clone(),
@@ -331,8 +331,8 @@ impl MetricSummary {
                         value: metric.value,
                         threshold: threshold.error_threshold,
                         message: format!(
-                            "{} exceeds error threshold: {:.2} > {:.2} {}",
-                            format!("{:?}", metric.metric_type),
+                            "{:?} exceeds error threshold: {:.2} > {:.2} {}",
+                            metric.metric_type,
                             metric.value,
                             threshold.error_threshold,
                             threshold.unit
@@ -346,8 +346,8 @@ impl MetricSummary {
                         value: metric.value,
                         threshold: threshold.warning_threshold,
                         message: format!(
-                            "{} exceeds warning threshold: {:.2} > {:.2} {}",
-                            format!("{:?}", metric.metric_type),
+                            "{:?} exceeds warning threshold: {:.2} > {:.2} {}",
+                            metric.metric_type,
                             metric.value,
                             threshold.warning_threshold,
                             threshold.unit
@@ -362,22 +362,24 @@ impl MetricSummary {
     fn get_suggestion(&self, metric_type: &MetricType) -> Option<String> {
         match metric_type {
             MetricType::CyclomaticComplexity => Some(
-                "Consider breaking down complex functions into smaller, more focused functions.".to_string()
-            ),
-            MetricType::CognitiveComplexity => Some(
-                "Simplify control flow and reduce nesting to improve readability.".to_string()
-            ),
-            MetricType::MaintainabilityIndex => Some(
-                "Refactor code to improve structure and reduce complexity.".to_string()
-            ),
-            MetricType::TestCoverage => Some(
-                "Add unit tests to increase code coverage and ensure reliability.".to_string()
-            ),
-            MetricType::CodeDuplication => Some(
-                "Extract common code into reusable functions or classes.".to_string()
+                "Consider breaking down complex functions into smaller, more focused functions."
+                    .to_string(),
             ),
+            MetricType::CognitiveComplexity => {
+                Some("Simplify control flow and reduce nesting to improve readability.".to_string())
+            }
+            MetricType::MaintainabilityIndex => {
+                Some("Refactor code to improve structure and reduce complexity.".to_string())
+            }
+            MetricType::TestCoverage => {
+                Some("Add unit tests to increase code coverage and ensure reliability.".to_string())
+            }
+            MetricType::CodeDuplication => {
+                Some("Extract common code into reusable functions or classes.".to_string())
+            }
             MetricType::TechnicalDebt => Some(
-                "Address technical debt items to improve code quality and maintainability.".to_string()
+                "Address technical debt items to improve code quality and maintainability."
+                    .to_string(),
             ),
             _ => None,
         }
@@ -408,7 +410,8 @@ mod tests {
     #[test]
     fn test_metric_thresholds() {
         let thresholds = MetricThreshold::standard_defaults();
-        let complexity_threshold = thresholds.iter()
+        let complexity_threshold = thresholds
+            .iter()
             .find(|t| matches!(t.metric_type, MetricType::CyclomaticComplexity))
             .unwrap();
 
@@ -437,4 +440,45 @@ mod tests {
         summary.add_metric(metric);
         assert_eq!(summary.metrics.len(), 1);
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_thresholds_only_apply_to_their_metric_type() {
+        let thresholds = MetricThreshold::standard_defaults();
+        let complexity = thresholds
+            .iter()
+            .find(|t| t.metric_type == MetricType::CyclomaticComplexity)
+            .unwrap();
+        let lines = CodeMetric::new(
+            "metric-1".to_string(),
+            "entity-1".to_string(),
+            "function".to_string(),
+            "src/main.rs".to_string(),
+            MetricType::LinesOfCode,
+            1000.0,
+            "lines".to_string(),
+        );
+
+        // 1000 lines would exceed the complexity threshold if it applied
+        assert!(!lines.is_critical(complexity));
+        assert!(!lines.is_warning(complexity));
+
+        let mut summary = MetricSummary::new(
+            "entity-1".to_string(),
+            "function".to_string(),
+            "src/main.rs".to_string(),
+        );
+        summary.add_metric(CodeMetric::new(
+            "metric-2".to_string(),
+            "entity-1".to_string(),
+            "function".to_string(),
+            "src/main.rs".to_string(),
+            MetricType::CyclomaticComplexity,
+            25.0,
+            "count".to_string(),
+        ));
+        summary.calculate_score(&thresholds);
+        assert_eq!(summary.issues.len(), 1);
+        assert!(summary.issues[0]
+            .message
+            .starts_with("CyclomaticComplexity exceeds error threshold"));
+    }
+}
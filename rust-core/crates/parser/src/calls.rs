@@ -0,0 +1,158 @@
+//! Call sites, the raw edges of a call graph
+//!
+//! Each call is recorded by the name it invokes, without resolving it to a
+//! declaration: `helper()`, `this.helper()` and `utils::helper()` all call
+//! `helper`. Constructor calls such as `new Cart()` name the class. Matching
+//! names to entities is left to the caller, which sees every indexed file.
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Node, Tree};
+
+use crate::Language;
+
+/// A call of a function, method or constructor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallSite {
+    /// Last segment of the callee as written
+    pub callee: String,
+    /// 1-based line of the call
+    pub line: u32,
+}
+
+/// Calls in `tree`, in source order
+pub fn extract(tree: &Tree, source: &str, language: &Language) -> Vec<CallSite> {
+    let mut calls = Vec::new();
+    let mut cursor = tree.walk();
+    let mut stack: Vec<Node> = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        let callee = callee_expression(node, language).and_then(callee_name);
+        if let Some(name) = callee.and_then(|name| source.get(name.byte_range())) {
+            calls.push(CallSite {
+                callee: name.to_string(),
+                line: node.start_position().row as u32 + 1,
+            });
+        }
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    calls
+}
+
+/// The expression a call node invokes, if `node` is a call
+fn callee_expression<'tree>(node: Node<'tree>, language: &Language) -> Option<Node<'tree>> {
+    let field = match (language, node.kind()) {
+        (Language::TypeScript | Language::JavaScript, "call_expression") => "function",
+        (Language::TypeScript | Language::JavaScript, "new_expression") => "constructor",
+        (Language::Python, "call") => "function",
+        (Language::Rust | Language::Go | Language::Cpp, "call_expression") => "function",
+        (Language::Java, "method_invocation") => "name",
+        (Language::CSharp, "invocation_expression") => "function",
+        (Language::Java | Language::CSharp, "object_creation_expression") => "type",
+        _ => return None,
+    };
+    node.child_by_field_name(field)
+}
+
+/// The identifier naming what `expression` refers to: the property of a
+/// member access, the last segment of a path, the function of a generic call
+fn callee_name(expression: Node) -> Option<Node> {
+    let mut node = expression;
+    loop {
+        let next = match node.kind() {
+            // Paths such as `a::b`, which end in their name
+            "scoped_identifier"
+            | "qualified_identifier"
+            | "scoped_type_identifier"
+            | "qualified_name" => node
+                .child_by_field_name("name")
+                .or_else(|| node.named_child(node.named_child_count().checked_sub(1)?)),
+            kind if kind.ends_with("identifier") => return Some(node),
+            // Java and C# generics keep the name as their first child
+            "generic_type" | "generic_name" => node.named_child(0),
+            _ => ["property", "attribute", "field", "name", "function", "type"]
+                .iter()
+                .find_map(|field| node.child_by_field_name(field)),
+        };
+        node = next?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::CodeParser;
+    use std::path::Path;
+
+    fn calls(file: &str, source: &str) -> Vec<(String, u32)> {
+        CodeParser::new()
+            .parse_file(Path::new(file), source)
+            .unwrap()
+            .calls
+            .into_iter()
+            .map(|call| (call.callee, call.line))
+            .collect()
+    }
+
+    fn names(file: &str, source: &str) -> Vec<String> {
+        calls(file, source)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    #[test]
+    fn test_calls_are_named_by_their_last_segment() {
+        assert_eq!(
+            calls(
+                "app.ts",
+                "function main() {\n  helper();\n  this.render(new Cart());\n  api.users.load<User>();\n}\n",
+            ),
+            vec![
+                ("helper".to_string(), 2),
+                ("render".to_string(), 3),
+                ("Cart".to_string(), 3),
+                ("load".to_string(), 4),
+            ]
+        );
+        assert_eq!(
+            names(
+                "main.py",
+                "def main():\n    run(Config())\n    self.log.write(x)\n"
+            ),
+            vec!["run", "Config", "write"]
+        );
+        assert_eq!(
+            names(
+                "lib.rs",
+                "fn main() {\n    helper();\n    utils::parse::<u8>(s);\n    store.save();\n}\n",
+            ),
+            vec!["helper", "parse", "save"]
+        );
+    }
+
+    #[test]
+    fn test_calls_in_other_languages() {
+        assert_eq!(
+            names(
+                "main.go",
+                "package main\nfunc main() { run(); fmt.Println(x) }\n"
+            ),
+            vec!["run", "Println"]
+        );
+        assert_eq!(
+            names(
+                "App.java",
+                "class App { void main() { start(); new Server().listen(); } }\n",
+            ),
+            vec!["start", "listen", "Server"]
+        );
+        assert_eq!(
+            names(
+                "App.cs",
+                "class App { void Main() { Start(); logger.Write(x); } }\n",
+            ),
+            vec!["Start", "Write"]
+        );
+        // Declarations are not calls
+        assert!(names("app.js", "function helper() {}\nconst f = () => 1;\n").is_empty());
+    }
+}
@@ -0,0 +1,302 @@
+//! API route discovery for web frameworks
+//!
+//! Route matchers inspect syntax nodes during the entity walk and report HTTP
+//! routes such as Express `app.get('/users', handler)` or FastAPI
+//! `@app.get("/users")`. Each match becomes an [`EntityType::Endpoint`] entity.
+//!
+//! [`EntityType::Endpoint`]: crate::EntityType::Endpoint
+
+use std::sync::Arc;
+use tree_sitter::Node;
+
+use crate::Language;
+
+/// HTTP methods recognized as route registrations, lowercase as written in source
+const HTTP_METHODS: [&str; 7] = ["get", "post", "put", "delete", "patch", "head", "options"];
+
+/// A route definition found in source
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteMatch {
+    /// Uppercase HTTP method, e.g. `GET`
+    pub method: String,
+    pub path: String,
+    /// Name of the handler function, when it is a named reference
+    pub handler: Option<String>,
+}
+
+/// Recognizes the route definitions of one web framework
+pub trait RouteMatcher: Send + Sync {
+    /// Framework name recorded on extracted endpoints
+    fn framework(&self) -> &str;
+
+    /// Whether this matcher applies to files of the given language
+    fn supports(&self, language: &Language) -> bool;
+
+    /// Inspect a single syntax node
+    fn match_node(&self, node: Node, source: &str) -> Option<RouteMatch>;
+}
+
+/// Matchers enabled by default
+pub fn default_route_matchers() -> Vec<Arc<dyn RouteMatcher>> {
+    vec![Arc::new(ExpressMatcher), Arc::new(FastApiMatcher)]
+}
+
+/// Express-style `app.get('/path', handler)` / `router.post(...)` calls
+pub struct ExpressMatcher;
+
+impl RouteMatcher for ExpressMatcher {
+    fn framework(&self) -> &str {
+        "express"
+    }
+
+    fn supports(&self, language: &Language) -> bool {
+        matches!(language, Language::TypeScript | Language::JavaScript)
+    }
+
+    fn match_node(&self, node: Node, source: &str) -> Option<RouteMatch> {
+        if node.kind() != "call_expression" {
+            return None;
+        }
+
+        let function = node.child_by_field_name("function")?;
+        if function.kind() != "member_expression" {
+            return None;
+        }
+        let method = text(function.child_by_field_name("property")?, source);
+        if !HTTP_METHODS.contains(&method) {
+            return None;
+        }
+
+        let arguments = node.child_by_field_name("arguments")?;
+        let mut cursor = arguments.walk();
+        let args: Vec<Node> = arguments.named_children(&mut cursor).collect();
+        let path = route_path(*args.first()?, source)?;
+
+        // Only a trailing identifier names the handler; inline functions are anonymous
+        let handler = args
+            .last()
+            .filter(|arg| args.len() > 1 && arg.kind() == "identifier")
+            .map(|arg| text(*arg, source).to_string());
+
+        Some(RouteMatch {
+            method: method.to_uppercase(),
+            path,
+            handler,
+        })
+    }
+}
+
+/// FastAPI-style `@app.get("/path")` decorators
+pub struct FastApiMatcher;
+
+impl RouteMatcher for FastApiMatcher {
+    fn framework(&self) -> &str {
+        "fastapi"
+    }
+
+    fn supports(&self, language: &Language) -> bool {
+        *language == Language::Python
+    }
+
+    fn match_node(&self, node: Node, source: &str) -> Option<RouteMatch> {
+        if node.kind() != "decorator" {
+            return None;
+        }
+
+        let call = node.named_child(0).filter(|child| child.kind() == "call")?;
+        let function = call.child_by_field_name("function")?;
+        if function.kind() != "attribute" {
+            return None;
+        }
+        let method = text(function.child_by_field_name("attribute")?, source);
+        if !HTTP_METHODS.contains(&method) {
+            return None;
+        }
+
+        let arguments = call.child_by_field_name("arguments")?;
+        let path = route_path(arguments.named_child(0)?, source)?;
+
+        let handler = node
+            .parent()
+            .filter(|parent| parent.kind() == "decorated_definition")
+            .and_then(|parent| parent.child_by_field_name("definition"))
+            .and_then(|definition| definition.child_by_field_name("name"))
+            .map(|name| text(name, source).to_string());
+
+        Some(RouteMatch {
+            method: method.to_uppercase(),
+            path,
+            handler,
+        })
+    }
+}
+
+/// A string literal that looks like a URL path
+fn route_path(node: Node, source: &str) -> Option<String> {
+    if !matches!(node.kind(), "string" | "template_string") {
+        return None;
+    }
+    let path = text(node, source).trim_matches(['"', '\'', '`']);
+    path.starts_with('/').then(|| path.to_string())
+}
+
+fn text<'a>(node: Node, source: &'a str) -> &'a str {
+    &source[node.byte_range()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CodeParser, EntityType};
+    use std::path::Path;
+
+    fn endpoints(
+        parser: &CodeParser,
+        file: &str,
+        source: &str,
+    ) -> Vec<(String, String, Option<String>)> {
+        parser
+            .parse_file(Path::new(file), source)
+            .unwrap()
+            .entities
+            .into_iter()
+            .filter(|e| e.entity_type == EntityType::Endpoint)
+            .map(|e| {
+                (
+                    e.metadata["http_method"].clone(),
+                    e.metadata["route_path"].clone(),
+                    e.metadata.get("handler").cloned(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_express_routes() {
+        let source = r#"
+const app = express();
+app.get('/users', listUsers);
+router.post("/users/:id", (req, res) => res.send(req.params.id));
+cache.get('key');
+"#;
+        assert_eq!(
+            endpoints(&CodeParser::new(), "server.js", source),
+            vec![
+                (
+                    "GET".to_string(),
+                    "/users".to_string(),
+                    Some("listUsers".to_string())
+                ),
+                ("POST".to_string(), "/users/:id".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fastapi_routes() {
+        let source = r#"
+app = FastAPI()
+
+@app.get("/items")
+def list_items():
+    return []
+
+@router.delete('/items/{item_id}')
+async def delete_item(item_id: int):
+    return None
+
+@functools.cache
+def helper():
+    pass
+"#;
+        assert_eq!(
+            endpoints(&CodeParser::new(), "main.py", source),
+            vec![
+                (
+                    "GET".to_string(),
+                    "/items".to_string(),
+                    Some("list_items".to_string())
+                ),
+                (
+                    "DELETE".to_string(),
+                    "/items/{item_id}".to_string(),
+                    Some("delete_item".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_endpoint_entity_is_searchable_by_route() {
+        let result = CodeParser::new()
+            .parse_file(Path::new("server.ts"), "app.get('/users', listUsers);\n")
+            .unwrap();
+        let endpoint = result
+            .entities
+            .iter()
+            .find(|e| e.entity_type == EntityType::Endpoint)
+            .unwrap();
+
+        assert_eq!(endpoint.name, "GET /users");
+        assert_eq!(endpoint.metadata["framework"], "express");
+        assert_eq!(endpoint.start_line, 1);
+    }
+
+    #[test]
+    fn test_matchers_are_configurable() {
+        let parser = CodeParser::with_options(crate::ParseOptions {
+            route_matchers: Vec::new(),
+            ..Default::default()
+        });
+        let result = parser
+            .parse_file(Path::new("server.js"), "app.get('/users', listUsers);\n")
+            .unwrap();
+
+        assert!(result
+            .entities
+            .iter()
+            .all(|e| e.entity_type != EntityType::Endpoint));
+    }
+
+    struct FlaskMatcher;
+
+    impl RouteMatcher for FlaskMatcher {
+        fn framework(&self) -> &str {
+            "flask"
+        }
+
+        fn supports(&self, language: &Language) -> bool {
+            *language == Language::Python
+        }
+
+        fn match_node(&self, node: Node, source: &str) -> Option<RouteMatch> {
+            let call = node.named_child(0).filter(|_| node.kind() == "decorator")?;
+            let function = call.child_by_field_name("function")?;
+            if !text(function, source).ends_with(".route") {
+                return None;
+            }
+            let arguments = call.child_by_field_name("arguments")?;
+            Some(RouteMatch {
+                method: "GET".to_string(),
+                path: route_path(arguments.named_child(0)?, source)?,
+                handler: None,
+            })
+        }
+    }
+
+    #[test]
+    fn test_custom_matcher() {
+        let mut parser = CodeParser::new();
+        parser.add_route_matcher(Arc::new(FlaskMatcher));
+
+        let result = endpoints(
+            &parser,
+            "app.py",
+            "@app.route('/health')\ndef health():\n    return 'ok'\n",
+        );
+        assert_eq!(
+            result,
+            vec![("GET".to_string(), "/health".to_string(), None)]
+        );
+    }
+}
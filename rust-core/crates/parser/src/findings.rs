@@ -0,0 +1,168 @@
+//! Tech-debt markers such as `TODO` and `FIXME` found in comments
+//!
+//! Only comment nodes of the syntax tree are scanned, so a marker inside a
+//! string literal is not reported. A marker must stand as its own uppercase
+//! word and may name an author, as in `// TODO(alice): fix`.
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Node, Tree};
+
+/// Markers reported when [`crate::ParseOptions::finding_markers`] is left at its default
+pub const DEFAULT_MARKERS: [&str; 4] = ["TODO", "FIXME", "HACK", "XXX"];
+
+/// A marker comment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    /// The marker, e.g. `TODO`
+    pub kind: String,
+    /// Name given in parentheses after the marker
+    pub author: Option<String>,
+    /// The rest of the comment line, without a leading `:`
+    pub text: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Findings in every comment of `tree`, in source order
+pub fn extract(tree: &Tree, source: &str, markers: &[String]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if markers.is_empty() {
+        return findings;
+    }
+
+    let mut cursor = tree.walk();
+    let mut stack: Vec<Node> = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.kind().ends_with("comment") {
+            scan_comment(node, source, markers, &mut findings);
+            continue;
+        }
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    findings
+}
+
+fn scan_comment(node: Node, source: &str, markers: &[String], findings: &mut Vec<Finding>) {
+    let Some(text) = source.get(node.byte_range()) else {
+        return;
+    };
+    let start = node.start_position();
+    for (offset, line) in text.lines().enumerate() {
+        let Some((column, kind, rest)) = find_marker(line, markers) else {
+            continue;
+        };
+        let (author, rest) = match rest.strip_prefix('(').and_then(|rest| rest.split_once(')')) {
+            Some((author, rest)) => (
+                Some(author.trim().to_string()).filter(|a| !a.is_empty()),
+                rest,
+            ),
+            None => (None, rest),
+        };
+        let text = rest
+            .trim_start_matches(':')
+            .trim()
+            .trim_end_matches("*/")
+            .trim_end();
+        let base_column = if offset == 0 { start.column } else { 0 };
+        findings.push(Finding {
+            kind: kind.to_string(),
+            author,
+            text: text.to_string(),
+            line: (start.row + offset) as u32 + 1,
+            column: (base_column + column) as u32,
+        });
+    }
+}
+
+/// The first marker standing as a whole word in `line`: its byte column,
+/// the marker and the text after it
+fn find_marker<'a>(line: &'a str, markers: &'a [String]) -> Option<(usize, &'a str, &'a str)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    markers
+        .iter()
+        .filter_map(|marker| {
+            line.match_indices(marker.as_str()).find_map(|(at, _)| {
+                let before = line[..at].chars().next_back();
+                let rest = &line[at + marker.len()..];
+                let bounded =
+                    !before.is_some_and(is_word) && !rest.chars().next().is_some_and(is_word);
+                bounded.then_some((at, marker.as_str(), rest))
+            })
+        })
+        .min_by_key(|(at, _, _)| *at)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CodeParser, ParseOptions};
+    use std::path::Path;
+
+    fn findings(file: &str, source: &str) -> Vec<super::Finding> {
+        CodeParser::new()
+            .parse_file(Path::new(file), source)
+            .unwrap()
+            .findings
+    }
+
+    #[test]
+    fn test_todo_with_author() {
+        let found = findings(
+            "app.ts",
+            "// TODO(alice): fix\nconst label = \"TODO: not a comment\";\n/* FIXME handle\n   HACK around it */\n",
+        );
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].kind, "TODO");
+        assert_eq!(found[0].author.as_deref(), Some("alice"));
+        assert_eq!(found[0].text, "fix");
+        assert_eq!((found[0].line, found[0].column), (1, 3));
+        assert_eq!(
+            (
+                found[1].kind.as_str(),
+                found[1].text.as_str(),
+                found[1].line
+            ),
+            ("FIXME", "handle", 3)
+        );
+        assert_eq!(
+            (
+                found[2].kind.as_str(),
+                found[2].text.as_str(),
+                found[2].line
+            ),
+            ("HACK", "around it", 4)
+        );
+        assert_eq!(found[2].author, None);
+    }
+
+    #[test]
+    fn test_markers_are_whole_words_and_configurable() {
+        let source = "# TODOS are not markers\n# XXX: check\nx = 1  # NOTE(bob): keep\n";
+        let found = findings("script.py", source);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].kind.as_str(), found[0].line), ("XXX", 2));
+
+        let parser = CodeParser::with_options(ParseOptions {
+            finding_markers: vec!["NOTE".to_string()],
+            ..Default::default()
+        });
+        let found = parser
+            .parse_file(Path::new("script.py"), source)
+            .unwrap()
+            .findings;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].author.as_deref(), Some("bob"));
+        assert_eq!(found[0].text, "keep");
+
+        let found = findings(
+            "lib.rs",
+            "/// TODO(carol) document\nfn main() {} // FIXME\n",
+        );
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].text, "document");
+        assert_eq!(
+            (found[1].kind.as_str(), found[1].text.as_str()),
+            ("FIXME", "")
+        );
+    }
+}
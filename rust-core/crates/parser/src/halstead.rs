@@ -0,0 +1,197 @@
+//! Halstead metrics from the Tree-sitter token stream
+//!
+//! Every leaf of the syntax tree is a token. Named leaves (identifiers, type
+//! names, literals) are operands; anonymous leaves (keywords, operators and
+//! punctuation) are operators. String literals count as one operand however
+//! the grammar splits them, comments are skipped, and closing brackets are not
+//! counted so that a bracket pair is a single operator.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tree_sitter::{Node, Tree};
+
+use crate::{languages, parsers};
+
+/// Node kinds whose subtree counts as a single literal operand
+const LITERAL_KINDS: [&str; 8] = [
+    "string",
+    "string_literal",
+    "raw_string_literal",
+    "template_string",
+    "interpreted_string_literal",
+    "char_literal",
+    "character_literal",
+    "concatenated_string",
+];
+
+/// Node kinds measured as entities of their own
+const FUNCTION_KINDS: [&str; 6] = [
+    "function_item",
+    "function_declaration",
+    "function_definition",
+    "method_definition",
+    "method_declaration",
+    "constructor_declaration",
+];
+
+/// Halstead counts and the measures derived from them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HalsteadMetrics {
+    /// n1
+    pub distinct_operators: u32,
+    /// n2
+    pub distinct_operands: u32,
+    /// N1
+    pub total_operators: u32,
+    /// N2
+    pub total_operands: u32,
+    /// `n1 + n2`
+    pub vocabulary: u32,
+    /// `N1 + N2`
+    pub length: u32,
+    /// `N * log2(n)`
+    pub volume: f64,
+    /// `(n1 / 2) * (N2 / n2)`
+    pub difficulty: f64,
+    /// `difficulty * volume`
+    pub effort: f64,
+}
+
+/// Halstead metrics of one function or method
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityHalstead {
+    pub name: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub metrics: HalsteadMetrics,
+}
+
+impl HalsteadMetrics {
+    fn from_counts(operators: &HashMap<String, u32>, operands: &HashMap<String, u32>) -> Self {
+        let n1 = operators.len() as u32;
+        let n2 = operands.len() as u32;
+        let total_operators = operators.values().sum();
+        let total_operands: u32 = operands.values().sum();
+        let vocabulary = n1 + n2;
+        let length = total_operators + total_operands;
+        let volume = if vocabulary > 0 {
+            length as f64 * (vocabulary as f64).log2()
+        } else {
+            0.0
+        };
+        let difficulty = if n2 > 0 {
+            (n1 as f64 / 2.0) * (total_operands as f64 / n2 as f64)
+        } else {
+            0.0
+        };
+
+        Self {
+            distinct_operators: n1,
+            distinct_operands: n2,
+            total_operators,
+            total_operands,
+            vocabulary,
+            length,
+            volume,
+            difficulty,
+            effort: difficulty * volume,
+        }
+    }
+}
+
+/// Parse `source` with the grammar for `file_path`, `None` for unsupported files
+pub fn parse(file_path: &str, source: &str) -> Option<Tree> {
+    let path = Path::new(file_path);
+    let language = languages::detect_language_from_extension(path.extension()?.to_str()?)?;
+    let dialect = languages::detect_dialect(path, source);
+    parsers::new_parser(parsers::grammar(&language, dialect)).parse(source, None)
+}
+
+/// Halstead metrics of the tokens under `node`
+pub fn measure(node: Node, source: &str) -> HalsteadMetrics {
+    let mut operators = HashMap::new();
+    let mut operands = HashMap::new();
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        let kind = node.kind();
+        if kind.contains("comment") {
+            continue;
+        }
+        if LITERAL_KINDS.contains(&kind) || node.child_count() == 0 {
+            let text = source.get(node.byte_range()).unwrap_or_default();
+            if node.is_named() {
+                *operands.entry(text.to_string()).or_insert(0) += 1;
+            } else if !matches!(kind, ")" | "]" | "}") && !text.trim().is_empty() {
+                *operators.entry(text.to_string()).or_insert(0) += 1;
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    HalsteadMetrics::from_counts(&operators, &operands)
+}
+
+/// Halstead metrics of each function and method in `tree`, nested ones included
+pub fn measure_entities(tree: &Tree, source: &str) -> Vec<EntityHalstead> {
+    let mut entities = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if FUNCTION_KINDS.contains(&node.kind()) {
+            let name = node
+                .child_by_field_name("name")
+                .or_else(|| node.child_by_field_name("declarator"))
+                .and_then(|name| source.get(name.byte_range()))
+                .unwrap_or("<anonymous>");
+            entities.push(EntityHalstead {
+                name: name.to_string(),
+                start_line: node.start_position().row as u32 + 1,
+                end_line: node.end_position().row as u32 + 1,
+                metrics: measure(node, source),
+            });
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    entities.sort_by_key(|entity| entity.start_line);
+    entities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_of_a_small_function() {
+        let source = "fn add(a: i32, b: i32) -> i32 { a + b }\n";
+        let tree = parse("add.rs", source).unwrap();
+        let entities = measure_entities(&tree, source);
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].name, "add");
+
+        // Operators: fn ( : , : -> { +   Operands: add a i32 b i32 i32 a b
+        let metrics = &entities[0].metrics;
+        assert_eq!(metrics.distinct_operators, 7);
+        assert_eq!(metrics.distinct_operands, 4);
+        assert_eq!(metrics.total_operators, 8);
+        assert_eq!(metrics.total_operands, 8);
+        assert_eq!(metrics.vocabulary, 11);
+        assert!((metrics.volume - 16.0 * 11f64.log2()).abs() < 1e-9);
+        assert!((metrics.difficulty - 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_literals_and_comments() {
+        // The string is one operand; the comment contributes nothing
+        let source = "function greet() { // says hi\n  return \"hi there\";\n}\n";
+        let tree = parse("greet.js", source).unwrap();
+        let metrics = measure(tree.root_node(), source);
+        // Operators: function ( { return ;   Operands: greet "hi there"
+        assert_eq!(
+            (metrics.distinct_operators, metrics.total_operators),
+            (5, 5)
+        );
+        assert_eq!((metrics.distinct_operands, metrics.total_operands), (2, 2));
+    }
+}
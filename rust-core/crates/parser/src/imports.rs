@@ -0,0 +1,790 @@
+//! Structured import and export statements
+//!
+//! Where the entity walker records an import as a single `Import` entity,
+//! this pass keeps the module specifier apart from the names it binds, so
+//! callers can build a module-dependency graph. ES modules and CommonJS
+//! `require` are read for TypeScript and JavaScript, `import`/`from` for
+//! Python and `use` declarations for Rust; other languages yield nothing.
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Node, Tree};
+
+use crate::Language;
+
+/// How an import binds the module it names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ImportKind {
+    /// Individual exports: `import { a } from 'm'`, `from m import a`, `use m::a`
+    Named,
+    /// The default export: `import a from 'm'`
+    Default,
+    /// The whole module under one name: `import * as m from 'm'`, `import m`
+    Namespace,
+    /// Loaded for its effects only: `import 'm'`, a bare `require('m')`
+    SideEffect,
+    /// Bound through CommonJS: `const m = require('m')`, `import m = require('m')`
+    Require,
+    /// Every export, unnamed: `from m import *`, `use m::*`
+    Glob,
+}
+
+impl ImportKind {
+    /// Lowercase name used when persisting the kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportKind::Named => "named",
+            ImportKind::Default => "default",
+            ImportKind::Namespace => "namespace",
+            ImportKind::SideEffect => "side_effect",
+            ImportKind::Require => "require",
+            ImportKind::Glob => "glob",
+        }
+    }
+}
+
+/// How an export exposes its names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExportKind {
+    /// `export { a }`, `export const a`, `exports.a =`, `pub use m::a`
+    Named,
+    /// `export default`, `module.exports =`
+    Default,
+    /// Another module's exports under one name: `export * as ns from 'm'`
+    Namespace,
+    /// Every export of another module: `export * from 'm'`, `pub use m::*`
+    Glob,
+}
+
+impl ExportKind {
+    /// Lowercase name used when persisting the kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportKind::Named => "named",
+            ExportKind::Default => "default",
+            ExportKind::Namespace => "namespace",
+            ExportKind::Glob => "glob",
+        }
+    }
+}
+
+/// A name bound by an import or exposed by an export
+///
+/// For named imports and exports `name` is the name in the exporting module
+/// and `alias` the one it is renamed to. Default, namespace and require
+/// imports bind a single local name, held in `name`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Specifier {
+    pub name: String,
+    pub alias: Option<String>,
+}
+
+/// One import of a module
+///
+/// A statement binding names in several ways, such as
+/// `import React, { useState } from 'react'`, yields one entry per kind.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportStatement {
+    /// Module specifier as written: `./util`, `os.path`, `crate::config`
+    pub module: String,
+    pub kind: ImportKind,
+    pub names: Vec<Specifier>,
+    /// 1-based line of the statement
+    pub line: u32,
+}
+
+/// One export, local or re-exported from another module
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportStatement {
+    pub kind: ExportKind,
+    pub names: Vec<Specifier>,
+    /// Source module of a re-export
+    pub module: Option<String>,
+    /// 1-based line of the statement
+    pub line: u32,
+}
+
+/// Imports and exports of a parsed file, in source order
+pub fn extract(
+    tree: &Tree,
+    source: &str,
+    language: &Language,
+) -> (Vec<ImportStatement>, Vec<ExportStatement>) {
+    let mut collector = Collector {
+        source,
+        language,
+        imports: Vec::new(),
+        exports: Vec::new(),
+    };
+    if !matches!(
+        language,
+        Language::TypeScript | Language::JavaScript | Language::Python | Language::Rust
+    ) {
+        return (Vec::new(), Vec::new());
+    }
+
+    // Pre-order without recursion, as in the entity walker
+    let mut cursor = tree.root_node().walk();
+    loop {
+        collector.visit(cursor.node());
+        if cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return (collector.imports, collector.exports);
+            }
+        }
+    }
+}
+
+struct Collector<'a> {
+    source: &'a str,
+    language: &'a Language,
+    imports: Vec<ImportStatement>,
+    exports: Vec<ExportStatement>,
+}
+
+impl Collector<'_> {
+    fn text(&self, node: Node) -> String {
+        self.source[node.byte_range()].to_string()
+    }
+
+    fn field(&self, node: Node, field: &str) -> Option<String> {
+        node.child_by_field_name(field)
+            .map(|child| self.text(child))
+    }
+
+    fn import(&mut self, node: Node, module: String, kind: ImportKind, names: Vec<Specifier>) {
+        self.imports.push(ImportStatement {
+            module,
+            kind,
+            names,
+            line: line(node),
+        });
+    }
+
+    fn export(
+        &mut self,
+        node: Node,
+        kind: ExportKind,
+        names: Vec<Specifier>,
+        module: Option<String>,
+    ) {
+        self.exports.push(ExportStatement {
+            kind,
+            names,
+            module,
+            line: line(node),
+        });
+    }
+
+    fn visit(&mut self, node: Node) {
+        match self.language {
+            Language::TypeScript | Language::JavaScript => self.visit_js(node),
+            Language::Python => self.visit_python(node),
+            Language::Rust => self.visit_rust(node),
+            _ => {}
+        }
+    }
+
+    fn visit_js(&mut self, node: Node) {
+        match node.kind() {
+            "import_statement" => self.js_import(node),
+            "export_statement" => self.js_export(node),
+            "call_expression" => self.js_require(node),
+            "assignment_expression" => self.commonjs_export(node),
+            _ => {}
+        }
+    }
+
+    fn js_import(&mut self, node: Node) {
+        let source = node
+            .child_by_field_name("source")
+            .map(|source| unquote(&self.text(source)));
+        let mut cursor = node.walk();
+        let clause = node
+            .named_children(&mut cursor)
+            .find(|child| matches!(child.kind(), "import_clause" | "import_require_clause"));
+
+        let Some(clause) = clause else {
+            if let Some(module) = source {
+                self.import(node, module, ImportKind::SideEffect, Vec::new());
+            }
+            return;
+        };
+
+        if clause.kind() == "import_require_clause" {
+            // `import fs = require('fs')` keeps its source inside the clause
+            let Some(module) = self.field(clause, "source") else {
+                return;
+            };
+            let mut cursor = clause.walk();
+            let names = clause
+                .named_children(&mut cursor)
+                .filter(|child| child.kind() == "identifier")
+                .map(|binding| local(self.text(binding)))
+                .collect();
+            self.import(node, unquote(&module), ImportKind::Require, names);
+            return;
+        }
+
+        let Some(module) = source else {
+            return;
+        };
+        let mut cursor = clause.walk();
+        for binding in clause.named_children(&mut cursor) {
+            match binding.kind() {
+                "identifier" => {
+                    let names = vec![local(self.text(binding))];
+                    self.import(node, module.clone(), ImportKind::Default, names);
+                }
+                "namespace_import" => {
+                    let mut cursor = binding.walk();
+                    let names = binding
+                        .named_children(&mut cursor)
+                        .filter(|child| child.kind() == "identifier")
+                        .map(|name| local(self.text(name)))
+                        .collect();
+                    self.import(node, module.clone(), ImportKind::Namespace, names);
+                }
+                "named_imports" => {
+                    let names = self.specifiers(binding, "import_specifier");
+                    self.import(node, module.clone(), ImportKind::Named, names);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn js_export(&mut self, node: Node) {
+        let module = node
+            .child_by_field_name("source")
+            .map(|source| unquote(&self.text(source)));
+        let mut cursor = node.walk();
+        let is_default = node
+            .children(&mut cursor)
+            .any(|child| child.kind() == "default");
+
+        if let Some(declaration) = node.child_by_field_name("declaration") {
+            let kind = if is_default {
+                ExportKind::Default
+            } else {
+                ExportKind::Named
+            };
+            let names = self.declared_names(declaration);
+            self.export(node, kind, names, None);
+            return;
+        }
+        if is_default {
+            // `export default expression`: named only when it is an identifier
+            let names = node
+                .child_by_field_name("value")
+                .filter(|value| value.kind() == "identifier")
+                .map(|value| vec![local(self.text(value))])
+                .unwrap_or_default();
+            self.export(node, ExportKind::Default, names, None);
+            return;
+        }
+
+        let mut cursor = node.walk();
+        let clause = node
+            .named_children(&mut cursor)
+            .find(|child| matches!(child.kind(), "export_clause" | "namespace_export"));
+        match clause {
+            Some(clause) if clause.kind() == "export_clause" => {
+                let names = self.specifiers(clause, "export_specifier");
+                self.export(node, ExportKind::Named, names, module);
+            }
+            Some(clause) => {
+                let mut cursor = clause.walk();
+                let names = clause
+                    .named_children(&mut cursor)
+                    .map(|name| local(unquote(&self.text(name))))
+                    .collect();
+                self.export(node, ExportKind::Namespace, names, module);
+            }
+            None if module.is_some() => {
+                self.export(node, ExportKind::Glob, Vec::new(), module);
+            }
+            None => {}
+        }
+    }
+
+    /// `name` / `alias` pairs of every `kind` child of a braced list
+    fn specifiers(&self, list: Node, kind: &str) -> Vec<Specifier> {
+        let mut cursor = list.walk();
+        list.named_children(&mut cursor)
+            .filter(|child| child.kind() == kind)
+            .filter_map(|specifier| {
+                Some(Specifier {
+                    name: unquote(&self.field(specifier, "name")?),
+                    alias: self.field(specifier, "alias"),
+                })
+            })
+            .collect()
+    }
+
+    /// Names introduced by an exported declaration
+    fn declared_names(&self, declaration: Node) -> Vec<Specifier> {
+        if let Some(name) = self.field(declaration, "name") {
+            return vec![local(name)];
+        }
+        // `export const a = 1, b = 2`
+        let mut cursor = declaration.walk();
+        declaration
+            .named_children(&mut cursor)
+            .filter(|child| child.kind() == "variable_declarator")
+            .filter_map(|declarator| declarator.child_by_field_name("name"))
+            .filter(|name| name.kind() == "identifier")
+            .map(|name| local(self.text(name)))
+            .collect()
+    }
+
+    /// A CommonJS `require('m')` call, classified by what it is bound to
+    fn js_require(&mut self, node: Node) {
+        let is_require = node
+            .child_by_field_name("function")
+            .is_some_and(|function| {
+                function.kind() == "identifier" && self.text(function) == "require"
+            });
+        let argument = node.child_by_field_name("arguments").and_then(|arguments| {
+            let mut cursor = arguments.walk();
+            let mut children = arguments.named_children(&mut cursor);
+            match (children.next(), children.next()) {
+                (Some(argument), None) if argument.kind() == "string" => Some(argument),
+                _ => None,
+            }
+        });
+        let (true, Some(argument)) = (is_require, argument) else {
+            return;
+        };
+        let module = unquote(&self.text(argument));
+
+        let parent = node.parent();
+        let binding = parent
+            .filter(|parent| parent.kind() == "variable_declarator")
+            .and_then(|declarator| declarator.child_by_field_name("name"));
+        match binding {
+            Some(binding) if binding.kind() == "identifier" => {
+                let names = vec![local(self.text(binding))];
+                self.import(node, module, ImportKind::Require, names);
+            }
+            Some(pattern) if pattern.kind() == "object_pattern" => {
+                let names = self.destructured(pattern);
+                self.import(node, module, ImportKind::Named, names);
+            }
+            _ if parent.is_some_and(|parent| parent.kind() == "expression_statement") => {
+                self.import(node, module, ImportKind::SideEffect, Vec::new());
+            }
+            _ => self.import(node, module, ImportKind::Require, Vec::new()),
+        }
+    }
+
+    /// Properties taken from `const { a, b: c } = require('m')`
+    fn destructured(&self, pattern: Node) -> Vec<Specifier> {
+        let mut cursor = pattern.walk();
+        pattern
+            .named_children(&mut cursor)
+            .filter_map(|property| match property.kind() {
+                "shorthand_property_identifier_pattern" => Some(local(self.text(property))),
+                "pair_pattern" => Some(Specifier {
+                    name: self.field(property, "key")?,
+                    alias: self.field(property, "value"),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// `module.exports = x`, `exports.a = x` and `module.exports.a = x`
+    fn commonjs_export(&mut self, node: Node) {
+        let Some(left) = node
+            .child_by_field_name("left")
+            .filter(|left| left.kind() == "member_expression")
+        else {
+            return;
+        };
+        let target = self.text(left);
+        if target == "module.exports" {
+            let names = node
+                .child_by_field_name("right")
+                .filter(|right| right.kind() == "identifier")
+                .map(|right| vec![local(self.text(right))])
+                .unwrap_or_default();
+            self.export(node, ExportKind::Default, names, None);
+            return;
+        }
+        let is_exports_member = left.child_by_field_name("object").is_some_and(|object| {
+            matches!(self.text(object).as_str(), "exports" | "module.exports")
+        });
+        if let (true, Some(property)) = (is_exports_member, self.field(left, "property")) {
+            self.export(node, ExportKind::Named, vec![local(property)], None);
+        }
+    }
+
+    fn visit_python(&mut self, node: Node) {
+        match node.kind() {
+            // `import a.b, c as d`: each module is bound under one name
+            "import_statement" => {
+                let mut cursor = node.walk();
+                let modules: Vec<Node> = node.children_by_field_name("name", &mut cursor).collect();
+                for module in modules {
+                    let (name, alias) = self.python_alias(module);
+                    let bound = alias.unwrap_or_else(|| name.clone());
+                    self.import(node, name, ImportKind::Namespace, vec![local(bound)]);
+                }
+            }
+            "import_from_statement" => {
+                let Some(module) = self.field(node, "module_name") else {
+                    return;
+                };
+                let mut cursor = node.walk();
+                if node
+                    .named_children(&mut cursor)
+                    .any(|child| child.kind() == "wildcard_import")
+                {
+                    self.import(node, module, ImportKind::Glob, Vec::new());
+                    return;
+                }
+                let mut cursor = node.walk();
+                let names = node
+                    .children_by_field_name("name", &mut cursor)
+                    .map(|name| {
+                        let (name, alias) = self.python_alias(name);
+                        Specifier { name, alias }
+                    })
+                    .collect();
+                self.import(node, module, ImportKind::Named, names);
+            }
+            _ => {}
+        }
+    }
+
+    /// Name and alias of a `dotted_name` or `aliased_import`
+    fn python_alias(&self, node: Node) -> (String, Option<String>) {
+        match node.kind() {
+            "aliased_import" => (
+                self.field(node, "name").unwrap_or_default(),
+                self.field(node, "alias"),
+            ),
+            _ => (self.text(node), None),
+        }
+    }
+
+    fn visit_rust(&mut self, node: Node) {
+        if node.kind() != "use_declaration" {
+            return;
+        }
+        let Some(argument) = node.child_by_field_name("argument") else {
+            return;
+        };
+
+        let mut leaves = Vec::new();
+        self.use_tree(argument, "", &mut leaves);
+
+        // One statement per module and kind, in the order modules first appear
+        let mut grouped: Vec<(String, ImportKind, Vec<Specifier>)> = Vec::new();
+        for (module, specifier) in leaves {
+            let kind = if specifier.is_some() {
+                ImportKind::Named
+            } else {
+                ImportKind::Glob
+            };
+            match grouped
+                .iter_mut()
+                .find(|(existing, existing_kind, _)| *existing == module && *existing_kind == kind)
+            {
+                Some((_, _, names)) => names.extend(specifier),
+                None => grouped.push((module, kind, specifier.into_iter().collect())),
+            }
+        }
+
+        let mut cursor = node.walk();
+        let is_pub = node
+            .children(&mut cursor)
+            .any(|child| child.kind() == "visibility_modifier");
+        for (module, kind, names) in grouped {
+            if is_pub {
+                let export_kind = match kind {
+                    ImportKind::Glob => ExportKind::Glob,
+                    _ => ExportKind::Named,
+                };
+                self.export(node, export_kind, names.clone(), Some(module.clone()));
+            }
+            self.import(node, module, kind, names);
+        }
+    }
+
+    /// Flatten a use tree into `(module, name)` leaves; globs have no name
+    fn use_tree(&self, node: Node, prefix: &str, leaves: &mut Vec<(String, Option<Specifier>)>) {
+        match node.kind() {
+            "scoped_identifier" => {
+                let path = self.field(node, "path");
+                let name = self.field(node, "name").unwrap_or_default();
+                let module = join_path(prefix, path.as_deref().unwrap_or(""));
+                leaves.push((module, Some(local(name))));
+            }
+            "use_as_clause" => {
+                let Some(path) = node.child_by_field_name("path") else {
+                    return;
+                };
+                let mut inner = Vec::new();
+                self.use_tree(path, prefix, &mut inner);
+                let alias = self.field(node, "alias");
+                for (module, specifier) in inner {
+                    let specifier = specifier.map(|specifier| Specifier {
+                        alias: alias.clone(),
+                        ..specifier
+                    });
+                    leaves.push((module, specifier));
+                }
+            }
+            "scoped_use_list" => {
+                let path = self.field(node, "path").unwrap_or_default();
+                let prefix = join_path(prefix, &path);
+                if let Some(list) = node.child_by_field_name("list") {
+                    self.use_tree(list, &prefix, leaves);
+                }
+            }
+            "use_list" => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    self.use_tree(child, prefix, leaves);
+                }
+            }
+            "use_wildcard" => {
+                let path = self.text(node);
+                let path = path.trim_end_matches('*').trim_end_matches("::");
+                leaves.push((join_path(prefix, path), None));
+            }
+            // `use foo;` or `self` in a list: the module is the path so far
+            "identifier" | "self" | "crate" | "super" => {
+                let name = self.text(node);
+                let module = if prefix.is_empty() {
+                    name.clone()
+                } else {
+                    prefix.to_string()
+                };
+                leaves.push((module, Some(local(name))));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn local(name: String) -> Specifier {
+    Specifier { name, alias: None }
+}
+
+fn line(node: Node) -> u32 {
+    node.start_position().row as u32 + 1
+}
+
+fn unquote(text: &str) -> String {
+    text.trim_matches(['"', '\'', '`']).to_string()
+}
+
+fn join_path(prefix: &str, path: &str) -> String {
+    match (prefix.is_empty(), path.is_empty()) {
+        (true, _) => path.to_string(),
+        (_, true) => prefix.to_string(),
+        _ => format!("{}::{}", prefix, path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CodeParser;
+    use std::path::Path;
+
+    fn parse(file: &str, source: &str) -> (Vec<ImportStatement>, Vec<ExportStatement>) {
+        let result = CodeParser::new()
+            .parse_file(Path::new(file), source)
+            .unwrap();
+        (result.imports, result.exports)
+    }
+
+    fn summary(imports: &[ImportStatement]) -> Vec<(&str, ImportKind, Vec<String>)> {
+        imports
+            .iter()
+            .map(|import| {
+                let names = import
+                    .names
+                    .iter()
+                    .map(|specifier| match &specifier.alias {
+                        Some(alias) => format!("{} as {}", specifier.name, alias),
+                        None => specifier.name.clone(),
+                    })
+                    .collect();
+                (import.module.as_str(), import.kind, names)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_es_import_kinds_are_distinguished() {
+        let (imports, _) = parse(
+            "app.ts",
+            "import React, { useState as state, useEffect } from 'react';\n\
+             import * as path from 'path';\n\
+             import './polyfills';\n\
+             import fs = require('fs');\n",
+        );
+
+        assert_eq!(
+            summary(&imports),
+            vec![
+                ("react", ImportKind::Default, vec!["React".to_string()]),
+                (
+                    "react",
+                    ImportKind::Named,
+                    vec!["useState as state".to_string(), "useEffect".to_string()]
+                ),
+                ("path", ImportKind::Namespace, vec!["path".to_string()]),
+                ("./polyfills", ImportKind::SideEffect, vec![]),
+                ("fs", ImportKind::Require, vec!["fs".to_string()]),
+            ]
+        );
+        assert_eq!(imports[2].line, 2);
+    }
+
+    #[test]
+    fn test_es_exports() {
+        let (_, exports) = parse(
+            "index.ts",
+            "export { a, b as c } from './m';\n\
+             export * from './all';\n\
+             export * as ns from './ns';\n\
+             export default function main() {}\n\
+             export const x = 1, y = 2;\n\
+             export { z };\n",
+        );
+
+        let kinds: Vec<(ExportKind, Option<&str>, Vec<&str>)> = exports
+            .iter()
+            .map(|export| {
+                let names = export.names.iter().map(|s| s.name.as_str()).collect();
+                (export.kind, export.module.as_deref(), names)
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (ExportKind::Named, Some("./m"), vec!["a", "b"]),
+                (ExportKind::Glob, Some("./all"), vec![]),
+                (ExportKind::Namespace, Some("./ns"), vec!["ns"]),
+                (ExportKind::Default, None, vec!["main"]),
+                (ExportKind::Named, None, vec!["x", "y"]),
+                (ExportKind::Named, None, vec!["z"]),
+            ]
+        );
+        assert_eq!(exports[0].names[1].alias.as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn test_commonjs_require_and_exports() {
+        let (imports, exports) = parse(
+            "server.js",
+            "const fs = require('fs');\n\
+             const { join, resolve: res } = require('path');\n\
+             require('dotenv');\n\
+             module.exports = main;\n\
+             exports.helper = 1;\n",
+        );
+
+        assert_eq!(
+            summary(&imports),
+            vec![
+                ("fs", ImportKind::Require, vec!["fs".to_string()]),
+                (
+                    "path",
+                    ImportKind::Named,
+                    vec!["join".to_string(), "resolve as res".to_string()]
+                ),
+                ("dotenv", ImportKind::SideEffect, vec![]),
+            ]
+        );
+        let exports: Vec<(ExportKind, &str)> = exports
+            .iter()
+            .map(|export| (export.kind, export.names[0].name.as_str()))
+            .collect();
+        assert_eq!(
+            exports,
+            vec![(ExportKind::Default, "main"), (ExportKind::Named, "helper")]
+        );
+    }
+
+    #[test]
+    fn test_python_imports() {
+        let (imports, exports) = parse(
+            "app.py",
+            "import os, os.path as osp\n\
+             from collections import OrderedDict, defaultdict as dd\n\
+             from ..models import *\n",
+        );
+
+        assert_eq!(
+            summary(&imports),
+            vec![
+                ("os", ImportKind::Namespace, vec!["os".to_string()]),
+                ("os.path", ImportKind::Namespace, vec!["osp".to_string()]),
+                (
+                    "collections",
+                    ImportKind::Named,
+                    vec!["OrderedDict".to_string(), "defaultdict as dd".to_string()]
+                ),
+                ("..models", ImportKind::Glob, vec![]),
+            ]
+        );
+        assert!(exports.is_empty());
+    }
+
+    #[test]
+    fn test_rust_use_trees() {
+        let (imports, exports) = parse(
+            "lib.rs",
+            "use std::collections::HashMap;\n\
+             use crate::config::{Config, loader::Loader as L, self};\n\
+             use serde;\n\
+             pub use crate::models::*;\n",
+        );
+
+        assert_eq!(
+            summary(&imports),
+            vec![
+                (
+                    "std::collections",
+                    ImportKind::Named,
+                    vec!["HashMap".to_string()]
+                ),
+                (
+                    "crate::config",
+                    ImportKind::Named,
+                    vec!["Config".to_string(), "self".to_string()]
+                ),
+                (
+                    "crate::config::loader",
+                    ImportKind::Named,
+                    vec!["Loader as L".to_string()]
+                ),
+                ("serde", ImportKind::Named, vec!["serde".to_string()]),
+                ("crate::models", ImportKind::Glob, vec![]),
+            ]
+        );
+        assert_eq!(exports.len(), 1);
+        assert_eq!(exports[0].kind, ExportKind::Glob);
+        assert_eq!(exports[0].module.as_deref(), Some("crate::models"));
+    }
+
+    #[test]
+    fn test_module_dependencies_are_distinct() {
+        let result = CodeParser::new()
+            .parse_file(
+                Path::new("app.ts"),
+                "import a from './b';\nimport { c } from './b';\nimport 'z';\n",
+            )
+            .unwrap();
+        assert_eq!(result.module_dependencies(), vec!["./b", "z"]);
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::Language;
 use std::collections::HashSet;
+use std::path::Path;
 
 /// Language configuration
 pub struct LanguageConfig {
@@ -538,3 +539,57 @@ pub fn detect_language_from_extension(extension: &str) -> Option<Language> {
         _ => None,
     }
 }
+
+/// The variant of its language a file is written in, from its extension and content
+///
+/// Distinguishes `typescript` from `tsx`, `javascript` from `jsx`, `python2`
+/// from `python3`, and `c` from `cpp` for headers. Python is taken to be
+/// `python3` unless a Python 2 `print` statement or `except X, e:` clause
+/// appears; a `.h` file is `c` unless it uses C++-only syntax. Languages with
+/// a single dialect have none.
+pub fn detect_dialect(file_path: &Path, content: &str) -> Option<&'static str> {
+    let extension = file_path.extension()?.to_str()?;
+    match extension {
+        "ts" => Some("typescript"),
+        "tsx" => Some("tsx"),
+        "js" | "mjs" | "cjs" => Some("javascript"),
+        "jsx" => Some("jsx"),
+        "py" | "pyw" => Some(if is_python2(content) {
+            "python2"
+        } else {
+            "python3"
+        }),
+        "h" => Some(if is_cpp_header(content) { "cpp" } else { "c" }),
+        "cpp" | "cc" | "cxx" | "c++" | "hpp" | "hxx" => Some("cpp"),
+        _ => None,
+    }
+}
+
+fn is_python2(content: &str) -> bool {
+    content.lines().map(str::trim_start).any(|line| {
+        let print_statement = line
+            .strip_prefix("print ")
+            .is_some_and(|rest| !rest.trim_start().starts_with(['(', '=']));
+        let except_comma = line.starts_with("except ")
+            && line.trim_end().ends_with(':')
+            && line.contains(',')
+            && !line.contains('(');
+        print_statement || except_comma
+    })
+}
+
+fn is_cpp_header(content: &str) -> bool {
+    const CPP_MARKERS: [&str; 6] = [
+        "class ",
+        "namespace ",
+        "template<",
+        "template <",
+        "::",
+        "public:",
+    ];
+    content
+        .lines()
+        .map(str::trim_start)
+        .filter(|line| !line.starts_with("//") && !line.starts_with('*'))
+        .any(|line| CPP_MARKERS.iter().any(|marker| line.contains(marker)))
+}
@@ -8,12 +8,24 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 
+pub mod calls;
+pub mod endpoints;
 pub mod extractors;
+pub mod findings;
+pub mod halstead;
+pub mod imports;
 pub mod languages;
+pub mod overloads;
 pub mod parsers;
+pub mod secrets;
+pub mod sexp;
 pub mod utils;
+pub mod walker;
 
 /// Supported programming languages
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -54,12 +66,26 @@ pub enum EntityType {
     Module,
     Import,
     Export,
+    /// HTTP route discovered by a [`endpoints::RouteMatcher`]
+    Endpoint,
+    /// Rust and TypeScript `enum`, and enums of Java, C# and C++
+    Enum,
+    /// Rust `struct`/`union`, Go struct types and C#/C++ structs
+    Struct,
+    /// TypeScript `type`, Rust `type` and Go non-struct, non-interface types
+    TypeAlias,
+    /// Rust `trait`
+    Trait,
 }
 
 /// Parsed code entity (simplified version for parsing)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeEntity {
     pub id: Uuid,
+    /// The innermost class, interface, module or function enclosing this
+    /// entity; `None` at the top level
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
     pub name: String,
     pub entity_type: EntityType,
     pub file_path: String,
@@ -67,11 +93,17 @@ pub struct CodeEntity {
     pub end_line: u32,
     pub start_column: u32,
     pub end_column: u32,
+    /// Byte offsets of the entity in the source, end exclusive
+    pub start_byte: u32,
+    pub end_byte: u32,
     pub content: String,
     pub signature: Option<String>,
     pub documentation: Option<String>,
     pub visibility: Option<String>,
     pub parameters: Vec<Parameter>,
+    /// Generic parameters of Rust, TypeScript and Java declarations
+    #[serde(default)]
+    pub type_parameters: Vec<TypeParameter>,
     pub return_type: Option<String>,
     pub dependencies: Vec<String>,
     pub metadata: HashMap<String, String>,
@@ -86,16 +118,62 @@ pub struct Parameter {
     pub is_optional: bool,
 }
 
+/// Generic type parameter, e.g. `T: Display + Clone` or `T extends User`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeParameter {
+    /// `T`, or a Rust lifetime such as `'a`
+    pub name: String,
+    /// Each trait, type or lifetime bound separately, including those of a
+    /// Rust `where` clause
+    pub bounds: Vec<String>,
+    pub default_type: Option<String>,
+}
+
 /// Parse result containing extracted entities
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParseResult {
     pub file_path: String,
     pub language: Language,
+    /// Variant of the language, such as `tsx` or `python3`; see
+    /// [`languages::detect_dialect`]
+    #[serde(default)]
+    pub dialect: Option<String>,
     pub entities: Vec<CodeEntity>,
-    pub imports: Vec<String>,
-    pub exports: Vec<String>,
+    pub imports: Vec<imports::ImportStatement>,
+    pub exports: Vec<imports::ExportStatement>,
+    /// `TODO`-style markers found in comments
+    pub findings: Vec<findings::Finding>,
+    /// Calls made anywhere in the file, unresolved
+    #[serde(default)]
+    pub calls: Vec<calls::CallSite>,
     pub errors: Vec<ParseError>,
     pub parse_time_ms: u64,
+    pub summary: FileSummary,
+}
+
+impl ParseResult {
+    /// Distinct modules the file imports, sorted
+    pub fn module_dependencies(&self) -> Vec<String> {
+        let mut modules: Vec<String> = self
+            .imports
+            .iter()
+            .map(|import| import.module.clone())
+            .collect();
+        modules.sort();
+        modules.dedup();
+        modules
+    }
+}
+
+/// Per-file statistics gathered alongside entity extraction
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileSummary {
+    pub total_lines: u32,
+    pub code_lines: u32,
+    pub comment_lines: u32,
+    pub blank_lines: u32,
+    /// Number of extracted entities per type
+    pub entity_counts: HashMap<EntityType, u32>,
 }
 
 /// Parse error information
@@ -115,14 +193,73 @@ pub enum ErrorSeverity {
     Info,
 }
 
+/// Options controlling what a parse extracts
+#[derive(Clone)]
+pub struct ParseOptions {
+    /// Framework matchers used to discover API endpoints
+    pub route_matchers: Vec<Arc<dyn endpoints::RouteMatcher>>,
+    /// Abort a Tree-sitter parse that runs longer than this; `None` never aborts
+    pub parse_timeout: Option<Duration>,
+    /// Fold C/C++ forward declarations into their definitions
+    pub merge_declarations: bool,
+    /// Report each overload set as a single entity with an `overload_count`
+    pub group_overloads: bool,
+    /// Stop extracting entities from a file after visiting this many syntax
+    /// nodes, recording an error; `None` visits the whole tree
+    pub max_node_visits: Option<usize>,
+    /// Comment markers reported as findings; empty reports none
+    pub finding_markers: Vec<String>,
+    /// Report closures, unbound function expressions and unnamed classes,
+    /// named `<anonymous@line>` after their 1-based start line; otherwise
+    /// entities without a name are dropped
+    pub index_anonymous: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            route_matchers: endpoints::default_route_matchers(),
+            parse_timeout: None,
+            merge_declarations: false,
+            group_overloads: false,
+            max_node_visits: None,
+            finding_markers: findings::DEFAULT_MARKERS
+                .iter()
+                .map(|marker| marker.to_string())
+                .collect(),
+            index_anonymous: false,
+        }
+    }
+}
+
 /// Main parser interface
 pub struct CodeParser {
     parsers: HashMap<Language, Box<dyn LanguageParser>>,
+    options: ParseOptions,
 }
 
 /// Language-specific parser trait
 pub trait LanguageParser: Send + Sync {
-    fn parse_file(&self, file_path: &Path, content: &str) -> Result<ParseResult>;
+    fn parse_file(&self, file_path: &Path, content: &str) -> Result<ParseResult> {
+        self.parse_file_with_options(file_path, content, &ParseOptions::default())
+    }
+    fn parse_file_with_options(
+        &self,
+        file_path: &Path,
+        content: &str,
+        options: &ParseOptions,
+    ) -> Result<ParseResult> {
+        self.parse_file_streaming(file_path, content, options, &mut |_| {})
+    }
+    /// Parse a file, passing each entity to `emit` in source order as the
+    /// tree walk finds it; the result holds the same entities
+    fn parse_file_streaming(
+        &self,
+        file_path: &Path,
+        content: &str,
+        options: &ParseOptions,
+        emit: &mut dyn FnMut(&CodeEntity),
+    ) -> Result<ParseResult>;
     fn extract_entities(&self, tree: &tree_sitter::Tree, content: &str) -> Result<Vec<CodeEntity>>;
     fn get_language(&self) -> Language;
 }
@@ -148,7 +285,28 @@ impl CodeParser {
         parsers.insert(Language::Cpp, Box::new(parsers::CppParser::new()));
         parsers.insert(Language::CSharp, Box::new(parsers::CSharpParser::new()));
 
-        Self { parsers }
+        Self {
+            parsers,
+            options: ParseOptions::default(),
+        }
+    }
+
+    /// Create a code parser with custom parse options
+    pub fn with_options(options: ParseOptions) -> Self {
+        Self {
+            options,
+            ..Self::new()
+        }
+    }
+
+    /// Register an additional framework matcher for endpoint discovery
+    pub fn add_route_matcher(&mut self, matcher: Arc<dyn endpoints::RouteMatcher>) {
+        self.options.route_matchers.push(matcher);
+    }
+
+    /// Get the parse options
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
     }
 
     /// Parse a file and extract code entities
@@ -156,12 +314,57 @@ impl CodeParser {
         let language = self.detect_language(file_path)?;
 
         if let Some(parser) = self.parsers.get(&language) {
-            parser.parse_file(file_path, content)
+            parser.parse_file_with_options(file_path, content, &self.options)
         } else {
             anyhow::bail!("Unsupported language: {:?}", language)
         }
     }
 
+    /// Parse a file, sending each entity through `tx` as soon as it is found
+    ///
+    /// Entities arrive in source order, the order [`Self::parse_file`]
+    /// returns them in, so an editor can show the outline of a huge file
+    /// before the parse ends. When [`ParseOptions::merge_declarations`] or
+    /// [`ParseOptions::group_overloads`] is set, entities are sent once the
+    /// walk finishes, since merging needs them all. A dropped receiver does
+    /// not stop the parse.
+    pub fn parse_file_streaming(
+        &self,
+        file_path: &Path,
+        content: &str,
+        tx: &Sender<CodeEntity>,
+    ) -> Result<ParseResult> {
+        let language = self.detect_language(file_path)?;
+
+        if let Some(parser) = self.parsers.get(&language) {
+            parser.parse_file_streaming(file_path, content, &self.options, &mut |entity| {
+                let _ = tx.send(entity.clone());
+            })
+        } else {
+            anyhow::bail!("Unsupported language: {:?}", language)
+        }
+    }
+
+    /// The syntax tree of a file as an S-expression, for debugging extraction
+    ///
+    /// Uses the grammar [`Self::parse_file`] would, including TSX for `.tsx`
+    /// files, and renders it with [`sexp::render`], so nodes deeper than
+    /// `max_depth` are elided. Files in unsupported languages are an error.
+    pub fn parse_to_sexp(
+        &self,
+        file_path: &Path,
+        content: &str,
+        max_depth: Option<usize>,
+    ) -> Result<String> {
+        let language = self.detect_language(file_path)?;
+        let dialect = languages::detect_dialect(file_path, content);
+        let mut parser = parsers::new_parser(parsers::grammar(&language, dialect));
+        let tree = parser
+            .parse(content, None)
+            .ok_or_else(|| anyhow::anyhow!("Failed to parse {} file", language))?;
+        Ok(sexp::render(tree.root_node(), max_depth))
+    }
+
     /// Detect programming language from file extension
     pub fn detect_language(&self, file_path: &Path) -> Result<Language> {
         let extension = file_path
@@ -220,6 +423,11 @@ impl From<ParserEntityType> for CoreEntityType {
             ParserEntityType::Module => CoreEntityType::Type, // Map Module to Type
             ParserEntityType::Import => CoreEntityType::Import,
             ParserEntityType::Export => CoreEntityType::Import, // Map Export to Import
+            ParserEntityType::Endpoint => CoreEntityType::Function, // Map Endpoint to its handler kind
+            ParserEntityType::Enum => CoreEntityType::Enum,
+            ParserEntityType::Struct => CoreEntityType::Class, // Map Struct to Class
+            ParserEntityType::TypeAlias => CoreEntityType::Type,
+            ParserEntityType::Trait => CoreEntityType::Interface, // Map Trait to Interface
         }
     }
 }
@@ -234,13 +442,16 @@ impl From<CoreEntityType> for ParserEntityType {
             CoreEntityType::Import => ParserEntityType::Import,
             CoreEntityType::Type => ParserEntityType::Module, // Map Type to Module
             CoreEntityType::Interface => ParserEntityType::Interface,
-            CoreEntityType::Enum => ParserEntityType::Class, // Map Enum to Class
+            CoreEntityType::Enum => ParserEntityType::Enum,
             CoreEntityType::Constant => ParserEntityType::Constant,
         }
     }
 }
 
 // Re-export utility functions
+pub use calls::CallSite;
+pub use findings::Finding;
+pub use imports::{ExportKind, ExportStatement, ImportKind, ImportStatement, Specifier};
 pub use languages::all_supported_extensions;
 
 // Type aliases for clarity
@@ -308,4 +519,80 @@ mod tests {
         assert!(languages.contains(&Language::Cpp));
         assert!(languages.contains(&Language::CSharp));
     }
+
+    #[test]
+    fn test_streamed_entities_match_batch_parse() {
+        let source = "import { db } from './db';\n\nexport class Cart {\n  total(): number { return 0; }\n  clear(): void {}\n}\n\nfunction checkout(cart: Cart) {\n  const limit = 3;\n  return cart.total();\n}\n";
+        let path = Path::new("cart.ts");
+        let parser = CodeParser::new();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let result = parser.parse_file_streaming(path, source, &tx).unwrap();
+        drop(tx);
+        let streamed: Vec<CodeEntity> = rx.iter().collect();
+
+        let ids = |entities: &[CodeEntity]| entities.iter().map(|e| e.id).collect::<Vec<_>>();
+        assert_eq!(ids(&streamed), ids(&result.entities));
+
+        let outline = |entities: &[CodeEntity]| {
+            entities
+                .iter()
+                .map(|e| {
+                    (
+                        e.name.clone(),
+                        e.entity_type.clone(),
+                        e.start_byte,
+                        e.file_path.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let batch = parser.parse_file(path, source).unwrap().entities;
+        assert!(streamed.len() >= 5);
+        assert_eq!(outline(&streamed), outline(&batch));
+        assert!(streamed.iter().all(|e| e.file_path == "cart.ts"));
+    }
+
+    #[test]
+    fn test_parse_to_sexp() {
+        let parser = CodeParser::new();
+        let path = Path::new("greet.py");
+        let source = "def greet(name):\n    return name\n";
+
+        let sexp = parser.parse_to_sexp(path, source, None).unwrap();
+        assert!(sexp.starts_with("(module\n  (function_definition\n    name: (identifier)"));
+        assert!(sexp.contains("body: (block"));
+        assert!(sexp.contains("(return_statement"));
+
+        let shallow = parser.parse_to_sexp(path, source, Some(1)).unwrap();
+        assert_eq!(shallow, "(module\n  (function_definition ...))");
+
+        let error = parser
+            .parse_to_sexp(Path::new("notes.txt"), "hello", None)
+            .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Unsupported file extension: txt"));
+    }
+
+    #[test]
+    fn test_parse_to_sexp_of_deeply_nested_source() {
+        let depth = 20_000;
+        let source = format!("const x = {}1{};\n", "(".repeat(depth), ")".repeat(depth));
+
+        let sexp = CodeParser::new()
+            .parse_to_sexp(Path::new("nested.js"), &source, None)
+            .unwrap();
+
+        assert!(sexp.matches("(parenthesized_expression").count() >= depth);
+        assert_eq!(sexp.matches('(').count(), sexp.matches(')').count());
+        let last = sexp.lines().last().unwrap().trim_start();
+        assert!(last.starts_with("(number)))))"), "{}", &last[..20]);
+        // Indentation stops growing past a fixed depth
+        let widest = sexp
+            .lines()
+            .map(|line| line.len() - line.trim_start().len())
+            .max();
+        assert_eq!(widest, Some(2 * sexp::MAX_INDENT_DEPTH));
+    }
 }
@@ -0,0 +1,225 @@
+//! Collapsing of forward declarations and overloads
+//!
+//! C++ prototypes and overload sets produce several entities with the same
+//! name a few lines apart. These passes merge them into one entity each, for
+//! callers that want one search result per function rather than per
+//! declaration. Both run only when enabled in [`ParseOptions`].
+//!
+//! [`ParseOptions`]: crate::ParseOptions
+
+use crate::{CodeEntity, EntityType};
+
+/// Metadata key marking a function declared without a body
+pub const DECLARATION_KEY: &str = "declaration";
+
+/// Fold each bodiless declaration into a matching definition in the same file
+///
+/// The definition keeps its own range; the declaration's line is recorded as
+/// `declaration_line`, and its documentation is used when the definition has
+/// none. Declarations without a definition are kept as they are.
+pub fn merge_declarations(entities: Vec<CodeEntity>) -> Vec<CodeEntity> {
+    let (declarations, mut merged): (Vec<_>, Vec<_>) = entities
+        .into_iter()
+        .partition(|entity| entity.metadata.contains_key(DECLARATION_KEY));
+
+    for declaration in declarations {
+        let definition = merged.iter_mut().find(|entity| {
+            entity.entity_type == EntityType::Function
+                && !entity.metadata.contains_key(DECLARATION_KEY)
+                && !entity.metadata.contains_key("declaration_line")
+                && unqualified(&entity.name) == unqualified(&declaration.name)
+                && parameter_types(entity) == parameter_types(&declaration)
+        });
+
+        match definition {
+            Some(definition) => {
+                definition.metadata.insert(
+                    "declaration_line".to_string(),
+                    declaration.start_line.to_string(),
+                );
+                if definition.documentation.is_none() {
+                    definition.documentation = declaration.documentation;
+                }
+            }
+            None => merged.push(declaration),
+        }
+    }
+
+    merged.sort_by_key(|entity| entity.start_byte);
+    merged
+}
+
+/// Keep one entity per overload set, annotated with the set's size
+///
+/// Functions group together when they share a name and enclosing class. The
+/// first overload stands for the set, with `overload_count` and the start
+/// lines of every member in `overload_lines`.
+pub fn group_overloads(entities: Vec<CodeEntity>) -> Vec<CodeEntity> {
+    let scopes: Vec<Option<usize>> = entities
+        .iter()
+        .map(|entity| enclosing_class(&entities, entity))
+        .collect();
+
+    let mut grouped: Vec<CodeEntity> = Vec::new();
+    // `None` for entities that are not functions and never group
+    let mut group_keys: Vec<Option<(Option<usize>, String)>> = Vec::new();
+    let mut group_lines: Vec<Vec<u32>> = Vec::new();
+
+    for (entity, scope) in entities.into_iter().zip(scopes) {
+        if entity.entity_type != EntityType::Function {
+            grouped.push(entity);
+            group_keys.push(None);
+            group_lines.push(Vec::new());
+            continue;
+        }
+
+        let key = (scope, entity.name.clone());
+        match group_keys
+            .iter()
+            .position(|existing| existing.as_ref() == Some(&key))
+        {
+            Some(index) => group_lines[index].push(entity.start_line),
+            None => {
+                group_lines.push(vec![entity.start_line]);
+                group_keys.push(Some(key));
+                grouped.push(entity);
+            }
+        }
+    }
+
+    for (entity, lines) in grouped.iter_mut().zip(group_lines) {
+        if lines.len() > 1 {
+            entity
+                .metadata
+                .insert("overload_count".to_string(), lines.len().to_string());
+            let lines: Vec<String> = lines.iter().map(u32::to_string).collect();
+            entity
+                .metadata
+                .insert("overload_lines".to_string(), lines.join(","));
+        }
+    }
+
+    grouped
+}
+
+/// Index of the innermost class whose byte range contains `entity`
+fn enclosing_class(entities: &[CodeEntity], entity: &CodeEntity) -> Option<usize> {
+    entities
+        .iter()
+        .enumerate()
+        .filter(|(_, candidate)| {
+            matches!(
+                candidate.entity_type,
+                EntityType::Class
+                    | EntityType::Struct
+                    | EntityType::Enum
+                    | EntityType::Interface
+                    | EntityType::Trait
+            ) && candidate.start_byte <= entity.start_byte
+                && entity.end_byte <= candidate.end_byte
+                && !std::ptr::eq(*candidate, entity)
+        })
+        .min_by_key(|(_, candidate)| candidate.end_byte - candidate.start_byte)
+        .map(|(index, _)| index)
+}
+
+/// `Widget::resize` and `resize` name the same function
+fn unqualified(name: &str) -> &str {
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+fn parameter_types(entity: &CodeEntity) -> Vec<Option<&str>> {
+    entity
+        .parameters
+        .iter()
+        .map(|param| param.param_type.as_deref())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CodeParser, EntityType, ParseOptions};
+    use std::path::Path;
+
+    fn functions(options: ParseOptions, file: &str, source: &str) -> Vec<crate::CodeEntity> {
+        CodeParser::with_options(options)
+            .parse_file(Path::new(file), source)
+            .unwrap()
+            .entities
+            .into_iter()
+            .filter(|e| e.entity_type == EntityType::Function)
+            .collect()
+    }
+
+    const CPP: &str = r#"
+// Adds two numbers
+int add(int a, int b);
+double scale(double x);
+
+int add(int a, int b) {
+    return a + b;
+}
+"#;
+
+    #[test]
+    fn test_declaration_merges_into_definition() {
+        let merged = functions(
+            ParseOptions {
+                merge_declarations: true,
+                ..Default::default()
+            },
+            "math.cpp",
+            CPP,
+        );
+
+        let names: Vec<&str> = merged.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["scale", "add"]);
+
+        let add = &merged[1];
+        assert_eq!((add.start_line, add.end_line), (6, 8));
+        assert_eq!(add.metadata["declaration_line"], "3");
+        assert!(!add.metadata.contains_key("declaration"));
+        assert_eq!(add.documentation.as_deref(), Some("Adds two numbers"));
+
+        // A declaration with no definition in the file is kept
+        assert_eq!(merged[0].metadata["declaration"], "true");
+    }
+
+    #[test]
+    fn test_declarations_kept_by_default() {
+        let all = functions(ParseOptions::default(), "math.cpp", CPP);
+        assert_eq!(all.iter().filter(|e| e.name == "add").count(), 2);
+    }
+
+    const JAVA: &str = r#"
+class Printer {
+    void print(String s) {}
+    void print(int i) {}
+    void print(String s, int times) {}
+    void flush() {}
+}
+
+class Logger {
+    void print(String s) {}
+}
+"#;
+
+    #[test]
+    fn test_overloads_grouped_per_class() {
+        let grouped = functions(
+            ParseOptions {
+                group_overloads: true,
+                ..Default::default()
+            },
+            "Printer.java",
+            JAVA,
+        );
+
+        let names: Vec<&str> = grouped.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["print", "flush", "print"]);
+        assert_eq!(grouped[0].metadata["overload_count"], "3");
+        assert_eq!(grouped[0].metadata["overload_lines"], "3,4,5");
+        assert!(!grouped[1].metadata.contains_key("overload_count"));
+        assert!(!grouped[2].metadata.contains_key("overload_count"));
+    }
+}
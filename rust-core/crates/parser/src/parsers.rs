@@ -1,22 +1,108 @@
 //! Language-specific parsers for Code Intelligence MCP Server
 
 use crate::CodeEntity;
-use crate::{Language, LanguageParser, ParseResult};
+use crate::{calls, findings, imports, languages, walker};
+use crate::{Language, LanguageParser, ParseOptions, ParseResult};
 use anyhow::Result;
 use std::path::Path;
 use std::sync::Mutex;
+use std::time::Instant;
 use tree_sitter::Parser;
 
+/// The Tree-sitter grammar for a language, picking TSX for the `tsx` dialect
+pub fn grammar(language: &Language, dialect: Option<&str>) -> tree_sitter::Language {
+    match language {
+        Language::TypeScript if dialect == Some("tsx") => tree_sitter_typescript::language_tsx(),
+        Language::TypeScript => tree_sitter_typescript::language_typescript(),
+        Language::JavaScript => tree_sitter_javascript::language(),
+        Language::Python => tree_sitter_python::language(),
+        Language::Rust => tree_sitter_rust::language(),
+        Language::Go => tree_sitter_go::language(),
+        Language::Java => tree_sitter_java::language(),
+        Language::Cpp => tree_sitter_cpp::language(),
+        Language::CSharp => tree_sitter_c_sharp::language(),
+    }
+}
+
+/// Create a Tree-sitter parser bound to a grammar
+pub(crate) fn new_parser(language: tree_sitter::Language) -> Parser {
+    let mut parser = Parser::new();
+    parser
+        .set_language(language)
+        .expect("Tree-sitter grammar is incompatible with the runtime");
+    parser
+}
+
+/// Parse a file with a language parser's grammar and extract its entities
+fn parse_with(
+    language_parser: &dyn LanguageParser,
+    parser: &Mutex<Parser>,
+    file_path: &Path,
+    content: &str,
+    options: &ParseOptions,
+    emit: &mut dyn FnMut(&CodeEntity),
+) -> Result<ParseResult> {
+    let start = Instant::now();
+    let language = language_parser.get_language();
+    let tree = {
+        let mut parser = parser.lock().unwrap();
+        let timeout = options
+            .parse_timeout
+            .map_or(0, |t| t.as_micros().max(1) as u64);
+        parser.set_timeout_micros(timeout);
+        match parser.parse(content, None) {
+            Some(tree) => tree,
+            None => {
+                // A timed-out parse would otherwise resume on the next call
+                parser.reset();
+                match options.parse_timeout {
+                    Some(limit) => {
+                        anyhow::bail!("Parsing {} file timed out after {:?}", language, limit)
+                    }
+                    None => anyhow::bail!("Failed to parse {} file", language),
+                }
+            }
+        }
+    };
+
+    let dialect = languages::detect_dialect(file_path, content).map(String::from);
+    let file_path = file_path.to_string_lossy().to_string();
+    let walker::WalkOutput {
+        entities,
+        errors,
+        summary,
+    } = walker::walk_streaming(&tree, content, &language, &file_path, options, emit);
+    let (imports, exports) = imports::extract(&tree, content, &language);
+    let findings = findings::extract(&tree, content, &options.finding_markers);
+    let calls = calls::extract(&tree, content, &language);
+
+    Ok(ParseResult {
+        file_path,
+        language,
+        dialect,
+        entities,
+        imports,
+        exports,
+        findings,
+        calls,
+        errors,
+        parse_time_ms: start.elapsed().as_millis() as u64,
+        summary,
+    })
+}
+
+/// Parses `.ts` files with the TypeScript grammar and `.tsx` files with the
+/// TSX grammar, which also accepts JSX
 pub struct TypeScriptParser {
     parser: Mutex<Parser>,
+    tsx_parser: Mutex<Parser>,
 }
 
 impl TypeScriptParser {
     pub fn new() -> Self {
-        let parser = Parser::new();
-        // TODO: Set language when tree-sitter-typescript is available
         Self {
-            parser: Mutex::new(parser),
+            parser: Mutex::new(new_parser(tree_sitter_typescript::language_typescript())),
+            tsx_parser: Mutex::new(new_parser(tree_sitter_typescript::language_tsx())),
         }
     }
 }
@@ -28,34 +114,26 @@ impl Default for TypeScriptParser {
 }
 
 impl LanguageParser for TypeScriptParser {
-    fn parse_file(&self, file_path: &Path, content: &str) -> Result<ParseResult> {
-        let tree = self
-            .parser
-            .lock()
-            .unwrap()
-            .parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse TypeScript file"))?;
-        let entities = self.extract_entities(&tree, content)?;
-
-        Ok(ParseResult {
-            file_path: file_path.to_string_lossy().to_string(),
-            language: Language::TypeScript,
-            entities,
-            imports: vec![],
-            exports: vec![],
-            errors: vec![],
-            parse_time_ms: 0,
-        })
-    }
-
-    fn extract_entities(
+    fn parse_file_streaming(
         &self,
-        _tree: &tree_sitter::Tree,
-        _content: &str,
-    ) -> Result<Vec<CodeEntity>> {
-        // TODO: Implement TypeScript entity extraction
-        // Rule 15: Replace placeholder with proper implementation
-        Ok(Vec::new())
+        file_path: &Path,
+        content: &str,
+        options: &ParseOptions,
+        emit: &mut dyn FnMut(&CodeEntity),
+    ) -> Result<ParseResult> {
+        let parser = match languages::detect_dialect(file_path, content) {
+            Some("tsx") => &self.tsx_parser,
+            _ => &self.parser,
+        };
+        parse_with(self, parser, file_path, content, options, emit)
+    }
+
+    fn extract_entities(&self, tree: &tree_sitter::Tree, content: &str) -> Result<Vec<CodeEntity>> {
+        Ok(walker::extract_entities(
+            tree,
+            content,
+            &self.get_language(),
+        ))
     }
 
     fn get_language(&self) -> Language {
@@ -69,10 +147,8 @@ pub struct JavaScriptParser {
 
 impl JavaScriptParser {
     pub fn new() -> Self {
-        let parser = Parser::new();
-        // TODO: Set language when tree-sitter-javascript is available
         Self {
-            parser: Mutex::new(parser),
+            parser: Mutex::new(new_parser(tree_sitter_javascript::language())),
         }
     }
 }
@@ -84,34 +160,22 @@ impl Default for JavaScriptParser {
 }
 
 impl LanguageParser for JavaScriptParser {
-    fn parse_file(&self, file_path: &Path, content: &str) -> Result<ParseResult> {
-        let tree = self
-            .parser
-            .lock()
-            .unwrap()
-            .parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse JavaScript file"))?;
-        let entities = self.extract_entities(&tree, content)?;
-
-        Ok(ParseResult {
-            file_path: file_path.to_string_lossy().to_string(),
-            language: Language::JavaScript,
-            entities,
-            imports: vec![],
-            exports: vec![],
-            errors: vec![],
-            parse_time_ms: 0,
-        })
-    }
-
-    fn extract_entities(
+    fn parse_file_streaming(
         &self,
-        _tree: &tree_sitter::Tree,
-        _content: &str,
-    ) -> Result<Vec<CodeEntity>> {
-        // TODO: Implement JavaScript entity extraction
-        // Rule 15: Replace placeholder with proper implementation
-        Ok(Vec::new())
+        file_path: &Path,
+        content: &str,
+        options: &ParseOptions,
+        emit: &mut dyn FnMut(&CodeEntity),
+    ) -> Result<ParseResult> {
+        parse_with(self, &self.parser, file_path, content, options, emit)
+    }
+
+    fn extract_entities(&self, tree: &tree_sitter::Tree, content: &str) -> Result<Vec<CodeEntity>> {
+        Ok(walker::extract_entities(
+            tree,
+            content,
+            &self.get_language(),
+        ))
     }
 
     fn get_language(&self) -> Language {
@@ -125,10 +189,8 @@ pub struct PythonParser {
 
 impl PythonParser {
     pub fn new() -> Self {
-        let parser = Parser::new();
-        // TODO: Set language when tree-sitter-python is available
         Self {
-            parser: Mutex::new(parser),
+            parser: Mutex::new(new_parser(tree_sitter_python::language())),
         }
     }
 }
@@ -140,34 +202,22 @@ impl Default for PythonParser {
 }
 
 impl LanguageParser for PythonParser {
-    fn parse_file(&self, file_path: &Path, content: &str) -> Result<ParseResult> {
-        let tree = self
-            .parser
-            .lock()
-            .unwrap()
-            .parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse Python file"))?;
-        let entities = self.extract_entities(&tree, content)?;
-
-        Ok(ParseResult {
-            file_path: file_path.to_string_lossy().to_string(),
-            language: Language::Python,
-            entities,
-            imports: vec![],
-            exports: vec![],
-            errors: vec![],
-            parse_time_ms: 0,
-        })
-    }
-
-    fn extract_entities(
+    fn parse_file_streaming(
         &self,
-        _tree: &tree_sitter::Tree,
-        _content: &str,
-    ) -> Result<Vec<CodeEntity>> {
-        // TODO: Implement Python entity extraction
-        // Rule 15: Replace placeholder with proper implementation
-        Ok(Vec::new())
+        file_path: &Path,
+        content: &str,
+        options: &ParseOptions,
+        emit: &mut dyn FnMut(&CodeEntity),
+    ) -> Result<ParseResult> {
+        parse_with(self, &self.parser, file_path, content, options, emit)
+    }
+
+    fn extract_entities(&self, tree: &tree_sitter::Tree, content: &str) -> Result<Vec<CodeEntity>> {
+        Ok(walker::extract_entities(
+            tree,
+            content,
+            &self.get_language(),
+        ))
     }
 
     fn get_language(&self) -> Language {
@@ -181,10 +231,8 @@ pub struct RustParser {
 
 impl RustParser {
     pub fn new() -> Self {
-        let parser = Parser::new();
-        // TODO: Set language when tree-sitter-rust is available
         Self {
-            parser: Mutex::new(parser),
+            parser: Mutex::new(new_parser(tree_sitter_rust::language())),
         }
     }
 }
@@ -196,34 +244,22 @@ impl Default for RustParser {
 }
 
 impl LanguageParser for RustParser {
-    fn parse_file(&self, file_path: &Path, content: &str) -> Result<ParseResult> {
-        let tree = self
-            .parser
-            .lock()
-            .unwrap()
-            .parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse Rust file"))?;
-        let entities = self.extract_entities(&tree, content)?;
-
-        Ok(ParseResult {
-            file_path: file_path.to_string_lossy().to_string(),
-            language: Language::Rust,
-            entities,
-            imports: vec![],
-            exports: vec![],
-            errors: vec![],
-            parse_time_ms: 0,
-        })
-    }
-
-    fn extract_entities(
+    fn parse_file_streaming(
         &self,
-        _tree: &tree_sitter::Tree,
-        _content: &str,
-    ) -> Result<Vec<CodeEntity>> {
-        // TODO: Implement Rust entity extraction
-        // Rule 15: Replace placeholder with proper implementation
-        Ok(Vec::new())
+        file_path: &Path,
+        content: &str,
+        options: &ParseOptions,
+        emit: &mut dyn FnMut(&CodeEntity),
+    ) -> Result<ParseResult> {
+        parse_with(self, &self.parser, file_path, content, options, emit)
+    }
+
+    fn extract_entities(&self, tree: &tree_sitter::Tree, content: &str) -> Result<Vec<CodeEntity>> {
+        Ok(walker::extract_entities(
+            tree,
+            content,
+            &self.get_language(),
+        ))
     }
 
     fn get_language(&self) -> Language {
@@ -237,10 +273,8 @@ pub struct GoParser {
 
 impl GoParser {
     pub fn new() -> Self {
-        let parser = Parser::new();
-        // TODO: Set language when tree-sitter-go is available
         Self {
-            parser: Mutex::new(parser),
+            parser: Mutex::new(new_parser(tree_sitter_go::language())),
         }
     }
 }
@@ -252,34 +286,22 @@ impl Default for GoParser {
 }
 
 impl LanguageParser for GoParser {
-    fn parse_file(&self, file_path: &Path, content: &str) -> Result<ParseResult> {
-        let tree = self
-            .parser
-            .lock()
-            .unwrap()
-            .parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse Go file"))?;
-        let entities = self.extract_entities(&tree, content)?;
-
-        Ok(ParseResult {
-            file_path: file_path.to_string_lossy().to_string(),
-            language: Language::Go,
-            entities,
-            imports: vec![],
-            exports: vec![],
-            errors: vec![],
-            parse_time_ms: 0,
-        })
-    }
-
-    fn extract_entities(
+    fn parse_file_streaming(
         &self,
-        _tree: &tree_sitter::Tree,
-        _content: &str,
-    ) -> Result<Vec<CodeEntity>> {
-        // TODO: Implement Go entity extraction
-        // Rule 15: Replace placeholder with proper implementation
-        Ok(Vec::new())
+        file_path: &Path,
+        content: &str,
+        options: &ParseOptions,
+        emit: &mut dyn FnMut(&CodeEntity),
+    ) -> Result<ParseResult> {
+        parse_with(self, &self.parser, file_path, content, options, emit)
+    }
+
+    fn extract_entities(&self, tree: &tree_sitter::Tree, content: &str) -> Result<Vec<CodeEntity>> {
+        Ok(walker::extract_entities(
+            tree,
+            content,
+            &self.get_language(),
+        ))
     }
 
     fn get_language(&self) -> Language {
@@ -293,10 +315,8 @@ pub struct JavaParser {
 
 impl JavaParser {
     pub fn new() -> Self {
-        let parser = Parser::new();
-        // TODO: Set language when tree-sitter-java is available
         Self {
-            parser: Mutex::new(parser),
+            parser: Mutex::new(new_parser(tree_sitter_java::language())),
         }
     }
 }
@@ -308,34 +328,22 @@ impl Default for JavaParser {
 }
 
 impl LanguageParser for JavaParser {
-    fn parse_file(&self, file_path: &Path, content: &str) -> Result<ParseResult> {
-        let tree = self
-            .parser
-            .lock()
-            .unwrap()
-            .parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse Java file"))?;
-        let entities = self.extract_entities(&tree, content)?;
-
-        Ok(ParseResult {
-            file_path: file_path.to_string_lossy().to_string(),
-            language: Language::Java,
-            entities,
-            imports: vec![],
-            exports: vec![],
-            errors: vec![],
-            parse_time_ms: 0,
-        })
-    }
-
-    fn extract_entities(
+    fn parse_file_streaming(
         &self,
-        _tree: &tree_sitter::Tree,
-        _content: &str,
-    ) -> Result<Vec<CodeEntity>> {
-        // TODO: Implement Java entity extraction
-        // Rule 15: Replace placeholder with proper implementation
-        Ok(Vec::new())
+        file_path: &Path,
+        content: &str,
+        options: &ParseOptions,
+        emit: &mut dyn FnMut(&CodeEntity),
+    ) -> Result<ParseResult> {
+        parse_with(self, &self.parser, file_path, content, options, emit)
+    }
+
+    fn extract_entities(&self, tree: &tree_sitter::Tree, content: &str) -> Result<Vec<CodeEntity>> {
+        Ok(walker::extract_entities(
+            tree,
+            content,
+            &self.get_language(),
+        ))
     }
 
     fn get_language(&self) -> Language {
@@ -349,10 +357,8 @@ pub struct CppParser {
 
 impl CppParser {
     pub fn new() -> Self {
-        let parser = Parser::new();
-        // TODO: Set language when tree-sitter-cpp is available
         Self {
-            parser: Mutex::new(parser),
+            parser: Mutex::new(new_parser(tree_sitter_cpp::language())),
         }
     }
 }
@@ -364,34 +370,22 @@ impl Default for CppParser {
 }
 
 impl LanguageParser for CppParser {
-    fn parse_file(&self, file_path: &Path, content: &str) -> Result<ParseResult> {
-        let tree = self
-            .parser
-            .lock()
-            .unwrap()
-            .parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse C++ file"))?;
-        let entities = self.extract_entities(&tree, content)?;
-
-        Ok(ParseResult {
-            file_path: file_path.to_string_lossy().to_string(),
-            language: Language::Cpp,
-            entities,
-            imports: vec![],
-            exports: vec![],
-            errors: vec![],
-            parse_time_ms: 0,
-        })
-    }
-
-    fn extract_entities(
+    fn parse_file_streaming(
         &self,
-        _tree: &tree_sitter::Tree,
-        _content: &str,
-    ) -> Result<Vec<CodeEntity>> {
-        // TODO: Implement C++ entity extraction
-        // Rule 15: Replace placeholder with proper implementation
-        Ok(Vec::new())
+        file_path: &Path,
+        content: &str,
+        options: &ParseOptions,
+        emit: &mut dyn FnMut(&CodeEntity),
+    ) -> Result<ParseResult> {
+        parse_with(self, &self.parser, file_path, content, options, emit)
+    }
+
+    fn extract_entities(&self, tree: &tree_sitter::Tree, content: &str) -> Result<Vec<CodeEntity>> {
+        Ok(walker::extract_entities(
+            tree,
+            content,
+            &self.get_language(),
+        ))
     }
 
     fn get_language(&self) -> Language {
@@ -405,10 +399,8 @@ pub struct CSharpParser {
 
 impl CSharpParser {
     pub fn new() -> Self {
-        let parser = Parser::new();
-        // TODO: Set language when tree-sitter-c-sharp is available
         Self {
-            parser: Mutex::new(parser),
+            parser: Mutex::new(new_parser(tree_sitter_c_sharp::language())),
         }
     }
 }
@@ -420,37 +412,635 @@ impl Default for CSharpParser {
 }
 
 impl LanguageParser for CSharpParser {
-    fn parse_file(&self, file_path: &Path, content: &str) -> Result<ParseResult> {
-        let tree = self
-            .parser
-            .lock()
-            .unwrap()
-            .parse(content, None)
-            .ok_or_else(|| anyhow::anyhow!("Failed to parse C# file"))?;
-        let entities = self.extract_entities(&tree, content)?;
-
-        Ok(ParseResult {
-            file_path: file_path.to_string_lossy().to_string(),
-            language: Language::CSharp,
-            entities,
-            imports: vec![],
-            exports: vec![],
-            errors: vec![],
-            parse_time_ms: 0,
-        })
-    }
-
-    fn extract_entities(
+    fn parse_file_streaming(
         &self,
-        _tree: &tree_sitter::Tree,
-        _content: &str,
-    ) -> Result<Vec<CodeEntity>> {
-        // TODO: Implement C# entity extraction
-        // Rule 15: Replace placeholder with proper implementation
-        Ok(Vec::new())
+        file_path: &Path,
+        content: &str,
+        options: &ParseOptions,
+        emit: &mut dyn FnMut(&CodeEntity),
+    ) -> Result<ParseResult> {
+        parse_with(self, &self.parser, file_path, content, options, emit)
+    }
+
+    fn extract_entities(&self, tree: &tree_sitter::Tree, content: &str) -> Result<Vec<CodeEntity>> {
+        Ok(walker::extract_entities(
+            tree,
+            content,
+            &self.get_language(),
+        ))
     }
 
     fn get_language(&self) -> Language {
         Language::CSharp
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EntityType, Parameter, TypeParameter};
+
+    fn function_params(
+        parser: &dyn LanguageParser,
+        file: &str,
+        source: &str,
+        name: &str,
+    ) -> Vec<Parameter> {
+        let result = parser.parse_file(Path::new(file), source).unwrap();
+        result
+            .entities
+            .into_iter()
+            .find(|e| e.name == name && e.entity_type == EntityType::Function)
+            .unwrap_or_else(|| panic!("function {} not extracted", name))
+            .parameters
+    }
+
+    fn entity_types(
+        parser: &dyn LanguageParser,
+        file: &str,
+        source: &str,
+    ) -> Vec<(String, EntityType)> {
+        parser
+            .parse_file(Path::new(file), source)
+            .unwrap()
+            .entities
+            .into_iter()
+            .map(|e| (e.name, e.entity_type))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_type_declarations() {
+        let source = r#"
+pub struct Point { x: i32 }
+enum Shape { Circle, Square }
+trait Draw { fn draw(&self); }
+type Coord = (i32, i32);
+"#;
+        let types = entity_types(&RustParser::new(), "shapes.rs", source);
+        let expected = [
+            ("Point", EntityType::Struct),
+            ("Shape", EntityType::Enum),
+            ("Draw", EntityType::Trait),
+            ("draw", EntityType::Function),
+            ("Coord", EntityType::TypeAlias),
+        ];
+        for (name, entity_type) in expected {
+            assert!(
+                types.contains(&(name.to_string(), entity_type.clone())),
+                "{} should be {:?} in {:?}",
+                name,
+                entity_type,
+                types
+            );
+        }
+    }
+
+    fn type_params(
+        parser: &dyn LanguageParser,
+        file: &str,
+        source: &str,
+        name: &str,
+    ) -> Vec<TypeParameter> {
+        let result = parser.parse_file(Path::new(file), source).unwrap();
+        result
+            .entities
+            .into_iter()
+            .find(|e| e.name == name)
+            .unwrap_or_else(|| panic!("no entity named {}", name))
+            .type_parameters
+    }
+
+    fn type_param(name: &str, bounds: &[&str], default_type: Option<&str>) -> TypeParameter {
+        TypeParameter {
+            name: name.to_string(),
+            bounds: bounds.iter().map(|b| b.to_string()).collect(),
+            default_type: default_type.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_type_parameters() {
+        let rust = "fn show<'a, T: Display + Clone, U = u8>(x: &'a T) where U: Debug {}\nstruct Repository<T> { items: Vec<T> }\n";
+        assert_eq!(
+            type_params(&RustParser::new(), "lib.rs", rust, "show"),
+            vec![
+                type_param("'a", &[], None),
+                type_param("T", &["Display", "Clone"], None),
+                type_param("U", &["Debug"], Some("u8")),
+            ]
+        );
+        assert_eq!(
+            type_params(&RustParser::new(), "lib.rs", rust, "Repository"),
+            vec![type_param("T", &[], None)]
+        );
+
+        let typescript = "class Repository<T extends Entity, K = string> {}\nconst first = <T,>(items: T[]) => items[0];\n";
+        assert_eq!(
+            type_params(
+                &TypeScriptParser::new(),
+                "repo.ts",
+                typescript,
+                "Repository"
+            ),
+            vec![
+                type_param("T", &["Entity"], None),
+                type_param("K", &[], Some("string")),
+            ]
+        );
+        assert_eq!(
+            type_params(&TypeScriptParser::new(), "repo.ts", typescript, "first"),
+            vec![type_param("T", &[], None)]
+        );
+
+        let java = "class Repository<T extends Comparable<T> & Serializable> { <U> U get() { return null; } }";
+        assert_eq!(
+            type_params(&JavaParser::new(), "Repository.java", java, "Repository"),
+            vec![type_param("T", &["Comparable<T>", "Serializable"], None)]
+        );
+        assert_eq!(
+            type_params(&JavaParser::new(), "Repository.java", java, "get"),
+            vec![type_param("U", &[], None)]
+        );
+    }
+
+    #[test]
+    fn test_typescript_type_declarations() {
+        let source = "export type UserId = string;\nenum Color { Red, Green }\ninterface User { id: UserId }\n";
+        let parsed = TypeScriptParser::new()
+            .parse_file(Path::new("types.ts"), source)
+            .unwrap();
+        let types: Vec<(&str, &EntityType)> = parsed
+            .entities
+            .iter()
+            .map(|e| (e.name.as_str(), &e.entity_type))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("UserId", &EntityType::TypeAlias),
+                ("Color", &EntityType::Enum),
+                ("User", &EntityType::Interface),
+            ]
+        );
+        assert_eq!(
+            parsed.entities[0].signature.as_deref(),
+            Some("type UserId = string")
+        );
+    }
+
+    #[test]
+    fn test_go_type_declarations() {
+        let source = "package shop\n\ntype Cart struct { items []string }\ntype Store interface { Get() }\ntype ID string\n";
+        let types = entity_types(&GoParser::new(), "shop.go", source);
+        assert!(types.contains(&("Cart".to_string(), EntityType::Struct)));
+        assert!(types.contains(&("Store".to_string(), EntityType::Interface)));
+        assert!(types.contains(&("ID".to_string(), EntityType::TypeAlias)));
+    }
+
+    #[test]
+    fn test_typescript_parameters() {
+        let source = r#"
+export function greet(name: string, title?: string, times = 1, ...rest: string[]): void {}
+"#;
+        let params = function_params(&TypeScriptParser::new(), "test.ts", source, "greet");
+
+        assert_eq!(params.len(), 4);
+        assert_eq!(params[0].name, "name");
+        assert_eq!(params[0].param_type.as_deref(), Some("string"));
+        assert!(!params[0].is_optional);
+        assert_eq!(params[1].name, "title");
+        assert!(params[1].is_optional);
+        assert_eq!(params[2].name, "times");
+        assert_eq!(params[2].default_value.as_deref(), Some("1"));
+        assert!(params[2].is_optional);
+        assert_eq!(params[3].name, "...rest");
+        assert_eq!(params[3].param_type.as_deref(), Some("string[]"));
+        assert!(params[3].is_optional);
+    }
+
+    #[test]
+    fn test_javascript_arrow_parameters() {
+        let source = "const add = (a, b = 2, ...more) => a + b;\n";
+        let params = function_params(&JavaScriptParser::new(), "test.js", source, "add");
+
+        assert_eq!(params.len(), 3);
+        assert!(!params[0].is_optional);
+        assert_eq!(params[1].default_value.as_deref(), Some("2"));
+        assert!(params[1].is_optional);
+        assert_eq!(params[2].name, "...more");
+    }
+
+    #[test]
+    fn test_python_parameters() {
+        let source = r#"
+class Greeter:
+    def greet(self, name: str, title="Dr", *args, retries: int = 3, **kwargs) -> str:
+        """Greet someone."""
+        return name
+"#;
+        let result = PythonParser::new()
+            .parse_file(Path::new("test.py"), source)
+            .unwrap();
+        let greet = result.entities.iter().find(|e| e.name == "greet").unwrap();
+        let params = &greet.parameters;
+
+        assert_eq!(params.len(), 6);
+        assert_eq!(params[0].name, "self");
+        assert_eq!(params[1].param_type.as_deref(), Some("str"));
+        assert!(!params[1].is_optional);
+        assert_eq!(params[2].default_value.as_deref(), Some("\"Dr\""));
+        assert!(params[2].is_optional);
+        assert_eq!(params[3].name, "*args");
+        assert!(params[3].is_optional);
+        assert_eq!(params[4].param_type.as_deref(), Some("int"));
+        assert_eq!(params[4].default_value.as_deref(), Some("3"));
+        assert_eq!(params[5].name, "**kwargs");
+        assert_eq!(greet.return_type.as_deref(), Some("str"));
+        assert_eq!(greet.documentation.as_deref(), Some("Greet someone."));
+        assert!(result.entities.iter().any(|e| e.name == "Greeter"));
+    }
+
+    #[test]
+    fn test_rust_parameters() {
+        let source = r#"
+impl Counter {
+    pub fn add(&mut self, amount: u32, label: &str) -> u32 {
+        amount
+    }
+}
+"#;
+        let result = RustParser::new()
+            .parse_file(Path::new("test.rs"), source)
+            .unwrap();
+        let add = result.entities.iter().find(|e| e.name == "add").unwrap();
+
+        assert_eq!(add.parameters.len(), 3);
+        assert_eq!(add.parameters[0].name, "self");
+        assert_eq!(add.parameters[0].param_type.as_deref(), Some("&mut self"));
+        assert_eq!(add.parameters[1].param_type.as_deref(), Some("u32"));
+        assert_eq!(add.parameters[2].param_type.as_deref(), Some("&str"));
+        assert!(add.parameters.iter().all(|p| !p.is_optional));
+        assert_eq!(add.return_type.as_deref(), Some("u32"));
+        assert_eq!(add.visibility.as_deref(), Some("public"));
+    }
+
+    #[test]
+    fn test_go_parameters() {
+        let source = r#"
+package main
+
+func Join(sep string, a, b int, rest ...string) string {
+	return sep
+}
+"#;
+        let params = function_params(&GoParser::new(), "test.go", source, "Join");
+
+        assert_eq!(params.len(), 4);
+        assert_eq!(params[1].name, "a");
+        assert_eq!(params[2].name, "b");
+        assert_eq!(params[2].param_type.as_deref(), Some("int"));
+        assert!(!params[2].is_optional);
+        assert_eq!(params[3].param_type.as_deref(), Some("...string"));
+        assert!(params[3].is_optional);
+    }
+
+    #[test]
+    fn test_java_parameters() {
+        let source = r#"
+public class Greeter {
+    public String greet(String name, Object... args) { return name; }
+}
+"#;
+        let params = function_params(&JavaParser::new(), "Test.java", source, "greet");
+
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[0].param_type.as_deref(), Some("String"));
+        assert!(!params[0].is_optional);
+        assert_eq!(params[1].name, "args");
+        assert!(params[1].is_optional);
+    }
+
+    #[test]
+    fn test_cpp_parameters() {
+        let source = "int scale(int value, double factor = 2.0) { return value; }\n";
+        let params = function_params(&CppParser::new(), "test.cpp", source, "scale");
+
+        assert_eq!(params.len(), 2);
+        assert!(!params[0].is_optional);
+        assert_eq!(params[1].name, "factor");
+        assert_eq!(params[1].default_value.as_deref(), Some("2.0"));
+        assert!(params[1].is_optional);
+    }
+
+    #[test]
+    fn test_csharp_parameters() {
+        let source = r#"
+class Greeter {
+    public string Greet(string name, int times = 1) { return name; }
+}
+"#;
+        let params = function_params(&CSharpParser::new(), "Test.cs", source, "Greet");
+
+        assert_eq!(params.len(), 2);
+        assert!(!params[0].is_optional);
+        assert_eq!(params[1].default_value.as_deref(), Some("1"));
+        assert!(params[1].is_optional);
+    }
+
+    #[test]
+    fn test_file_summary_matches_entities() {
+        let source = r#"import { a } from "./a";
+
+// Adds numbers
+function add(x: number, y: number): number {
+    return x + y;
+}
+
+/*
+ * A point
+ */
+class Point {}
+const ORIGIN = new Point();
+"#;
+        let result = TypeScriptParser::new()
+            .parse_file(Path::new("test.ts"), source)
+            .unwrap();
+        let summary = &result.summary;
+
+        assert_eq!(summary.total_lines, 12);
+        assert_eq!(summary.blank_lines, 2);
+        assert_eq!(summary.comment_lines, 4);
+        assert_eq!(summary.code_lines, 6);
+
+        let mut expected = std::collections::HashMap::new();
+        for entity in &result.entities {
+            *expected.entry(entity.entity_type.clone()).or_insert(0) += 1;
+        }
+        assert_eq!(summary.entity_counts, expected);
+        assert_eq!(summary.entity_counts.get(&EntityType::Function), Some(&1));
+        assert_eq!(summary.entity_counts.get(&EntityType::Class), Some(&1));
+        assert_eq!(summary.entity_counts.get(&EntityType::Constant), Some(&1));
+        assert_eq!(summary.entity_counts.get(&EntityType::Import), Some(&1));
+    }
+
+    #[test]
+    fn test_parse_timeout_reports_error() {
+        let source: String = (0..5000)
+            .map(|i| format!("function f{}(a: number) {{ return a * {}; }}\n", i, i))
+            .collect();
+        let parser = TypeScriptParser::new();
+        let options = ParseOptions {
+            parse_timeout: Some(std::time::Duration::from_micros(1)),
+            ..Default::default()
+        };
+
+        let error = parser
+            .parse_file_with_options(Path::new("big.ts"), &source, &options)
+            .unwrap_err();
+        assert!(error.to_string().contains("timed out"));
+
+        // The parser is reset, so the next parse starts from scratch
+        let result = parser
+            .parse_file(Path::new("small.ts"), "function ok() {}\n")
+            .unwrap();
+        assert_eq!(result.entities.len(), 1);
+        assert_eq!(result.entities[0].name, "ok");
+    }
+
+    #[test]
+    fn test_deeply_nested_source_does_not_overflow() {
+        let depth = 20_000;
+        let source = format!(
+            "function outer() {{}}\nconst x = {}1{};\n",
+            "(".repeat(depth),
+            ")".repeat(depth)
+        );
+        let parser = JavaScriptParser::new();
+
+        let result = parser.parse_file(Path::new("nested.js"), &source).unwrap();
+        assert!(result.entities.iter().any(|e| e.name == "outer"));
+        assert!(result.errors.is_empty());
+
+        let options = ParseOptions {
+            max_node_visits: Some(100),
+            ..Default::default()
+        };
+        let result = parser
+            .parse_file_with_options(Path::new("nested.js"), &source, &options)
+            .unwrap();
+        assert!(result.entities.iter().any(|e| e.name == "outer"));
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0]
+            .message
+            .contains("aborted after visiting 100 nodes"));
+    }
+
+    #[test]
+    fn test_entities_record_their_enclosing_entity() {
+        let source = "class Shop:\n    def checkout(self):\n        def total():\n            return 0\n        return total()\n\ndef main():\n    pass\n";
+        let entities = PythonParser::new()
+            .parse_file(Path::new("shop.py"), source)
+            .unwrap()
+            .entities;
+        let parent_name = |name: &str| -> Option<String> {
+            let entity = entities.iter().find(|e| e.name == name).unwrap();
+            entity
+                .parent_id
+                .map(|id| entities.iter().find(|e| e.id == id).unwrap().name.clone())
+        };
+
+        assert_eq!(parent_name("Shop"), None);
+        assert_eq!(parent_name("checkout").as_deref(), Some("Shop"));
+        assert_eq!(parent_name("total").as_deref(), Some("checkout"));
+        assert_eq!(parent_name("main"), None);
+    }
+
+    #[test]
+    fn test_functions_record_their_nesting_depth() {
+        let source = r#"
+function flat(a) {
+    return a + 1;
+}
+
+function nested(items) {
+    for (const item of items) {
+        if (item.ready) {
+            try {
+                item.step();
+            } catch (e) {
+                if (e.fatal) {
+                    throw e;
+                } else if (e.retry) {
+                    continue;
+                }
+            }
+        }
+    }
+}
+"#;
+        let entities = TypeScriptParser::new()
+            .parse_file(Path::new("nesting.ts"), source)
+            .unwrap()
+            .entities;
+        let depth = |name: &str| -> Option<String> {
+            entities
+                .iter()
+                .find(|e| e.name == name)
+                .and_then(|e| e.metadata.get(walker::NESTING_DEPTH_KEY).cloned())
+        };
+
+        assert_eq!(depth("flat").as_deref(), Some("0"));
+        // for > if > try > if; the `else if` stays at the same level
+        assert_eq!(depth("nested").as_deref(), Some("4"));
+    }
+
+    fn annotations_of(
+        parser: &dyn LanguageParser,
+        file: &str,
+        source: &str,
+    ) -> Vec<(String, Vec<String>)> {
+        parser
+            .parse_file(Path::new(file), source)
+            .unwrap()
+            .entities
+            .into_iter()
+            .filter(|e| e.entity_type != EntityType::Import)
+            .map(|e| {
+                let annotations = e
+                    .metadata
+                    .get(walker::ANNOTATIONS_KEY)
+                    .map(|json| serde_json::from_str(json).unwrap())
+                    .unwrap_or_default();
+                (e.name, annotations)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_attributes_are_captured() {
+        let source = r#"
+/// Checks parsing
+#[test]
+#[should_panic(expected = "bad")]
+fn rejects_input() {}
+
+#[derive(Debug, Clone)]
+pub struct Config;
+
+fn plain() {}
+"#;
+        assert_eq!(
+            annotations_of(&RustParser::new(), "lib.rs", source),
+            vec![
+                (
+                    "rejects_input".to_string(),
+                    vec![
+                        "test".to_string(),
+                        r#"should_panic(expected = "bad")"#.to_string()
+                    ]
+                ),
+                (
+                    "Config".to_string(),
+                    vec!["derive(Debug, Clone)".to_string()]
+                ),
+                ("plain".to_string(), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decorators_and_annotations_are_captured() {
+        let ts = r#"
+@Component({ selector: "app-root" })
+export class AppComponent {
+  @Input() title: string;
+
+  @HostListener("click")
+  onClick() {}
+}
+"#;
+        assert_eq!(
+            annotations_of(&TypeScriptParser::new(), "app.ts", ts),
+            vec![
+                (
+                    "AppComponent".to_string(),
+                    vec![r#"Component({ selector: "app-root" })"#.to_string()]
+                ),
+                ("title".to_string(), vec!["Input()".to_string()]),
+                (
+                    "onClick".to_string(),
+                    vec![r#"HostListener("click")"#.to_string()]
+                ),
+            ]
+        );
+
+        let python = "@app.route('/health')\n@login_required\ndef health():\n    pass\n";
+        assert_eq!(
+            annotations_of(&PythonParser::new(), "app.py", python),
+            vec![(
+                "health".to_string(),
+                vec![
+                    "app.route('/health')".to_string(),
+                    "login_required".to_string()
+                ]
+            )]
+        );
+
+        let java = "@Service\nclass Users {\n  @Override\n  @Deprecated(since = \"2\")\n  public String toString() { return \"\"; }\n}\n";
+        assert_eq!(
+            annotations_of(&JavaParser::new(), "Users.java", java),
+            vec![
+                ("Users".to_string(), vec!["Service".to_string()]),
+                (
+                    "toString".to_string(),
+                    vec![
+                        "Override".to_string(),
+                        "Deprecated(since = \"2\")".to_string()
+                    ]
+                ),
+            ]
+        );
+        assert_eq!(
+            walker::annotation_name("Deprecated(since = \"2\")"),
+            "Deprecated"
+        );
+        assert_eq!(walker::annotation_name("app.route('/health')"), "app.route");
+    }
+
+    #[test]
+    fn test_tsx_files_use_the_tsx_grammar() {
+        let source = "function App() {\n  return <div className=\"app\">{title}</div>;\n}\n";
+        let parser = TypeScriptParser::new();
+
+        let tsx = parser.parse_file(Path::new("App.tsx"), source).unwrap();
+        assert_eq!(tsx.dialect.as_deref(), Some("tsx"));
+        assert!(tsx.errors.is_empty(), "{:?}", tsx.errors);
+        assert!(tsx.entities.iter().any(|e| e.name == "App"));
+
+        // JSX is not TypeScript, so the plain grammar reports errors
+        let ts = parser.parse_file(Path::new("App.ts"), source).unwrap();
+        assert_eq!(ts.dialect.as_deref(), Some("typescript"));
+        assert!(!ts.errors.is_empty());
+    }
+
+    #[test]
+    fn test_dialect_detection() {
+        let dialect = |file: &str, source: &str| languages::detect_dialect(Path::new(file), source);
+        assert_eq!(dialect("main.py", "print(\"hi\")\n"), Some("python3"));
+        assert_eq!(dialect("main.py", "print \"hi\"\n"), Some("python2"));
+        assert_eq!(
+            dialect(
+                "main.py",
+                "try:\n    pass\nexcept ValueError, e:\n    pass\n"
+            ),
+            Some("python2")
+        );
+        assert_eq!(dialect("point.h", "struct point { int x; };\n"), Some("c"));
+        assert_eq!(
+            dialect("point.h", "namespace geo {\nclass Point {};\n}\n"),
+            Some("cpp")
+        );
+        assert_eq!(dialect("main.rs", "fn main() {}\n"), None);
+    }
+}
@@ -0,0 +1,91 @@
+//! S-expression rendering of Tree-sitter syntax trees
+//!
+//! The output matches `Node::to_sexp`, listing named nodes with their field
+//! names, but puts each node on its own line indented by depth, and can stop
+//! descending at a given depth.
+
+use tree_sitter::{Node, TreeCursor};
+
+/// Depth past which nodes are indented no further, so that deeply nested
+/// input renders in time and space linear in its node count
+pub const MAX_INDENT_DEPTH: usize = 64;
+
+/// Render `node` and its named descendants as an indented S-expression
+///
+/// Nodes deeper than `max_depth` below `node` are replaced by `...`; `None`
+/// renders the whole tree. The tree is walked with a cursor rather than
+/// recursively, so any depth of nesting renders without exhausting the stack.
+pub fn render(node: Node, max_depth: Option<usize>) -> String {
+    let mut out = String::new();
+    let mut cursor = node.walk();
+    let mut depth = 0;
+    loop {
+        let node = cursor.node();
+        write_open(node, cursor.field_name(), depth, &mut out);
+
+        if node.named_child_count() > 0 {
+            if max_depth.is_some_and(|max| depth >= max) {
+                out.push_str(" ...");
+            } else if goto_first_named_child(&mut cursor) {
+                depth += 1;
+                continue;
+            }
+        }
+        out.push(')');
+
+        // Close every ancestor whose named children are all written
+        loop {
+            if depth == 0 {
+                return out;
+            }
+            if goto_next_named_sibling(&mut cursor) {
+                break;
+            }
+            cursor.goto_parent();
+            depth -= 1;
+            out.push(')');
+        }
+    }
+}
+
+/// Write the opening of `node`: its line, field name and kind
+fn write_open(node: Node, field: Option<&str>, depth: usize, out: &mut String) {
+    if depth > 0 {
+        out.push('\n');
+    }
+    for _ in 0..depth.min(MAX_INDENT_DEPTH) {
+        out.push_str("  ");
+    }
+    if let Some(field) = field {
+        out.push_str(field);
+        out.push_str(": ");
+    }
+    out.push('(');
+    if node.is_missing() {
+        out.push_str("MISSING ");
+    }
+    out.push_str(node.kind());
+}
+
+/// Move to the first named child, leaving the cursor in place if there is none
+fn goto_first_named_child(cursor: &mut TreeCursor) -> bool {
+    if !cursor.goto_first_child() {
+        return false;
+    }
+    if cursor.node().is_named() || goto_next_named_sibling(cursor) {
+        return true;
+    }
+    cursor.goto_parent();
+    false
+}
+
+/// Move to the next named sibling, possibly past the last unnamed one if
+/// there is none
+fn goto_next_named_sibling(cursor: &mut TreeCursor) -> bool {
+    while cursor.goto_next_sibling() {
+        if cursor.node().is_named() {
+            return true;
+        }
+    }
+    false
+}
@@ -0,0 +1,1296 @@
+//! Syntax tree walking shared by the language parsers
+//!
+//! Each language parser owns a Tree-sitter grammar. This module turns the
+//! resulting syntax tree into [`CodeEntity`] values by mapping the node kinds
+//! of each grammar onto entity types, names, parameters and signatures.
+
+use std::collections::HashMap;
+use tree_sitter::{Node, Tree};
+use uuid::Uuid;
+
+use crate::endpoints::{RouteMatch, RouteMatcher};
+use crate::overloads::{self, DECLARATION_KEY};
+use crate::{
+    CodeEntity, EntityType, ErrorSeverity, FileSummary, Language, Parameter, ParseError,
+    ParseOptions, TypeParameter,
+};
+
+/// Metadata key holding an entity's decorators or attributes as a JSON list
+pub const ANNOTATIONS_KEY: &str = "annotations";
+
+/// Metadata key holding how deeply a function's conditionals, loops,
+/// `switch`/`match` and `try` blocks nest, `0` for a body without any
+pub const NESTING_DEPTH_KEY: &str = "nesting_depth";
+
+/// Everything gathered from a single pass over a syntax tree
+pub struct WalkOutput {
+    pub entities: Vec<CodeEntity>,
+    pub errors: Vec<ParseError>,
+    pub summary: FileSummary,
+}
+
+/// Extract entities from a syntax tree in source order
+pub fn extract_entities(tree: &Tree, source: &str, language: &Language) -> Vec<CodeEntity> {
+    walk(tree, source, language, &ParseOptions::default()).entities
+}
+
+/// Walk a syntax tree once, collecting entities, syntax errors and file statistics
+pub fn walk(tree: &Tree, source: &str, language: &Language, options: &ParseOptions) -> WalkOutput {
+    walk_streaming(tree, source, language, "", options, &mut |_| {})
+}
+
+/// Like [`walk`], tagging entities with `file_path` and passing each to
+/// `emit` as it is found
+///
+/// Merging declarations or grouping overloads needs every entity, so with
+/// either option set the entities are emitted after the walk instead.
+pub fn walk_streaming(
+    tree: &Tree,
+    source: &str,
+    language: &Language,
+    file_path: &str,
+    options: &ParseOptions,
+    emit: &mut dyn FnMut(&CodeEntity),
+) -> WalkOutput {
+    let post_processed = options.merge_declarations || options.group_overloads;
+    let route_matchers = options
+        .route_matchers
+        .iter()
+        .filter(|matcher| matcher.supports(language))
+        .map(|matcher| matcher.as_ref())
+        .collect();
+
+    let mut walker = Walker {
+        source,
+        language,
+        file_path,
+        emit: if post_processed { None } else { Some(emit) },
+        index_anonymous: options.index_anonymous,
+        route_matchers,
+        entities: Vec::new(),
+        errors: Vec::new(),
+        comment_from: HashMap::new(),
+        enclosing: Vec::new(),
+    };
+    walker.walk(tree.root_node(), options.max_node_visits);
+
+    let summary = walker.summary();
+    let errors = walker.errors;
+    let mut entities = walker.entities;
+    if options.merge_declarations {
+        entities = overloads::merge_declarations(entities);
+    }
+    if options.group_overloads {
+        entities = overloads::group_overloads(entities);
+    }
+    if post_processed {
+        for entity in &entities {
+            emit(entity);
+        }
+    }
+    WalkOutput {
+        entities,
+        errors,
+        summary,
+    }
+}
+
+struct Walker<'a> {
+    source: &'a str,
+    language: &'a Language,
+    file_path: &'a str,
+    /// Receives each entity as it is pushed
+    emit: Option<&'a mut dyn FnMut(&CodeEntity)>,
+    /// Keep unnamed declarations and closures under a synthesized name
+    index_anonymous: bool,
+    route_matchers: Vec<&'a dyn RouteMatcher>,
+    entities: Vec<CodeEntity>,
+    errors: Vec<ParseError>,
+    /// Row -> first column covered by a comment on that row
+    comment_from: HashMap<usize, usize>,
+    /// End byte and id of each container entity around the current node,
+    /// innermost last
+    enclosing: Vec<(usize, Uuid)>,
+}
+
+impl Walker<'_> {
+    /// Visit every node in pre-order without recursing, so nesting depth is
+    /// bounded by the tree rather than the call stack
+    ///
+    /// After `max_node_visits` nodes the walk stops with a recorded error,
+    /// keeping the entities found so far.
+    fn walk(&mut self, root: Node, max_node_visits: Option<usize>) {
+        let mut cursor = root.walk();
+        let mut visited = 0usize;
+        loop {
+            if max_node_visits.is_some_and(|max| visited >= max) {
+                let position = cursor.node().start_position();
+                self.errors.push(ParseError {
+                    message: format!("Entity extraction aborted after visiting {} nodes", visited),
+                    line: position.row as u32 + 1,
+                    column: position.column as u32,
+                    severity: ErrorSeverity::Error,
+                });
+                return;
+            }
+            visited += 1;
+            self.visit(cursor.node());
+
+            if cursor.goto_first_child() {
+                continue;
+            }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn visit(&mut self, node: Node) {
+        if node.is_error() || node.is_missing() {
+            self.record_error(node);
+        }
+
+        if node.kind().contains("comment") {
+            self.record_comment(node);
+        }
+
+        // Nodes are visited in pre-order, so containers ending before this
+        // node starts enclose none of what follows
+        while self
+            .enclosing
+            .last()
+            .is_some_and(|&(end_byte, _)| end_byte <= node.start_byte())
+        {
+            self.enclosing.pop();
+        }
+        let parent_id = self.enclosing.last().map(|&(_, id)| id);
+
+        let entity_type = classify(node, self.source, self.language).or_else(|| {
+            self.index_anonymous
+                .then(|| classify_anonymous(node, self.language))
+                .flatten()
+        });
+        if let Some(entity_type) = entity_type {
+            if let Some(mut entity) = build_entity(
+                node,
+                entity_type,
+                self.source,
+                self.language,
+                self.index_anonymous,
+            ) {
+                entity.parent_id = parent_id;
+                if is_container(&entity.entity_type) {
+                    self.enclosing.push((node.end_byte(), entity.id));
+                }
+                self.push(entity);
+            }
+        }
+
+        // Indexed so the matcher borrow does not outlive `push`
+        for index in 0..self.route_matchers.len() {
+            let matcher = self.route_matchers[index];
+            if let Some(route) = matcher.match_node(node, self.source) {
+                let mut entity = endpoint_entity(node, route, matcher.framework(), self.source);
+                entity.parent_id = parent_id;
+                self.push(entity);
+            }
+        }
+    }
+
+    fn push(&mut self, mut entity: CodeEntity) {
+        entity.file_path = self.file_path.to_string();
+        if let Some(emit) = self.emit.as_mut() {
+            emit(&entity);
+        }
+        self.entities.push(entity);
+    }
+
+    fn record_error(&mut self, node: Node) {
+        let position = node.start_position();
+        self.errors.push(ParseError {
+            message: if node.is_missing() {
+                format!("Missing {}", node.kind())
+            } else {
+                "Syntax error".to_string()
+            },
+            line: position.row as u32 + 1,
+            column: position.column as u32,
+            severity: ErrorSeverity::Error,
+        });
+    }
+
+    fn record_comment(&mut self, node: Node) {
+        let start = node.start_position();
+        let end = node.end_position();
+        for row in start.row..=end.row {
+            let column = if row == start.row { start.column } else { 0 };
+            let covered = self.comment_from.entry(row).or_insert(column);
+            *covered = (*covered).min(column);
+        }
+    }
+
+    fn summary(&self) -> FileSummary {
+        let mut summary = FileSummary::default();
+
+        for (row, line) in self.source.lines().enumerate() {
+            summary.total_lines += 1;
+            let indent = line.len() - line.trim_start().len();
+            if line.trim().is_empty() {
+                summary.blank_lines += 1;
+            } else if self
+                .comment_from
+                .get(&row)
+                .is_some_and(|&column| column <= indent)
+            {
+                summary.comment_lines += 1;
+            } else {
+                summary.code_lines += 1;
+            }
+        }
+
+        for entity in &self.entities {
+            *summary
+                .entity_counts
+                .entry(entity.entity_type.clone())
+                .or_insert(0) += 1;
+        }
+
+        summary
+    }
+}
+
+/// Entity types whose body may declare further entities
+fn is_container(entity_type: &EntityType) -> bool {
+    matches!(
+        entity_type,
+        EntityType::Class
+            | EntityType::Struct
+            | EntityType::Enum
+            | EntityType::Interface
+            | EntityType::Trait
+            | EntityType::Module
+            | EntityType::Function
+    )
+}
+
+fn endpoint_entity(node: Node, route: RouteMatch, framework: &str, source: &str) -> CodeEntity {
+    let start = node.start_position();
+    let end = node.end_position();
+    let name = format!("{} {}", route.method, route.path);
+
+    let mut metadata = HashMap::new();
+    metadata.insert("http_method".to_string(), route.method);
+    metadata.insert("route_path".to_string(), route.path);
+    metadata.insert("framework".to_string(), framework.to_string());
+    if let Some(handler) = route.handler {
+        metadata.insert("handler".to_string(), handler);
+    }
+
+    CodeEntity {
+        id: Uuid::new_v4(),
+        parent_id: None,
+        signature: Some(name.clone()),
+        name,
+        entity_type: EntityType::Endpoint,
+        file_path: String::new(),
+        start_line: start.row as u32 + 1,
+        end_line: end.row as u32 + 1,
+        start_column: start.column as u32,
+        end_column: end.column as u32,
+        start_byte: node.start_byte() as u32,
+        end_byte: node.end_byte() as u32,
+        content: node_text(node, source).to_string(),
+        documentation: None,
+        visibility: Some("public".to_string()),
+        parameters: Vec::new(),
+        type_parameters: Vec::new(),
+        return_type: None,
+        dependencies: Vec::new(),
+        metadata,
+    }
+}
+
+/// Map a syntax node onto the entity type it declares, if any
+fn classify(node: Node, source: &str, language: &Language) -> Option<EntityType> {
+    let kind = node.kind();
+    match language {
+        Language::TypeScript | Language::JavaScript => match kind {
+            "function_declaration"
+            | "generator_function_declaration"
+            | "function_signature"
+            | "method_definition" => Some(EntityType::Function),
+            "class_declaration" | "abstract_class_declaration" => Some(EntityType::Class),
+            "interface_declaration" => Some(EntityType::Interface),
+            "type_alias_declaration" => Some(EntityType::TypeAlias),
+            "enum_declaration" => Some(EntityType::Enum),
+            "public_field_definition" | "field_definition" => Some(EntityType::Variable),
+            "variable_declarator" => {
+                if node
+                    .child_by_field_name("value")
+                    .is_some_and(|value| is_function_value(value.kind()))
+                {
+                    Some(EntityType::Function)
+                } else if node
+                    .parent()
+                    .and_then(|parent| parent.child_by_field_name("kind"))
+                    .is_some_and(|declaration_kind| declaration_kind.kind() == "const")
+                {
+                    Some(EntityType::Constant)
+                } else {
+                    Some(EntityType::Variable)
+                }
+            }
+            "import_statement" => Some(EntityType::Import),
+            _ => None,
+        },
+        Language::Python => match kind {
+            "function_definition" => Some(EntityType::Function),
+            "class_definition" => Some(EntityType::Class),
+            "assignment" => {
+                let parent_is_statement = node
+                    .parent()
+                    .is_some_and(|parent| parent.kind() == "expression_statement");
+                let target = node.child_by_field_name("left")?;
+                if !parent_is_statement || target.kind() != "identifier" {
+                    return None;
+                }
+                let name = node_text(target, source);
+                if name
+                    .chars()
+                    .all(|c| c.is_uppercase() || c == '_' || c.is_numeric())
+                {
+                    Some(EntityType::Constant)
+                } else {
+                    Some(EntityType::Variable)
+                }
+            }
+            "import_statement" | "import_from_statement" => Some(EntityType::Import),
+            _ => None,
+        },
+        Language::Rust => match kind {
+            "function_item" | "function_signature_item" => Some(EntityType::Function),
+            "struct_item" | "union_item" => Some(EntityType::Struct),
+            "enum_item" => Some(EntityType::Enum),
+            "trait_item" => Some(EntityType::Trait),
+            "type_item" => Some(EntityType::TypeAlias),
+            "const_item" | "static_item" => Some(EntityType::Constant),
+            "let_declaration" => Some(EntityType::Variable),
+            "mod_item" => Some(EntityType::Module),
+            "use_declaration" => Some(EntityType::Import),
+            _ => None,
+        },
+        Language::Go => match kind {
+            "function_declaration" | "method_declaration" => Some(EntityType::Function),
+            "type_spec" => match node.child_by_field_name("type").map(|t| t.kind()) {
+                Some("interface_type") => Some(EntityType::Interface),
+                Some("struct_type") => Some(EntityType::Struct),
+                _ => Some(EntityType::TypeAlias),
+            },
+            "type_alias" => Some(EntityType::TypeAlias),
+            "const_spec" => Some(EntityType::Constant),
+            "var_spec" => Some(EntityType::Variable),
+            "import_spec" => Some(EntityType::Import),
+            _ => None,
+        },
+        Language::Java => match kind {
+            "method_declaration" | "constructor_declaration" => Some(EntityType::Function),
+            "class_declaration" | "record_declaration" => Some(EntityType::Class),
+            "enum_declaration" => Some(EntityType::Enum),
+            "interface_declaration" | "annotation_type_declaration" => Some(EntityType::Interface),
+            "field_declaration" => {
+                let modifiers = node
+                    .children(&mut node.walk())
+                    .find(|child| child.kind() == "modifiers")
+                    .map(|modifiers| node_text(modifiers, source))
+                    .unwrap_or_default();
+                if modifiers.contains("static") && modifiers.contains("final") {
+                    Some(EntityType::Constant)
+                } else {
+                    Some(EntityType::Variable)
+                }
+            }
+            "import_declaration" => Some(EntityType::Import),
+            _ => None,
+        },
+        Language::Cpp => match kind {
+            "function_definition" => Some(EntityType::Function),
+            "declaration" | "field_declaration" => {
+                function_declarator(node).map(|_| EntityType::Function)
+            }
+            "class_specifier" | "struct_specifier" | "union_specifier" | "enum_specifier" => {
+                // Only definitions carry a body; `struct Foo;` is a forward reference
+                node.child_by_field_name("body").map(|_| match kind {
+                    "struct_specifier" | "union_specifier" => EntityType::Struct,
+                    "enum_specifier" => EntityType::Enum,
+                    _ => EntityType::Class,
+                })
+            }
+            "namespace_definition" => Some(EntityType::Module),
+            "preproc_include" => Some(EntityType::Import),
+            _ => None,
+        },
+        Language::CSharp => match kind {
+            "method_declaration" | "constructor_declaration" => Some(EntityType::Function),
+            "class_declaration" | "record_declaration" => Some(EntityType::Class),
+            "struct_declaration" => Some(EntityType::Struct),
+            "enum_declaration" => Some(EntityType::Enum),
+            "interface_declaration" => Some(EntityType::Interface),
+            "field_declaration" => Some(EntityType::Variable),
+            "namespace_declaration" | "file_scoped_namespace_declaration" => {
+                Some(EntityType::Module)
+            }
+            "using_directive" => Some(EntityType::Import),
+            _ => None,
+        },
+    }
+}
+
+/// Map an anonymous function or class expression onto its entity type
+///
+/// A JS/TS arrow function or function expression bound by a declarator is
+/// already reported under the declarator's name, so it is skipped here.
+fn classify_anonymous(node: Node, language: &Language) -> Option<EntityType> {
+    let kind = node.kind();
+    match language {
+        Language::TypeScript | Language::JavaScript => match kind {
+            _ if is_function_value(kind) => {
+                let bound = node
+                    .parent()
+                    .is_some_and(|parent| parent.kind() == "variable_declarator");
+                (!bound).then_some(EntityType::Function)
+            }
+            "class" => Some(EntityType::Class),
+            _ => None,
+        },
+        Language::Python => (kind == "lambda").then_some(EntityType::Function),
+        Language::Rust => (kind == "closure_expression").then_some(EntityType::Function),
+        Language::Go => (kind == "func_literal").then_some(EntityType::Function),
+        Language::Java | Language::Cpp => {
+            (kind == "lambda_expression").then_some(EntityType::Function)
+        }
+        Language::CSharp => matches!(kind, "lambda_expression" | "anonymous_method_expression")
+            .then_some(EntityType::Function),
+    }
+}
+
+fn is_function_value(kind: &str) -> bool {
+    matches!(
+        kind,
+        "arrow_function" | "function" | "function_expression" | "generator_function"
+    )
+}
+
+fn build_entity(
+    node: Node,
+    entity_type: EntityType,
+    source: &str,
+    language: &Language,
+    index_anonymous: bool,
+) -> Option<CodeEntity> {
+    let start = node.start_position();
+    let end = node.end_position();
+    let name = match entity_name(node, source, language) {
+        Some(name) if !name.is_empty() => name,
+        _ if index_anonymous => format!("<anonymous@{}>", start.row + 1),
+        _ => return None,
+    };
+    let is_function = entity_type == EntityType::Function;
+    let callable = callable_node(node, language);
+
+    let parameters = if is_function {
+        callable
+            .child_by_field_name("parameters")
+            .map(|params| extract_parameters(params, source, language))
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    let return_type = if is_function {
+        return_type(node, callable, source, language)
+    } else {
+        None
+    };
+
+    let signature = match entity_type {
+        EntityType::Function
+        | EntityType::Class
+        | EntityType::Struct
+        | EntityType::Enum
+        | EntityType::Interface
+        | EntityType::Trait
+        | EntityType::TypeAlias => Some(signature(node, callable, source, language)),
+        _ => None,
+    };
+
+    // C/C++ prototypes are declarations; definitions are `function_definition`
+    let mut metadata = HashMap::new();
+    if is_function && matches!(node.kind(), "declaration" | "field_declaration") {
+        metadata.insert(DECLARATION_KEY.to_string(), "true".to_string());
+    }
+    if is_function {
+        metadata.insert(
+            NESTING_DEPTH_KEY.to_string(),
+            nesting_depth(node).to_string(),
+        );
+    }
+    let annotations = annotations(node, source, language);
+    if !annotations.is_empty() {
+        let annotations = serde_json::to_string(&annotations).unwrap_or_default();
+        metadata.insert(ANNOTATIONS_KEY.to_string(), annotations);
+    }
+
+    Some(CodeEntity {
+        id: Uuid::new_v4(),
+        parent_id: None,
+        name,
+        entity_type,
+        file_path: String::new(),
+        start_line: start.row as u32 + 1,
+        end_line: end.row as u32 + 1,
+        start_column: start.column as u32,
+        end_column: end.column as u32,
+        start_byte: node.start_byte() as u32,
+        end_byte: node.end_byte() as u32,
+        content: node_text(node, source).to_string(),
+        signature,
+        documentation: documentation(node, source, language),
+        visibility: visibility(node, source, language),
+        parameters,
+        type_parameters: type_parameters(node, callable, source, language),
+        return_type,
+        dependencies: Vec::new(),
+        metadata,
+    })
+}
+
+/// Deepest nesting of control-flow blocks under `node`
+///
+/// Walked with an explicit stack, as the tree may be deeper than the call stack allows.
+fn nesting_depth(node: Node) -> u32 {
+    let mut deepest = 0;
+    let mut stack = vec![(node, 0)];
+    while let Some((node, depth)) = stack.pop() {
+        deepest = deepest.max(depth);
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            stack.push((child, depth + u32::from(opens_nesting_level(child))));
+        }
+    }
+    deepest
+}
+
+/// Whether a node is a conditional, loop, `switch`/`match` or `try` block,
+/// in any of the supported grammars
+///
+/// An `else if` continues its chain at the same level rather than nesting.
+fn opens_nesting_level(node: Node) -> bool {
+    let kind = node.kind();
+    let nests = matches!(
+        kind,
+        "if_statement"
+            | "if_expression"
+            | "for_statement"
+            | "for_in_statement"
+            | "for_expression"
+            | "for_range_loop"
+            | "enhanced_for_statement"
+            | "foreach_statement"
+            | "while_statement"
+            | "while_expression"
+            | "loop_expression"
+            | "do_statement"
+            | "switch_statement"
+            | "switch_expression"
+            | "expression_switch_statement"
+            | "type_switch_statement"
+            | "select_statement"
+            | "match_statement"
+            | "match_expression"
+            | "try_statement"
+            | "with_statement"
+    );
+    let continues_else_chain = || {
+        node.parent()
+            .is_some_and(|parent| parent.kind() == "else_clause" || parent.kind() == kind)
+    };
+    nests && !(kind.starts_with("if_") && continues_else_chain())
+}
+
+/// The node holding parameters, return type and body for a declaration
+///
+/// For most grammars this is the declaration itself; JS/TS variables bound to
+/// arrow functions and C++ declarators nest it one level deeper.
+fn callable_node<'a>(node: Node<'a>, language: &Language) -> Node<'a> {
+    match language {
+        Language::TypeScript | Language::JavaScript if node.kind() == "variable_declarator" => {
+            node.child_by_field_name("value").unwrap_or(node)
+        }
+        Language::Cpp => function_declarator(node).unwrap_or(node),
+        _ => node,
+    }
+}
+
+fn entity_name(node: Node, source: &str, language: &Language) -> Option<String> {
+    let name = match (language, node.kind()) {
+        (Language::TypeScript | Language::JavaScript, "import_statement") => {
+            strip_quotes(node_text(node.child_by_field_name("source")?, source))
+        }
+        (Language::TypeScript | Language::JavaScript, "public_field_definition")
+        | (Language::TypeScript | Language::JavaScript, "field_definition") => {
+            let name = node
+                .child_by_field_name("name")
+                .or_else(|| node.child_by_field_name("property"))?;
+            node_text(name, source).to_string()
+        }
+        (Language::Python, "assignment") => {
+            node_text(node.child_by_field_name("left")?, source).to_string()
+        }
+        (Language::Python, "import_statement") => {
+            let name = node.child_by_field_name("name")?;
+            let name = name.child_by_field_name("name").unwrap_or(name);
+            node_text(name, source).to_string()
+        }
+        (Language::Python, "import_from_statement") => {
+            node_text(node.child_by_field_name("module_name")?, source).to_string()
+        }
+        (Language::Rust, "let_declaration") => {
+            node_text(node.child_by_field_name("pattern")?, source).to_string()
+        }
+        (Language::Rust, "use_declaration") => {
+            node_text(node.child_by_field_name("argument")?, source).to_string()
+        }
+        (Language::Go, "import_spec") => {
+            strip_quotes(node_text(node.child_by_field_name("path")?, source))
+        }
+        (Language::Java, "field_declaration") => {
+            let declarator = node.child_by_field_name("declarator")?;
+            node_text(declarator.child_by_field_name("name")?, source).to_string()
+        }
+        (Language::Java, "import_declaration") | (Language::CSharp, "using_directive") => {
+            let path = node.named_child(node.named_child_count().checked_sub(1)?)?;
+            node_text(path, source).to_string()
+        }
+        (Language::Cpp, "preproc_include") => {
+            let path = node_text(node.child_by_field_name("path")?, source);
+            path.trim_matches(['"', '<', '>']).to_string()
+        }
+        (Language::Cpp, "function_definition")
+        | (Language::Cpp, "declaration")
+        | (Language::Cpp, "field_declaration") => {
+            let declarator = function_declarator(node)?.child_by_field_name("declarator")?;
+            declarator_name(declarator, source)?
+        }
+        (Language::CSharp, "field_declaration") => {
+            let declaration = node
+                .children(&mut node.walk())
+                .find(|child| child.kind() == "variable_declaration")?;
+            let declarator = declaration
+                .children(&mut declaration.walk())
+                .find(|child| child.kind() == "variable_declarator")?;
+            let name = declarator
+                .child_by_field_name("name")
+                .or_else(|| declarator.named_child(0))?;
+            node_text(name, source).to_string()
+        }
+        _ => node_text(node.child_by_field_name("name")?, source).to_string(),
+    };
+
+    Some(name)
+}
+
+/// Find the function declarator nested in a C/C++ declaration, if any
+fn function_declarator(node: Node) -> Option<Node> {
+    let mut current = node.child_by_field_name("declarator")?;
+    loop {
+        if current.kind() == "function_declarator" {
+            return Some(current);
+        }
+        current = current
+            .child_by_field_name("declarator")
+            .or_else(|| match current.kind() {
+                "reference_declarator" | "parenthesized_declarator" => current.named_child(0),
+                _ => None,
+            })?;
+    }
+}
+
+/// Resolve the identifier at the core of a C/C++ declarator
+fn declarator_name(node: Node, source: &str) -> Option<String> {
+    match node.kind() {
+        "identifier" | "field_identifier" | "type_identifier" | "destructor_name"
+        | "operator_name" => Some(node_text(node, source).to_string()),
+        "qualified_identifier" => declarator_name(node.child_by_field_name("name")?, source),
+        _ => {
+            let inner = node
+                .child_by_field_name("declarator")
+                .or_else(|| node.named_child(0))?;
+            declarator_name(inner, source)
+        }
+    }
+}
+
+/// Extract structured parameters from a parameter list node
+fn extract_parameters(params: Node, source: &str, language: &Language) -> Vec<Parameter> {
+    let mut parameters = Vec::new();
+    let mut cursor = params.walk();
+
+    for param in params.named_children(&mut cursor) {
+        match language {
+            Language::TypeScript | Language::JavaScript => {
+                if let Some(parameter) = js_parameter(param, source) {
+                    parameters.push(parameter);
+                }
+            }
+            Language::Python => {
+                if let Some(parameter) = python_parameter(param, source) {
+                    parameters.push(parameter);
+                }
+            }
+            Language::Rust => match param.kind() {
+                "self_parameter" => parameters.push(parameter(
+                    "self",
+                    Some(node_text(param, source)),
+                    None,
+                    false,
+                )),
+                "parameter" => parameters.push(parameter(
+                    field_text(param, "pattern", source).unwrap_or_default(),
+                    field_text(param, "type", source),
+                    None,
+                    false,
+                )),
+                "variadic_parameter" => parameters.push(parameter("...", None, None, true)),
+                _ => {}
+            },
+            Language::Go => {
+                let variadic = param.kind() == "variadic_parameter_declaration";
+                if !variadic && param.kind() != "parameter_declaration" {
+                    continue;
+                }
+                let param_type = field_text(param, "type", source).map(|t| {
+                    if variadic {
+                        format!("...{}", t)
+                    } else {
+                        t.to_string()
+                    }
+                });
+                let names: Vec<Node> = param
+                    .children_by_field_name("name", &mut param.walk())
+                    .collect();
+                if names.is_empty() {
+                    parameters.push(parameter("_", param_type.as_deref(), None, variadic));
+                }
+                for name in names {
+                    parameters.push(parameter(
+                        node_text(name, source),
+                        param_type.as_deref(),
+                        None,
+                        variadic,
+                    ));
+                }
+            }
+            Language::Java => match param.kind() {
+                "formal_parameter" => parameters.push(parameter(
+                    field_text(param, "name", source).unwrap_or_default(),
+                    field_text(param, "type", source),
+                    None,
+                    false,
+                )),
+                "spread_parameter" => {
+                    let param_type = param
+                        .named_child(0)
+                        .filter(|child| child.kind() != "modifiers")
+                        .map(|t| format!("{}...", node_text(t, source)));
+                    let name = param
+                        .children(&mut param.walk())
+                        .find(|child| child.kind() == "variable_declarator")
+                        .and_then(|declarator| field_text(declarator, "name", source))
+                        .unwrap_or_default();
+                    parameters.push(parameter(name, param_type.as_deref(), None, true));
+                }
+                _ => {}
+            },
+            Language::Cpp => match param.kind() {
+                "parameter_declaration" | "optional_parameter_declaration" => {
+                    let name = param
+                        .child_by_field_name("declarator")
+                        .and_then(|declarator| declarator_name(declarator, source))
+                        .unwrap_or_else(|| "_".to_string());
+                    let default_value = field_text(param, "default_value", source);
+                    parameters.push(parameter(
+                        &name,
+                        field_text(param, "type", source),
+                        default_value,
+                        default_value.is_some(),
+                    ));
+                }
+                "variadic_parameter_declaration" | "variadic_parameter" => {
+                    parameters.push(parameter("...", None, None, true))
+                }
+                _ => {}
+            },
+            Language::CSharp => {
+                if param.kind() != "parameter" {
+                    continue;
+                }
+                let default_value = param
+                    .children(&mut param.walk())
+                    .find(|child| child.kind() == "equals_value_clause")
+                    .and_then(|clause| clause.named_child(0))
+                    .map(|value| node_text(value, source));
+                parameters.push(parameter(
+                    field_text(param, "name", source).unwrap_or_default(),
+                    field_text(param, "type", source),
+                    default_value,
+                    default_value.is_some(),
+                ));
+            }
+        }
+    }
+
+    parameters
+}
+
+fn js_parameter(param: Node, source: &str) -> Option<Parameter> {
+    match param.kind() {
+        // TypeScript wraps every parameter in required/optional_parameter
+        "required_parameter" | "optional_parameter" => {
+            let pattern = param.child_by_field_name("pattern")?;
+            let rest = pattern.kind() == "rest_pattern";
+            let default_value = field_text(param, "value", source);
+            let param_type = param
+                .child_by_field_name("type")
+                .map(|t| strip_type_annotation(node_text(t, source)));
+            Some(parameter(
+                node_text(pattern, source),
+                param_type,
+                default_value,
+                param.kind() == "optional_parameter" || rest || default_value.is_some(),
+            ))
+        }
+        "identifier" | "object_pattern" | "array_pattern" => {
+            Some(parameter(node_text(param, source), None, None, false))
+        }
+        "assignment_pattern" => Some(parameter(
+            field_text(param, "left", source)?,
+            None,
+            field_text(param, "right", source),
+            true,
+        )),
+        "rest_pattern" => Some(parameter(node_text(param, source), None, None, true)),
+        _ => None,
+    }
+}
+
+fn python_parameter(param: Node, source: &str) -> Option<Parameter> {
+    match param.kind() {
+        "identifier" => Some(parameter(node_text(param, source), None, None, false)),
+        "typed_parameter" => {
+            let name = param.named_child(0)?;
+            let splat = matches!(
+                name.kind(),
+                "list_splat_pattern" | "dictionary_splat_pattern"
+            );
+            Some(parameter(
+                node_text(name, source),
+                field_text(param, "type", source),
+                None,
+                splat,
+            ))
+        }
+        "default_parameter" | "typed_default_parameter" => Some(parameter(
+            field_text(param, "name", source)?,
+            field_text(param, "type", source),
+            field_text(param, "value", source),
+            true,
+        )),
+        "list_splat_pattern" | "dictionary_splat_pattern" => {
+            Some(parameter(node_text(param, source), None, None, true))
+        }
+        _ => None,
+    }
+}
+
+fn parameter(
+    name: &str,
+    param_type: Option<&str>,
+    default_value: Option<&str>,
+    is_optional: bool,
+) -> Parameter {
+    Parameter {
+        name: name.to_string(),
+        param_type: param_type.map(|t| t.to_string()),
+        default_value: default_value.map(|v| v.to_string()),
+        is_optional,
+    }
+}
+
+/// Generic parameters declared by Rust, TypeScript and Java entities
+fn type_parameters(
+    node: Node,
+    callable: Node,
+    source: &str,
+    language: &Language,
+) -> Vec<TypeParameter> {
+    if !matches!(
+        language,
+        Language::Rust | Language::TypeScript | Language::Java
+    ) {
+        return Vec::new();
+    }
+    let Some(list) = callable.child_by_field_name("type_parameters") else {
+        return Vec::new();
+    };
+
+    let new = |name: Node| TypeParameter {
+        name: node_text(name, source).to_string(),
+        bounds: Vec::new(),
+        default_type: None,
+    };
+    let bounds = |bounds: Node| -> Vec<String> {
+        bounds
+            .named_children(&mut bounds.walk())
+            .map(|bound| node_text(bound, source).to_string())
+            .collect()
+    };
+
+    let mut parameters: Vec<TypeParameter> = Vec::new();
+    for child in list.named_children(&mut list.walk()) {
+        let parameter = match child.kind() {
+            // Rust
+            "type_identifier" | "lifetime" => new(child),
+            "constrained_type_parameter" => {
+                let Some(name) = child.child_by_field_name("left") else {
+                    continue;
+                };
+                TypeParameter {
+                    bounds: child
+                        .child_by_field_name("bounds")
+                        .map_or_else(Vec::new, bounds),
+                    ..new(name)
+                }
+            }
+            "optional_type_parameter" | "const_parameter" => {
+                let Some(name) = child.child_by_field_name("name") else {
+                    continue;
+                };
+                TypeParameter {
+                    default_type: field_text(child, "default_type", source).map(str::to_string),
+                    ..new(name)
+                }
+            }
+            // TypeScript and Java
+            "type_parameter" => {
+                let name = child.child_by_field_name("name").or_else(|| {
+                    child
+                        .named_child(0)
+                        .filter(|n| n.kind() == "type_identifier")
+                });
+                let Some(name) = name else {
+                    continue;
+                };
+                let bound = child.child_by_field_name("constraint").or_else(|| {
+                    child
+                        .named_children(&mut child.walk())
+                        .find(|n| n.kind() == "type_bound")
+                });
+                let default_type = child
+                    .child_by_field_name("value")
+                    .and_then(|value| value.named_child(0))
+                    .map(|value| node_text(value, source).to_string());
+                TypeParameter {
+                    bounds: bound.map_or_else(Vec::new, bounds),
+                    default_type,
+                    ..new(name)
+                }
+            }
+            _ => continue,
+        };
+        parameters.push(parameter);
+    }
+
+    // `where T: Debug` adds to the bounds of `T`
+    if let Some(where_clause) = node
+        .children(&mut node.walk())
+        .find(|child| child.kind() == "where_clause")
+    {
+        for predicate in where_clause.named_children(&mut where_clause.walk()) {
+            let (Some(left), Some(predicate_bounds)) = (
+                predicate.child_by_field_name("left"),
+                predicate.child_by_field_name("bounds"),
+            ) else {
+                continue;
+            };
+            let name = node_text(left, source);
+            if let Some(parameter) = parameters.iter_mut().find(|p| p.name == name) {
+                parameter.bounds.extend(bounds(predicate_bounds));
+            }
+        }
+    }
+    parameters
+}
+
+fn return_type(node: Node, callable: Node, source: &str, language: &Language) -> Option<String> {
+    let return_type = match language {
+        Language::TypeScript | Language::JavaScript => callable
+            .child_by_field_name("return_type")
+            .map(|t| strip_type_annotation(node_text(t, source)).to_string()),
+        Language::Python | Language::Rust => {
+            field_text(node, "return_type", source).map(|t| t.to_string())
+        }
+        Language::Go => field_text(node, "result", source).map(|t| t.to_string()),
+        Language::Java | Language::CSharp | Language::Cpp => {
+            field_text(node, "type", source).map(|t| t.to_string())
+        }
+    };
+
+    return_type.filter(|t| !t.is_empty())
+}
+
+/// Declaration header: everything before the body, whitespace-collapsed
+fn signature(node: Node, callable: Node, source: &str, language: &Language) -> String {
+    let body = match (language, node.kind()) {
+        (Language::Cpp, "function_definition") => node.child_by_field_name("body"),
+        (Language::Cpp, _) => None,
+        _ => callable.child_by_field_name("body"),
+    };
+    let end = body.map_or(node.end_byte(), |body| body.start_byte());
+    let header = &source[node.start_byte()..end.max(node.start_byte())];
+
+    let header = header.split_whitespace().collect::<Vec<_>>().join(" ");
+    header
+        .trim_end_matches(|c: char| c == '{' || c == ':' || c == ';' || c.is_whitespace())
+        .trim_end_matches("=>")
+        .trim_end()
+        .to_string()
+}
+
+/// Leading doc comments, or a Python docstring
+fn documentation(node: Node, source: &str, language: &Language) -> Option<String> {
+    if *language == Language::Python {
+        if let Some(docstring) = python_docstring(node, source) {
+            return Some(docstring);
+        }
+    }
+
+    // Comments attach to wrappers such as `export` statements or decorators
+    let mut anchor = node;
+    while let Some(parent) = anchor.parent() {
+        if matches!(
+            parent.kind(),
+            "export_statement" | "decorated_definition" | "lexical_declaration"
+        ) {
+            anchor = parent;
+        } else {
+            break;
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut expected_row = anchor.start_position().row;
+    let mut current = anchor.prev_sibling();
+    while let Some(comment) = current {
+        if !comment.kind().contains("comment") || comment.end_position().row + 1 < expected_row {
+            break;
+        }
+        lines.push(clean_comment(node_text(comment, source)));
+        expected_row = comment.start_position().row;
+        current = comment.prev_sibling();
+    }
+
+    if lines.is_empty() {
+        return None;
+    }
+    lines.reverse();
+    let documentation = lines.join("\n").trim().to_string();
+    (!documentation.is_empty()).then_some(documentation)
+}
+
+fn python_docstring(node: Node, source: &str) -> Option<String> {
+    let body = node.child_by_field_name("body")?;
+    let first = body.named_child(0)?;
+    if first.kind() != "expression_statement" {
+        return None;
+    }
+    let string = first.named_child(0).filter(|s| s.kind() == "string")?;
+    let text = node_text(string, source)
+        .trim_start_matches(['r', 'u', 'b'])
+        .trim_matches(['"', '\''])
+        .trim();
+    Some(text.to_string())
+}
+
+fn clean_comment(comment: &str) -> String {
+    comment
+        .lines()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches("/**")
+                .trim_start_matches("/*")
+                .trim_end_matches("*/")
+                .trim_start_matches("///")
+                .trim_start_matches("//!")
+                .trim_start_matches("//")
+                .trim_start_matches('*')
+                .trim_start_matches('#')
+                .trim()
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Decorators, annotations and attributes attached to a declaration, in
+/// source order and without their `@` or `#[...]` sigils
+///
+/// `@app.route("/")` yields `app.route("/")` and `#[derive(Debug)]` yields
+/// `derive(Debug)`; [`annotation_name`] reduces either to its name.
+fn annotations(node: Node, source: &str, language: &Language) -> Vec<String> {
+    let mut found: Vec<Node> = Vec::new();
+    match language {
+        Language::Python => {
+            if let Some(parent) = node
+                .parent()
+                .filter(|parent| parent.kind() == "decorated_definition")
+            {
+                found.extend(
+                    parent
+                        .named_children(&mut parent.walk())
+                        .filter(|child| child.kind() == "decorator"),
+                );
+            }
+        }
+        Language::TypeScript | Language::JavaScript => {
+            // Methods and exported classes are preceded by their decorators;
+            // other classes and fields hold them as children
+            found.extend(preceding_siblings(node, &["decorator"]));
+            found.extend(
+                node.named_children(&mut node.walk())
+                    .filter(|child| child.kind() == "decorator"),
+            );
+        }
+        Language::Java => {
+            if let Some(modifiers) = node
+                .named_children(&mut node.walk())
+                .find(|child| child.kind() == "modifiers")
+            {
+                found.extend(
+                    modifiers
+                        .named_children(&mut modifiers.walk())
+                        .filter(|child| matches!(child.kind(), "marker_annotation" | "annotation")),
+                );
+            }
+        }
+        Language::Rust => {
+            found.extend(
+                preceding_siblings(node, &["attribute_item"])
+                    .into_iter()
+                    .filter_map(|item| item.named_child(0)),
+            );
+        }
+        _ => {}
+    }
+
+    found
+        .into_iter()
+        .map(|annotation| {
+            node_text(annotation, source)
+                .trim_start_matches('@')
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+/// Siblings of `kinds` directly before `node`, nearest last, stepping over comments
+fn preceding_siblings<'a>(node: Node<'a>, kinds: &[&str]) -> Vec<Node<'a>> {
+    let mut siblings = Vec::new();
+    let mut current = node.prev_named_sibling();
+    while let Some(sibling) = current {
+        if kinds.contains(&sibling.kind()) {
+            siblings.push(sibling);
+        } else if !sibling.kind().contains("comment") {
+            break;
+        }
+        current = sibling.prev_named_sibling();
+    }
+    siblings.reverse();
+    siblings
+}
+
+/// The name of a captured annotation: `derive(Debug)` -> `derive`,
+/// `app.route("/")` -> `app.route`
+pub fn annotation_name(annotation: &str) -> &str {
+    annotation
+        .split_once('(')
+        .map_or(annotation, |(name, _)| name)
+        .trim()
+}
+
+fn visibility(node: Node, source: &str, language: &Language) -> Option<String> {
+    match language {
+        Language::Rust => {
+            let modifier = node
+                .children(&mut node.walk())
+                .find(|child| child.kind() == "visibility_modifier");
+            Some(match modifier.map(|m| node_text(m, source)) {
+                Some("pub") => "public".to_string(),
+                Some(_) => "internal".to_string(),
+                None => "private".to_string(),
+            })
+        }
+        Language::Go => {
+            let name = entity_name(node, source, language)?;
+            let exported = name.chars().next().is_some_and(|c| c.is_uppercase());
+            Some(if exported { "public" } else { "private" }.to_string())
+        }
+        Language::Python => {
+            let name = entity_name(node, source, language)?;
+            let private = name.starts_with('_') && !name.ends_with("__");
+            Some(if private { "private" } else { "public" }.to_string())
+        }
+        Language::TypeScript | Language::JavaScript => {
+            let modifier = node
+                .children(&mut node.walk())
+                .find(|child| child.kind() == "accessibility_modifier")
+                .map(|m| node_text(m, source).to_string());
+            let private_name = node
+                .child_by_field_name("name")
+                .is_some_and(|name| name.kind() == "private_property_identifier");
+            modifier.or_else(|| private_name.then(|| "private".to_string()))
+        }
+        Language::Java | Language::CSharp => {
+            let mut cursor = node.walk();
+            let modifiers: Vec<&str> = node
+                .children(&mut cursor)
+                .filter(|child| child.kind() == "modifiers" || child.kind() == "modifier")
+                .flat_map(|m| node_text(m, source).split_whitespace())
+                .collect();
+            ["public", "private", "protected", "internal"]
+                .into_iter()
+                .find(|level| modifiers.contains(level))
+                .map(|level| level.to_string())
+        }
+        Language::Cpp => None,
+    }
+}
+
+fn node_text<'a>(node: Node, source: &'a str) -> &'a str {
+    &source[node.byte_range()]
+}
+
+fn field_text<'a>(node: Node, field: &str, source: &'a str) -> Option<&'a str> {
+    node.child_by_field_name(field)
+        .map(|child| node_text(child, source))
+}
+
+fn strip_quotes(text: &str) -> String {
+    text.trim_matches(['"', '\'', '`']).to_string()
+}
+
+fn strip_type_annotation(text: &str) -> &str {
+    text.trim_start_matches(':').trim()
+}
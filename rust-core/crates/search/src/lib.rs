@@ -1,30 +1,474 @@
 //! Search engine for Code Intelligence MCP Server
-
-// pub mod engine;
-// pub mod query;
-// pub mod results;
+//!
+//! [`SearchEngine`] answers [`SearchQuery`]s against the `code_entities`
+//! table written by the indexer. Keyword queries match names and bodies with
+//! `LIKE`; semantic queries rank by cosine similarity between the query's
+//! embedding and stored entity embeddings; hybrid queries blend both scores
+//! with the weights in [`SearchEngineConfig`].
 
 use anyhow::Result;
+use code_intelligence_core::{CodeEntity, EntityType, QueryType, SearchQuery, SearchResult};
+use code_intelligence_embedding::{EmbeddingBackend, MockEmbedding};
+use code_intelligence_storage::{decode_embedding, MetadataMatch, StorageManager, StoredEntity};
+use rusqlite::params_from_iter;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Search configuration
+#[derive(Debug, Clone)]
+pub struct SearchEngineConfig {
+    /// Query type used by [`SearchEngine::search_text`]
+    pub default_query_type: QueryType,
+    /// Results returned when a query asks for 0
+    pub default_limit: usize,
+    /// Upper bound on results per query
+    pub max_limit: usize,
+    /// Share of a hybrid score coming from the keyword match
+    pub keyword_weight: f32,
+    /// Share of a hybrid score coming from semantic similarity
+    pub semantic_weight: f32,
+    /// Matching lines of an entity's body returned as highlights
+    pub max_highlights: usize,
+    /// Entity bodies embedded per backend call when filling missing embeddings
+    pub embedding_batch_size: usize,
+}
+
+impl Default for SearchEngineConfig {
+    fn default() -> Self {
+        Self {
+            default_query_type: QueryType::Keyword,
+            default_limit: 20,
+            max_limit: 100,
+            keyword_weight: 0.5,
+            semantic_weight: 0.5,
+            max_highlights: 3,
+            embedding_batch_size: 32,
+        }
+    }
+}
 
 /// Main search engine
 pub struct SearchEngine {
-    // Implementation details
+    storage: Arc<StorageManager>,
+    backend: Arc<dyn EmbeddingBackend>,
+    config: SearchEngineConfig,
+}
+
+/// A `code_entities` row as stored by the indexer
+struct EntityRow {
+    id: String,
+    name: String,
+    file_path: String,
+    entity_type: String,
+    start_line: u32,
+    end_line: u32,
+    content: String,
 }
 
+const ENTITY_COLUMNS: &str =
+    "e.id, e.name, e.file_path, e.entity_type, e.start_line, e.end_line, e.content";
+
 impl SearchEngine {
-    pub fn new() -> Self {
-        Self {}
+    /// Open the index database at `db_path`
+    pub fn open(db_path: impl AsRef<Path>, config: SearchEngineConfig) -> Result<Self> {
+        Ok(Self::with_storage(
+            Arc::new(StorageManager::open(db_path)?),
+            config,
+        ))
+    }
+
+    /// Search the database behind an already open storage manager
+    ///
+    /// Semantic search uses the hash-based [`MockEmbedding`] until
+    /// [`Self::with_backend`] supplies a model.
+    pub fn with_storage(storage: Arc<StorageManager>, config: SearchEngineConfig) -> Self {
+        Self {
+            storage,
+            backend: Arc::new(MockEmbedding::default()),
+            config,
+        }
+    }
+
+    /// Embed queries and entities with `backend`
+    ///
+    /// Stored embeddings from another backend are not comparable; clear the
+    /// `entity_embeddings` table when switching models.
+    pub fn with_backend(mut self, backend: Arc<dyn EmbeddingBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    pub fn config(&self) -> &SearchEngineConfig {
+        &self.config
+    }
+
+    /// Search `text` with the configured default query type and limit
+    pub fn search_text(&self, text: &str) -> Result<Vec<SearchResult>> {
+        self.search(&SearchQuery {
+            text: text.to_string(),
+            query_type: self.config.default_query_type.clone(),
+            limit: 0,
+            filters: HashMap::new(),
+        })
+    }
+
+    /// Run a query with the backend matching its `query_type`
+    ///
+    /// Supported filters are `file_path` (substring) and `entity_type` (exact).
+    pub fn search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        match query.query_type {
+            QueryType::Keyword => self.keyword_search(query),
+            QueryType::Semantic => self.semantic_search(query),
+            QueryType::Hybrid => self.hybrid_search(query),
+        }
+    }
+
+    /// Entities whose metadata `key` equals `value`, or starts with it when
+    /// `matching` is [`MetadataMatch::Prefix`], ordered by file and line
+    ///
+    /// Every match scores 1.0 and carries its stored metadata.
+    pub fn search_by_metadata(
+        &self,
+        key: &str,
+        value: &str,
+        matching: MetadataMatch,
+    ) -> Result<Vec<SearchResult>> {
+        Ok(self
+            .storage
+            .entities_by_metadata(key, value, matching)?
+            .into_iter()
+            .map(|stored| {
+                let StoredEntity {
+                    id,
+                    name,
+                    file_path,
+                    entity_type,
+                    start_line,
+                    end_line,
+                    content,
+                    metadata,
+                } = stored;
+                let mut entity = core_entity(EntityRow {
+                    id,
+                    name,
+                    file_path,
+                    entity_type,
+                    start_line,
+                    end_line,
+                    content,
+                });
+                entity.metadata.extend(metadata);
+                SearchResult {
+                    entity,
+                    score: 1.0,
+                    highlights: Vec::new(),
+                }
+            })
+            .collect())
+    }
+
+    /// Entities whose name or body contains the query text
+    pub fn keyword_search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        let limit = self.limit(query.limit);
+        let mut results: Vec<SearchResult> = self
+            .keyword_candidates(query)?
+            .into_iter()
+            .map(|row| {
+                let score = keyword_score(&query.text, &row.name, &row.content);
+                self.result(row, score, &query.text)
+            })
+            .collect();
+
+        sort_by_score(&mut results);
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Entities ranked by cosine similarity to the query
+    ///
+    /// Entities indexed since the last semantic query are embedded first.
+    pub fn semantic_search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        let limit = self.limit(query.limit);
+        let mut results: Vec<SearchResult> = self
+            .semantic_scores(query)?
+            .into_iter()
+            .map(|(row, similarity)| self.result(row, similarity, &query.text))
+            .collect();
+
+        sort_by_score(&mut results);
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Keyword and semantic scores blended by the configured weights
+    ///
+    /// An entity found by only one backend scores 0 for the other.
+    pub fn hybrid_search(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        let limit = self.limit(query.limit);
+        let total_weight = self.config.keyword_weight + self.config.semantic_weight;
+        let (keyword_weight, semantic_weight) = if total_weight > 0.0 {
+            (
+                self.config.keyword_weight / total_weight,
+                self.config.semantic_weight / total_weight,
+            )
+        } else {
+            (0.5, 0.5)
+        };
+
+        let mut keyword_scores: HashMap<String, f32> = self
+            .keyword_candidates(query)?
+            .into_iter()
+            .map(|row| {
+                let score = keyword_score(&query.text, &row.name, &row.content);
+                (row.id, score)
+            })
+            .collect();
+
+        let mut results: Vec<SearchResult> = self
+            .semantic_scores(query)?
+            .into_iter()
+            .map(|(row, similarity)| {
+                let keyword = keyword_scores.remove(&row.id).unwrap_or(0.0);
+                let score = keyword_weight * keyword + semantic_weight * similarity.max(0.0);
+                self.result(row, score, &query.text)
+            })
+            .collect();
+
+        sort_by_score(&mut results);
+        results.truncate(limit);
+        Ok(results)
     }
 
-    pub async fn search(&self, _query: &str) -> Result<Vec<String>> {
-        // TODO: Implement search logic
-        Ok(vec![])
+    /// Embed every entity that has no stored embedding, returning how many were embedded
+    pub fn index_embeddings(&self) -> Result<usize> {
+        let missing: Vec<(String, String)> = {
+            let conn = self.storage.connection()?;
+            let mut stmt = conn.prepare(
+                "SELECT e.id, IFNULL(e.content, '') FROM code_entities e
+                 LEFT JOIN entity_embeddings v ON v.entity_id = e.id
+                 WHERE v.entity_id IS NULL",
+            )?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        for batch in missing.chunks(self.config.embedding_batch_size.max(1)) {
+            let bodies: Vec<String> = batch.iter().map(|(_, body)| body.clone()).collect();
+            let embeddings = self.backend.embed_batch(&bodies)?;
+            let stored: Vec<(String, Vec<f32>)> = batch
+                .iter()
+                .map(|(id, _)| id.clone())
+                .zip(embeddings)
+                .collect();
+            self.storage.store_embeddings(&stored)?;
+        }
+
+        Ok(missing.len())
+    }
+
+    fn keyword_candidates(&self, query: &SearchQuery) -> Result<Vec<EntityRow>> {
+        let mut sql_params: Vec<String> = vec![format!("%{}%", query.text)];
+        let mut sql = format!(
+            "SELECT {} FROM code_entities e WHERE (e.name LIKE ?1 OR e.content LIKE ?1)",
+            ENTITY_COLUMNS
+        );
+        sql.push_str(&filter_conditions(query, &mut sql_params)?);
+        // Ranking happens in Rust; name matches are read first so the cap
+        // only drops body-only matches
+        sql.push_str(&format!(
+            " ORDER BY CASE WHEN e.name LIKE ?1 THEN 0 ELSE 1 END, e.name LIMIT {}",
+            self.config.max_limit * 4
+        ));
+
+        let conn = self.storage.connection()?;
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(sql_params.iter()), map_entity_row)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Every filtered entity with its cosine similarity to the query
+    fn semantic_scores(&self, query: &SearchQuery) -> Result<Vec<(EntityRow, f32)>> {
+        self.index_embeddings()?;
+        let query_embedding = self
+            .backend
+            .embed_batch(std::slice::from_ref(&query.text))?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("Embedding backend returned no vector"))?;
+
+        let mut sql_params: Vec<String> = Vec::new();
+        let mut sql = format!(
+            "SELECT {}, v.embedding FROM code_entities e
+             JOIN entity_embeddings v ON v.entity_id = e.id WHERE 1 = 1",
+            ENTITY_COLUMNS
+        );
+        sql.push_str(&filter_conditions(query, &mut sql_params)?);
+
+        let conn = self.storage.connection()?;
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(sql_params.iter()), |row| {
+            Ok((map_entity_row(row)?, row.get::<_, Vec<u8>>(7)?))
+        })?;
+
+        let mut scored = Vec::new();
+        for row in rows {
+            let (entity, embedding) = row?;
+            let similarity = cosine_similarity(&query_embedding, &decode_embedding(&embedding));
+            scored.push((entity, similarity));
+        }
+        Ok(scored)
+    }
+
+    fn result(&self, row: EntityRow, score: f32, text: &str) -> SearchResult {
+        let highlights = highlights(&row.content, text, self.config.max_highlights);
+        SearchResult {
+            entity: core_entity(row),
+            score,
+            highlights,
+        }
+    }
+
+    fn limit(&self, requested: usize) -> usize {
+        let limit = if requested == 0 {
+            self.config.default_limit
+        } else {
+            requested
+        };
+        limit.min(self.config.max_limit)
     }
 }
 
-impl Default for SearchEngine {
-    fn default() -> Self {
-        Self::new()
+/// ` AND ...` clauses for the query's filters, binding their values
+fn filter_conditions(query: &SearchQuery, sql_params: &mut Vec<String>) -> Result<String> {
+    let mut filters: Vec<_> = query.filters.iter().collect();
+    filters.sort();
+
+    let mut sql = String::new();
+    for (key, value) in filters {
+        match key.as_str() {
+            "file_path" => {
+                sql_params.push(format!("%{}%", value));
+                sql.push_str(&format!(" AND e.file_path LIKE ?{}", sql_params.len()));
+            }
+            "entity_type" => {
+                sql_params.push(value.to_lowercase());
+                sql.push_str(&format!(
+                    " AND LOWER(e.entity_type) = ?{}",
+                    sql_params.len()
+                ));
+            }
+            _ => anyhow::bail!("Unsupported search filter: {}", key),
+        }
+    }
+    Ok(sql)
+}
+
+fn map_entity_row(row: &rusqlite::Row) -> rusqlite::Result<EntityRow> {
+    Ok(EntityRow {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        file_path: row.get(2)?,
+        entity_type: row.get(3)?,
+        start_line: row.get::<_, Option<u32>>(4)?.unwrap_or(0),
+        end_line: row.get::<_, Option<u32>>(5)?.unwrap_or(0),
+        content: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+    })
+}
+
+/// Convert a stored row to the core entity, keeping the stored id in `metadata`
+///
+/// Stored ids such as `file:line:name` are not UUIDs, so the core id is
+/// derived from a hash of the stored one and is stable across queries.
+fn core_entity(row: EntityRow) -> CodeEntity {
+    let id = Uuid::parse_str(&row.id).unwrap_or_else(|_| {
+        let digest = Sha256::digest(row.id.as_bytes());
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&digest[..16]);
+        Uuid::from_bytes(bytes)
+    });
+
+    CodeEntity {
+        id,
+        entity_type: entity_type(&row.entity_type),
+        name: row.name,
+        file_path: row.file_path,
+        start_line: row.start_line,
+        end_line: row.end_line,
+        content: row.content,
+        metadata: HashMap::from([("entity_id".to_string(), row.id)]),
+    }
+}
+
+/// Map a stored entity type, written as `function` or `Function`, to the core type
+fn entity_type(stored: &str) -> EntityType {
+    match stored.to_lowercase().as_str() {
+        "function" | "method" => EntityType::Function,
+        "endpoint" => EntityType::Endpoint,
+        "class" => EntityType::Class,
+        "interface" => EntityType::Interface,
+        "constant" => EntityType::Constant,
+        "module" => EntityType::Module,
+        "import" => EntityType::Import,
+        "export" => EntityType::Export,
+        "enum" => EntityType::Enum,
+        "struct" => EntityType::Struct,
+        "typealias" | "type_alias" => EntityType::TypeAlias,
+        "trait" => EntityType::Trait,
+        _ => EntityType::Variable,
+    }
+}
+
+/// Score in [0, 1]: exact name, name prefix, name substring, then body-only matches
+fn keyword_score(query: &str, name: &str, content: &str) -> f32 {
+    let query = query.to_lowercase();
+    let name = name.to_lowercase();
+
+    if name == query {
+        1.0
+    } else if name.starts_with(&query) {
+        0.9
+    } else if name.contains(&query) {
+        0.7
+    } else if content.to_lowercase().contains(&query) {
+        0.5
+    } else {
+        0.0
+    }
+}
+
+/// Trimmed lines of `content` containing `text`, ignoring case
+fn highlights(content: &str, text: &str, max: usize) -> Vec<String> {
+    let needle = text.to_lowercase();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    content
+        .lines()
+        .filter(|line| line.to_lowercase().contains(&needle))
+        .map(|line| line.trim().to_string())
+        .take(max)
+        .collect()
+}
+
+fn sort_by_score(results: &mut [SearchResult]) {
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.entity.name.cmp(&b.entity.name))
+    });
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
     }
 }
 
@@ -32,10 +476,157 @@ impl Default for SearchEngine {
 mod tests {
     use super::*;
 
-    #[tokio::test]
-    async fn test_search_engine() {
-        let engine = SearchEngine::new();
-        let results = engine.search("test").await.unwrap();
-        assert!(results.is_empty());
+    const PARSE_BODY: &str = "function parseConfig(path) {\n  return JSON.parse(read(path));\n}";
+
+    /// Three entities in two files, as the FFI indexer stores them
+    fn fixture() -> SearchEngine {
+        let storage = StorageManager::open_in_memory().unwrap();
+        for (id, name, file, entity_type, line, content) in [
+            (
+                "config.js:1:parseConfig",
+                "parseConfig",
+                "src/config.js",
+                "function",
+                1,
+                PARSE_BODY,
+            ),
+            (
+                "config.js:5:Config",
+                "Config",
+                "src/config.js",
+                "class",
+                5,
+                "class Config {}",
+            ),
+            (
+                "render.js:1:render",
+                "render",
+                "src/render.js",
+                "function",
+                1,
+                "function render() {\n  return parseConfig('app.json');\n}",
+            ),
+        ] {
+            storage
+                .insert_entities(&[StoredEntity {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    file_path: file.to_string(),
+                    entity_type: entity_type.to_string(),
+                    start_line: line,
+                    end_line: line,
+                    content: content.to_string(),
+                    metadata: HashMap::from([(
+                        "owners".to_string(),
+                        format!("@org/{}", entity_type),
+                    )]),
+                }])
+                .unwrap();
+        }
+        SearchEngine::with_storage(Arc::new(storage), SearchEngineConfig::default())
+    }
+
+    fn query(text: &str, query_type: QueryType) -> SearchQuery {
+        SearchQuery {
+            text: text.to_string(),
+            query_type,
+            limit: 10,
+            filters: HashMap::new(),
+        }
+    }
+
+    fn names(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.entity.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_keyword_search_ranks_name_matches_first() {
+        let engine = fixture();
+        let results = engine
+            .search(&query("parseConfig", QueryType::Keyword))
+            .unwrap();
+
+        assert_eq!(names(&results), vec!["parseConfig", "render"]);
+        assert_eq!(results[0].score, 1.0);
+        assert_eq!(results[0].entity.entity_type, EntityType::Function);
+        assert_eq!(
+            results[0].entity.metadata["entity_id"],
+            "config.js:1:parseConfig"
+        );
+        assert_eq!(
+            results[1].highlights,
+            vec!["return parseConfig('app.json');"]
+        );
+
+        let mut filtered = query("config", QueryType::Keyword);
+        filtered
+            .filters
+            .insert("entity_type".to_string(), "Class".to_string());
+        assert_eq!(names(&engine.search(&filtered).unwrap()), vec!["Config"]);
+    }
+
+    #[test]
+    fn test_semantic_search_finds_identical_body() {
+        let engine = fixture();
+        let results = engine
+            .search(&query(PARSE_BODY, QueryType::Semantic))
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].entity.name, "parseConfig");
+        assert!((results[0].score - 1.0).abs() < 1e-5);
+        // Embeddings are stored once
+        assert_eq!(engine.index_embeddings().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_hybrid_search_blends_scores() {
+        let engine = fixture();
+        let results = engine.search(&query("render", QueryType::Hybrid)).unwrap();
+
+        assert_eq!(results[0].entity.name, "render");
+        assert!(results[0].score >= 0.5 && results[0].score <= 1.0);
+        assert!(results.iter().all(|r| r.score <= results[0].score));
+
+        let mut scoped = query("render", QueryType::Hybrid);
+        scoped
+            .filters
+            .insert("file_path".to_string(), "config".to_string());
+        assert!(!names(&engine.search(&scoped).unwrap()).contains(&"render"));
+    }
+
+    #[test]
+    fn test_search_by_metadata() {
+        let engine = fixture();
+        let results = engine
+            .search_by_metadata("owners", "@org/function", MetadataMatch::Exact)
+            .unwrap();
+        assert_eq!(names(&results), vec!["parseConfig", "render"]);
+        assert_eq!(results[0].score, 1.0);
+        assert_eq!(results[0].entity.metadata["owners"], "@org/function");
+        assert_eq!(
+            results[0].entity.metadata["entity_id"],
+            "config.js:1:parseConfig"
+        );
+
+        let results = engine
+            .search_by_metadata("owners", "@org/c", MetadataMatch::Prefix)
+            .unwrap();
+        assert_eq!(names(&results), vec!["Config"]);
+    }
+
+    #[test]
+    fn test_search_text_uses_defaults() {
+        let engine = fixture();
+        assert_eq!(
+            names(&engine.search_text("Config").unwrap()),
+            vec!["Config", "parseConfig", "render"]
+        );
+        assert!(engine
+            .search(&SearchQuery {
+                filters: HashMap::from([("owner".to_string(), "me".to_string())]),
+                ..query("x", QueryType::Keyword)
+            })
+            .is_err());
     }
 }
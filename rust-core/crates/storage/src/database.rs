@@ -0,0 +1,84 @@
+//! Fixed-size SQLite connection pool
+
+use anyhow::Result;
+use rusqlite::Connection;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Condvar, Mutex};
+
+/// Connections opened up front and handed out one caller at a time
+///
+/// Checking out blocks while every connection is in use, so the pool size
+/// bounds how many statements run against the database at once.
+pub struct ConnectionPool {
+    idle: Mutex<Vec<Connection>>,
+    returned: Condvar,
+}
+
+impl ConnectionPool {
+    /// Pool the given connections
+    pub fn new(connections: Vec<Connection>) -> Self {
+        Self {
+            idle: Mutex::new(connections),
+            returned: Condvar::new(),
+        }
+    }
+
+    /// Take a connection, waiting for one to be returned if none is idle
+    pub fn get(&self) -> Result<PooledConnection<'_>> {
+        let mut idle = self
+            .idle
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Connection pool lock poisoned"))?;
+        loop {
+            if let Some(conn) = idle.pop() {
+                return Ok(PooledConnection {
+                    pool: self,
+                    conn: Some(conn),
+                });
+            }
+            idle = self
+                .returned
+                .wait(idle)
+                .map_err(|_| anyhow::anyhow!("Connection pool lock poisoned"))?;
+        }
+    }
+
+    fn put(&self, conn: Connection) {
+        if let Ok(mut idle) = self.idle.lock() {
+            idle.push(conn);
+            self.returned.notify_one();
+        }
+    }
+}
+
+/// A checked-out connection, returned to its pool on drop
+pub struct PooledConnection<'a> {
+    pool: &'a ConnectionPool,
+    conn: Option<Connection>,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+            .as_ref()
+            .expect("connection is present until drop")
+    }
+}
+
+impl DerefMut for PooledConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.conn
+            .as_mut()
+            .expect("connection is present until drop")
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.put(conn);
+        }
+    }
+}
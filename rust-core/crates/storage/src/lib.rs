@@ -1,47 +1,806 @@
 //! Storage layer for Code Intelligence MCP Server
+//!
+//! [`StorageManager`] owns the index database: it opens a pool of SQLite
+//! connections, brings the schema up to date through [`migrations`], and
+//! exposes typed reads and writes of entities, relationships and embeddings.
+//! Writes that touch several rows run in a single transaction.
 
-// pub mod database;
-// pub mod models;
-// pub mod migrations;
+pub mod database;
+pub mod migrations;
+pub mod models;
+
+pub use database::{ConnectionPool, PooledConnection};
+pub use models::{EntityQuery, MetadataMatch, StoredEntity, StoredRelationship};
 
 use anyhow::Result;
+use rusqlite::{params, params_from_iter, Connection, OpenFlags, OptionalExtension, Transaction};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Connection settings
+#[derive(Debug, Clone)]
+pub struct StorageConfig {
+    /// Connections opened for a database file; in-memory databases always use one
+    pub pool_size: usize,
+    /// How long a statement waits on a lock held by another connection
+    pub busy_timeout_ms: u64,
+    /// Truncate the WAL after this many committed write batches, keeping the
+    /// `-wal` file from growing through a long index run; 0 leaves it to SQLite
+    pub checkpoint_every_batches: usize,
+    /// Bytes per database page; only takes effect when the file is created
+    pub page_size: u32,
+    /// Page cache per connection, in pages when positive or KiB when negative
+    pub cache_size: i64,
+    /// Bytes of the file read through a memory map; 0 disables it
+    pub mmap_size: u64,
+    /// How often SQLite waits for writes to reach the disk
+    pub synchronous: Synchronous,
+    /// Open an existing file without write access: nothing is created or
+    /// migrated, and every write fails
+    pub read_only: bool,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            pool_size: 4,
+            busy_timeout_ms: 5000,
+            checkpoint_every_batches: 100,
+            page_size: 4096,
+            cache_size: -65536,
+            mmap_size: 256 * 1024 * 1024,
+            synchronous: Synchronous::Normal,
+            read_only: false,
+        }
+    }
+}
+
+/// The `synchronous` pragma applied to file databases
+///
+/// Under WAL, `Normal` syncs only at checkpoints: a crash or power loss can
+/// roll back the last transactions committed since then, but never corrupts
+/// the database. That suits an index that can be rebuilt from source; `Full`
+/// makes every commit durable at the cost of a sync per write batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Synchronous {
+    Off,
+    Normal,
+    Full,
+    Extra,
+}
+
+impl Synchronous {
+    /// The pragma value, e.g. `NORMAL`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Synchronous::Off => "OFF",
+            Synchronous::Normal => "NORMAL",
+            Synchronous::Full => "FULL",
+            Synchronous::Extra => "EXTRA",
+        }
+    }
+
+    /// Parse a pragma value, ignoring case; `None` when it names no level
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_uppercase().as_str() {
+            "OFF" => Some(Synchronous::Off),
+            "NORMAL" => Some(Synchronous::Normal),
+            "FULL" => Some(Synchronous::Full),
+            "EXTRA" => Some(Synchronous::Extra),
+            _ => None,
+        }
+    }
+}
+
+/// Database size before and after [`StorageManager::compact`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactResult {
+    /// Bytes of database pages, free pages included, once the WAL is merged
+    pub size_before: u64,
+    pub size_after: u64,
+    /// Free pages were released with `incremental_vacuum` rather than a full `VACUUM`
+    pub incremental: bool,
+}
+
+/// What [`StorageManager::merge_index`] took from the other database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MergeResult {
+    /// Entities with ids not yet stored
+    pub entities_added: usize,
+    /// Stored entities replaced by a more recently indexed copy
+    pub entities_updated: usize,
+    /// Entities whose stored copy was indexed at the same time or later
+    pub entities_skipped: usize,
+    pub relationships_added: usize,
+    /// Embeddings of the added and updated entities
+    pub embeddings_copied: usize,
+}
 
 /// Main storage manager
 pub struct StorageManager {
-    // Implementation details
+    pool: ConnectionPool,
+    config: StorageConfig,
+    /// Write transactions committed since the last checkpoint
+    batches_since_checkpoint: AtomicUsize,
 }
 
+const ENTITY_COLUMNS: &str = "id, name, file_path, entity_type, start_line, end_line, content";
+
 impl StorageManager {
-    pub fn new() -> Self {
-        Self {}
+    /// Open the database at `db_path` with the default configuration
+    pub fn open(db_path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_config(db_path, StorageConfig::default())
+    }
+
+    /// Open the database at `db_path`, creating it and applying pending migrations
+    ///
+    /// File databases use WAL journaling so pooled readers do not block the
+    /// writer, and every connection gets the pragmas set in `config`.
+    pub fn open_with_config(db_path: impl AsRef<Path>, config: StorageConfig) -> Result<Self> {
+        let db_path = db_path.as_ref();
+        let mut connections = Vec::with_capacity(config.pool_size.max(1));
+        for _ in 0..config.pool_size.max(1) {
+            let conn = if config.read_only {
+                Connection::open_with_flags(
+                    db_path,
+                    OpenFlags::SQLITE_OPEN_READ_ONLY
+                        | OpenFlags::SQLITE_OPEN_URI
+                        | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                )
+            } else {
+                Connection::open(db_path)
+            }
+            .map_err(|e| anyhow::anyhow!("Failed to open database {}: {}", db_path.display(), e))?;
+            conn.busy_timeout(Duration::from_millis(config.busy_timeout_ms))?;
+            apply_pragmas(&conn, &config)?;
+            connections.push(conn);
+        }
+        Self::from_connections(connections, config)
+    }
+
+    /// A private in-memory database, for tests and throwaway indexes
+    pub fn open_in_memory() -> Result<Self> {
+        let config = StorageConfig {
+            pool_size: 1,
+            ..Default::default()
+        };
+        Self::from_connections(vec![Connection::open_in_memory()?], config)
+    }
+
+    fn from_connections(mut connections: Vec<Connection>, config: StorageConfig) -> Result<Self> {
+        if let Some(conn) = connections.first_mut() {
+            if config.read_only {
+                let version = migrations::schema_version(conn)?;
+                if version != migrations::LATEST_VERSION {
+                    anyhow::bail!(
+                        "Database schema version {} differs from the supported version {} and cannot be migrated read-only",
+                        version,
+                        migrations::LATEST_VERSION
+                    );
+                }
+            } else {
+                migrations::migrate(conn)?;
+            }
+        }
+        Ok(Self {
+            pool: ConnectionPool::new(connections),
+            config,
+            batches_since_checkpoint: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn config(&self) -> &StorageConfig {
+        &self.config
+    }
+
+    /// Check out a pooled connection for queries the typed methods do not cover
+    pub fn connection(&self) -> Result<PooledConnection<'_>> {
+        self.pool.get()
+    }
+
+    /// Copy the WAL into the database file and truncate it to zero bytes
+    ///
+    /// Called every `checkpoint_every_batches` writes and by indexers once a
+    /// run completes. A no-op for databases not in WAL mode.
+    pub fn checkpoint(&self) -> Result<()> {
+        checkpoint(&*self.connection()?)?;
+        self.batches_since_checkpoint.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Reclaim free pages left by deletes and refresh query-planner statistics
+    ///
+    /// Databases created with `auto_vacuum = INCREMENTAL` release their free
+    /// pages with `PRAGMA incremental_vacuum`, which avoids rewriting the file
+    /// under a long exclusive lock; others are rebuilt with `VACUUM`.
+    pub fn compact(&self) -> Result<CompactResult> {
+        let conn = self.connection()?;
+        checkpoint(&conn)?;
+        let size_before = database_size(&conn)?;
+
+        let auto_vacuum: i64 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))?;
+        let incremental = auto_vacuum == 2;
+        if incremental {
+            // Each step frees one page, so run the pragma to completion
+            let mut stmt = conn.prepare("PRAGMA incremental_vacuum")?;
+            let mut rows = stmt.query([])?;
+            while rows.next()?.is_some() {}
+        } else {
+            conn.execute_batch("VACUUM")?;
+            // VACUUM may renumber the rowids the full-text index refers to
+            conn.execute_batch(
+                "INSERT INTO code_entities_fts (code_entities_fts) VALUES ('rebuild')",
+            )?;
+        }
+        conn.execute_batch("ANALYZE")?;
+
+        // VACUUM in WAL mode writes the rebuilt pages to the WAL first
+        checkpoint(&conn)?;
+        self.batches_since_checkpoint.store(0, Ordering::Relaxed);
+        Ok(CompactResult {
+            size_before,
+            size_after: database_size(&conn)?,
+            incremental,
+        })
+    }
+
+    /// Count a committed write, checkpointing through `conn` when the interval is reached
+    fn batch_committed(&self, conn: &Connection) -> Result<()> {
+        let every = self.config.checkpoint_every_batches;
+        if every == 0 {
+            return Ok(());
+        }
+        let committed = self
+            .batches_since_checkpoint
+            .fetch_add(1, Ordering::Relaxed)
+            + 1;
+        if committed >= every {
+            self.batches_since_checkpoint.store(0, Ordering::Relaxed);
+            checkpoint(conn)?;
+        }
+        Ok(())
+    }
+
+    /// The schema version of the open database
+    pub fn schema_version(&self) -> Result<u32> {
+        migrations::schema_version(&*self.connection()?)
+    }
+
+    /// Insert or replace entities in one transaction, returning how many were written
+    pub fn insert_entities(&self, entities: &[StoredEntity]) -> Result<usize> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+        insert_entities(&tx, entities)?;
+        tx.commit()?;
+        self.batch_committed(&conn)?;
+        Ok(entities.len())
+    }
+
+    /// Swap a file's entities for `entities` atomically
+    ///
+    /// Readers see either the old or the new set, never a file with no entities.
+    pub fn replace_file_entities(&self, file_path: &str, entities: &[StoredEntity]) -> Result<()> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+        delete_where(&tx, "file_path = ?1", file_path)?;
+        insert_entities(&tx, entities)?;
+        tx.commit()?;
+        self.batch_committed(&conn)?;
+        Ok(())
+    }
+
+    /// Get one entity by id
+    pub fn get_entity(&self, id: &str) -> Result<Option<StoredEntity>> {
+        let conn = self.connection()?;
+        let entity = conn
+            .query_row(
+                &format!("SELECT {} FROM code_entities WHERE id = ?1", ENTITY_COLUMNS),
+                params![id],
+                map_entity_row,
+            )
+            .optional()?;
+        let mut entities: Vec<StoredEntity> = entity.into_iter().collect();
+        attach_metadata(&conn, &mut entities)?;
+        Ok(entities.pop())
+    }
+
+    /// Entities matching every set filter, ordered by file and line
+    pub fn query_entities(&self, query: &EntityQuery) -> Result<Vec<StoredEntity>> {
+        let mut conditions = Vec::new();
+        let mut sql_params: Vec<String> = Vec::new();
+
+        if let Some(name) = &query.name_contains {
+            sql_params.push(format!("%{}%", name));
+            conditions.push(format!("name LIKE ?{}", sql_params.len()));
+        }
+        if let Some(file_path) = &query.file_path {
+            sql_params.push(file_path.clone());
+            conditions.push(format!("file_path = ?{}", sql_params.len()));
+        }
+        if let Some(prefix) = &query.path_prefix {
+            sql_params.push(prefix.clone());
+            conditions.push(format!(
+                "substr(file_path, 1, length(?{0})) = ?{0}",
+                sql_params.len()
+            ));
+        }
+        if let Some(entity_type) = &query.entity_type {
+            sql_params.push(entity_type.to_lowercase());
+            conditions.push(format!("LOWER(entity_type) = ?{}", sql_params.len()));
+        }
+
+        let mut sql = format!("SELECT {} FROM code_entities", ENTITY_COLUMNS);
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(" ORDER BY file_path, start_line, name");
+        if let Some(limit) = query.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(sql_params.iter()), map_entity_row)?;
+        let mut entities = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+        attach_metadata(&conn, &mut entities)?;
+        Ok(entities)
+    }
+
+    /// Entities whose metadata `key` holds `value`, or a value starting with
+    /// it, ordered by file and line
+    ///
+    /// Both modes are range scans of the `(key, value)` index on
+    /// `entity_metadata`, never a scan of every entity.
+    pub fn entities_by_metadata(
+        &self,
+        key: &str,
+        value: &str,
+        mode: MetadataMatch,
+    ) -> Result<Vec<StoredEntity>> {
+        let mut sql_params = vec![key.to_string(), value.to_string()];
+        let condition = match mode {
+            MetadataMatch::Exact => "m.value = ?2",
+            MetadataMatch::Prefix => {
+                // Every string starting with `value` sorts below `value`
+                // followed by the highest code point
+                sql_params.push(format!("{}{}", value, char::MAX));
+                "m.value >= ?2 AND m.value < ?3"
+            }
+        };
+
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM entity_metadata m
+             JOIN code_entities e ON e.id = m.entity_id
+             WHERE m.key = ?1 AND {}
+             ORDER BY e.file_path, e.start_line, e.name",
+            entity_columns("e"),
+            condition
+        ))?;
+        let rows = stmt.query_map(params_from_iter(sql_params.iter()), map_entity_row)?;
+        let mut entities = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+        attach_metadata(&conn, &mut entities)?;
+        Ok(entities)
+    }
+
+    /// Entities whose name or content contain every word of `text`, best match first
+    ///
+    /// Words are matched whole, case-insensitively, and FTS5 query syntax in
+    /// `text` is taken literally. No words means no matches.
+    pub fn search_text(&self, text: &str, limit: usize) -> Result<Vec<StoredEntity>> {
+        let query = text
+            .split_whitespace()
+            .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM code_entities_fts f
+             JOIN code_entities e ON e.rowid = f.rowid
+             WHERE code_entities_fts MATCH ?1
+             ORDER BY f.rank LIMIT ?2",
+            entity_columns("e")
+        ))?;
+        let rows = stmt.query_map(params![query, limit as i64], map_entity_row)?;
+        let mut entities = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+        attach_metadata(&conn, &mut entities)?;
+        Ok(entities)
+    }
+
+    /// Number of stored entities
+    pub fn entity_count(&self) -> Result<usize> {
+        let conn = self.connection()?;
+        let count: i64 =
+            conn.query_row("SELECT COUNT(*) FROM code_entities", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Delete a file's entities with their relationships and embeddings,
+    /// returning how many entities were removed
+    pub fn delete_by_file(&self, file_path: &str) -> Result<usize> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+        let deleted = delete_where(&tx, "file_path = ?1", file_path)?;
+        tx.commit()?;
+        self.batch_committed(&conn)?;
+        Ok(deleted)
+    }
+
+    /// Delete every entity under `prefix`, such as a codebase root being re-indexed
+    pub fn delete_by_path_prefix(&self, prefix: &str) -> Result<usize> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+        let deleted = delete_where(&tx, "substr(file_path, 1, length(?1)) = ?1", prefix)?;
+        tx.commit()?;
+        self.batch_committed(&conn)?;
+        Ok(deleted)
     }
 
-    pub async fn store_data(&self, _key: &str, _value: &str) -> Result<()> {
-        // TODO: Implement storage logic
+    /// Delete all entities, relationships, embeddings and metadata
+    pub fn clear(&self) -> Result<()> {
+        self.connection()?.execute_batch(
+            "BEGIN;
+             DELETE FROM code_relationships;
+             DELETE FROM entity_embeddings;
+             DELETE FROM entity_metadata;
+             DELETE FROM code_entities;
+             COMMIT;",
+        )?;
         Ok(())
     }
 
-    pub async fn retrieve_data(&self, _key: &str) -> Result<Option<String>> {
-        // TODO: Implement retrieval logic
-        Ok(None)
+    /// Record relationships, skipping ones already stored; returns how many were new
+    pub fn insert_relationships(&self, relationships: &[StoredRelationship]) -> Result<usize> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+        let mut inserted = 0;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR IGNORE INTO code_relationships
+                 (source_entity_id, target_entity_id, relationship_type) VALUES (?1, ?2, ?3)",
+            )?;
+            for relationship in relationships {
+                inserted += stmt.execute(params![
+                    relationship.source_entity_id,
+                    relationship.target_entity_id,
+                    relationship.relationship_type
+                ])?;
+            }
+        }
+        tx.commit()?;
+        self.batch_committed(&conn)?;
+        Ok(inserted)
+    }
+
+    /// Relationships in which the entity is the source or the target
+    pub fn relationships_of(&self, entity_id: &str) -> Result<Vec<StoredRelationship>> {
+        let conn = self.connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT source_entity_id, target_entity_id, relationship_type FROM code_relationships
+             WHERE source_entity_id = ?1 OR target_entity_id = ?1
+             ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![entity_id], |row| {
+            Ok(StoredRelationship {
+                source_entity_id: row.get(0)?,
+                target_entity_id: row.get(1)?,
+                relationship_type: row.get(2)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Insert or replace entity embeddings in one transaction
+    pub fn store_embeddings(&self, embeddings: &[(String, Vec<f32>)]) -> Result<()> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO entity_embeddings (entity_id, embedding) VALUES (?1, ?2)",
+            )?;
+            for (entity_id, embedding) in embeddings {
+                stmt.execute(params![entity_id, encode_embedding(embedding)])?;
+            }
+        }
+        tx.commit()?;
+        self.batch_committed(&conn)?;
+        Ok(())
+    }
+
+    /// Get the stored embedding of an entity
+    pub fn get_embedding(&self, entity_id: &str) -> Result<Option<Vec<f32>>> {
+        let conn = self.connection()?;
+        let bytes: Option<Vec<u8>> = conn
+            .query_row(
+                "SELECT embedding FROM entity_embeddings WHERE entity_id = ?1",
+                params![entity_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(bytes.map(|bytes| decode_embedding(&bytes)))
+    }
+
+    /// Import the entities, relationships and embeddings of the index at
+    /// `other_path`, such as a shard of a monorepo indexed on another machine
+    ///
+    /// Entities are matched by id; when both databases hold one, the copy
+    /// with the later `indexed_at` wins, and the stored one on a tie. An
+    /// older `other_path` is first migrated to the current schema, and one
+    /// written by a newer schema is refused. Runs in one transaction.
+    pub fn merge_index(&self, other_path: impl AsRef<Path>) -> Result<MergeResult> {
+        let other_path = other_path.as_ref();
+        if !other_path.exists() {
+            anyhow::bail!("Index to merge not found: {}", other_path.display());
+        }
+        drop(Self::open_with_config(
+            other_path,
+            StorageConfig {
+                pool_size: 1,
+                ..Default::default()
+            },
+        )?);
+
+        let mut conn = self.connection()?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS other",
+            params![other_path.to_string_lossy()],
+        )?;
+        let merged = merge_attached(&mut conn);
+        conn.execute_batch("DETACH DATABASE other")?;
+        let merged = merged?;
+        self.batch_committed(&conn)?;
+        Ok(merged)
     }
 }
 
-impl Default for StorageManager {
-    fn default() -> Self {
-        Self::new()
+/// Merge the database attached as `other` into `main`, per [`StorageManager::merge_index`]
+fn merge_attached(conn: &mut Connection) -> Result<MergeResult> {
+    let tx = conn.transaction()?;
+    // Ids whose copy in `other` wins, and whether they are new
+    tx.execute_batch(
+        "CREATE TEMP TABLE merge_winners (id TEXT PRIMARY KEY, is_new INTEGER NOT NULL);
+         INSERT INTO merge_winners
+         SELECT o.id, m.id IS NULL FROM other.code_entities o
+         LEFT JOIN main.code_entities m ON m.id = o.id
+         WHERE m.id IS NULL OR o.indexed_at > m.indexed_at;",
+    )?;
+
+    let other_entities: usize =
+        tx.query_row("SELECT COUNT(*) FROM other.code_entities", [], |row| {
+            row.get(0)
+        })?;
+    let entities_updated = tx.execute(
+        "UPDATE main.code_entities SET
+             name = o.name, file_path = o.file_path, entity_type = o.entity_type,
+             start_line = o.start_line, end_line = o.end_line, content = o.content,
+             indexed_at = o.indexed_at
+         FROM other.code_entities o
+         WHERE o.id = code_entities.id
+           AND o.id IN (SELECT id FROM merge_winners WHERE NOT is_new)",
+        [],
+    )?;
+    let entities_added = tx.execute(
+        &format!(
+            "INSERT INTO main.code_entities ({0}, indexed_at)
+             SELECT {0}, indexed_at FROM other.code_entities
+             WHERE id IN (SELECT id FROM merge_winners WHERE is_new)",
+            ENTITY_COLUMNS
+        ),
+        [],
+    )?;
+
+    // An updated entity's old embedding no longer describes it
+    tx.execute(
+        "DELETE FROM main.entity_embeddings WHERE entity_id IN (SELECT id FROM merge_winners)",
+        [],
+    )?;
+    tx.execute(
+        "DELETE FROM main.entity_metadata WHERE entity_id IN (SELECT id FROM merge_winners)",
+        [],
+    )?;
+    tx.execute(
+        "INSERT INTO main.entity_metadata (entity_id, key, value)
+         SELECT entity_id, key, value FROM other.entity_metadata
+         WHERE entity_id IN (SELECT id FROM merge_winners)",
+        [],
+    )?;
+    let embeddings_copied = tx.execute(
+        "INSERT INTO main.entity_embeddings (entity_id, embedding)
+         SELECT entity_id, embedding FROM other.entity_embeddings
+         WHERE entity_id IN (SELECT id FROM merge_winners)",
+        [],
+    )?;
+    let relationships_added = tx.execute(
+        "INSERT OR IGNORE INTO main.code_relationships
+             (source_entity_id, target_entity_id, relationship_type)
+         SELECT source_entity_id, target_entity_id, relationship_type
+         FROM other.code_relationships ORDER BY id",
+        [],
+    )?;
+
+    tx.execute_batch("DROP TABLE temp.merge_winners")?;
+    tx.commit()?;
+    Ok(MergeResult {
+        entities_added,
+        entities_updated,
+        entities_skipped: other_entities - entities_added - entities_updated,
+        relationships_added,
+        embeddings_copied,
+    })
+}
+
+/// Set the page size while the file is still empty, then switch to WAL and
+/// apply the per-connection tuning
+fn apply_pragmas(conn: &Connection, config: &StorageConfig) -> Result<()> {
+    // Both write to the file, which keeps the layout it was created with
+    if !config.read_only {
+        conn.pragma_update(None, "page_size", config.page_size)?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+    }
+    conn.pragma_update(None, "synchronous", config.synchronous.as_str())?;
+    conn.pragma_update(None, "cache_size", config.cache_size)?;
+    // The readback row is the size SQLite settled on, capped at its compile-time limit
+    conn.pragma_update_and_check(None, "mmap_size", config.mmap_size as i64, |_| Ok(()))?;
+    Ok(())
+}
+
+fn checkpoint(conn: &Connection) -> Result<()> {
+    // Returns (busy, wal pages, checkpointed pages); a busy result only means
+    // a reader kept the WAL alive, and the next checkpoint retries
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    Ok(())
+}
+
+fn database_size(conn: &Connection) -> Result<u64> {
+    let page_count: u64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    let page_size: u64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    Ok(page_count * page_size)
+}
+
+fn insert_entities(tx: &Transaction, entities: &[StoredEntity]) -> Result<()> {
+    // An upsert rather than INSERT OR REPLACE: the implicit delete of a
+    // REPLACE does not fire triggers, which would leave stale full-text rows
+    let mut stmt = tx.prepare(&format!(
+        "INSERT INTO code_entities ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT (id) DO UPDATE SET
+             name = excluded.name, file_path = excluded.file_path,
+             entity_type = excluded.entity_type, start_line = excluded.start_line,
+             end_line = excluded.end_line, content = excluded.content,
+             indexed_at = CURRENT_TIMESTAMP",
+        ENTITY_COLUMNS
+    ))?;
+    let mut clear_metadata = tx.prepare("DELETE FROM entity_metadata WHERE entity_id = ?1")?;
+    let mut insert_metadata =
+        tx.prepare("INSERT INTO entity_metadata (entity_id, key, value) VALUES (?1, ?2, ?3)")?;
+    for entity in entities {
+        stmt.execute(params![
+            entity.id,
+            entity.name,
+            entity.file_path,
+            entity.entity_type,
+            entity.start_line,
+            entity.end_line,
+            entity.content
+        ])?;
+        clear_metadata.execute(params![entity.id])?;
+        for (key, value) in &entity.metadata {
+            insert_metadata.execute(params![entity.id, key, value])?;
+        }
+    }
+    Ok(())
+}
+
+/// Fill in each entity's `metadata` from `entity_metadata`
+fn attach_metadata(conn: &Connection, entities: &mut [StoredEntity]) -> Result<()> {
+    let mut stmt = conn.prepare("SELECT key, value FROM entity_metadata WHERE entity_id = ?1")?;
+    for entity in entities.iter_mut() {
+        let rows = stmt.query_map(params![entity.id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        entity.metadata = rows.collect::<rusqlite::Result<_>>()?;
     }
+    Ok(())
+}
+
+/// `ENTITY_COLUMNS` qualified with a table alias, such as `e.id, e.name, ...`
+fn entity_columns(alias: &str) -> String {
+    ENTITY_COLUMNS
+        .split(", ")
+        .map(|column| format!("{}.{}", alias, column))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Delete the entities matching `condition` on `?1` along with rows referring to them
+fn delete_where(tx: &Transaction, condition: &str, value: &str) -> Result<usize> {
+    let ids = format!("SELECT id FROM code_entities WHERE {}", condition);
+    tx.execute(
+        &format!(
+            "DELETE FROM code_relationships
+             WHERE source_entity_id IN ({0}) OR target_entity_id IN ({0})",
+            ids
+        ),
+        params![value],
+    )?;
+    tx.execute(
+        &format!("DELETE FROM entity_embeddings WHERE entity_id IN ({})", ids),
+        params![value],
+    )?;
+    tx.execute(
+        &format!("DELETE FROM entity_metadata WHERE entity_id IN ({})", ids),
+        params![value],
+    )?;
+    Ok(tx.execute(
+        &format!("DELETE FROM code_entities WHERE {}", condition),
+        params![value],
+    )?)
+}
+
+fn map_entity_row(row: &rusqlite::Row) -> rusqlite::Result<StoredEntity> {
+    Ok(StoredEntity {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        file_path: row.get(2)?,
+        entity_type: row.get(3)?,
+        start_line: row.get::<_, Option<u32>>(4)?.unwrap_or(0),
+        end_line: row.get::<_, Option<u32>>(5)?.unwrap_or(0),
+        content: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+        metadata: HashMap::new(),
+    })
+}
+
+/// Embeddings are stored as little-endian `f32` bytes
+pub fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+pub fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[tokio::test]
-    async fn test_storage_manager() {
-        let manager = StorageManager::new();
-        manager.store_data("test", "value").await.unwrap();
-        let result = manager.retrieve_data("test").await.unwrap();
-        assert!(result.is_none()); // Mock implementation
+    #[test]
+    fn test_embedding_round_trip() {
+        let embedding = vec![0.25, -1.5, 3.0];
+        assert_eq!(decode_embedding(&encode_embedding(&embedding)), embedding);
+    }
+
+    #[test]
+    fn test_pool_hands_out_each_connection_once() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let storage = StorageManager::open_with_config(
+            temp_dir.path().join("index.db"),
+            StorageConfig {
+                pool_size: 2,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let first = storage.connection().unwrap();
+        let second = storage.connection().unwrap();
+        let waiter = std::thread::scope(|scope| {
+            let handle = scope.spawn(|| storage.connection().map(|_| ()).is_ok());
+            std::thread::sleep(Duration::from_millis(20));
+            assert!(!handle.is_finished());
+            drop(first);
+            handle.join().unwrap()
+        });
+        assert!(waiter);
+        drop(second);
     }
 }
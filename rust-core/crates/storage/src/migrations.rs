@@ -0,0 +1,105 @@
+//! Schema migrations for the index database
+//!
+//! The applied version is kept in SQLite's `user_version` pragma. Each
+//! migration runs in its own transaction together with the version bump, so
+//! an interrupted upgrade leaves the database at the last complete version.
+
+use anyhow::Result;
+use rusqlite::Connection;
+
+/// Migrations in order; the database version is the number applied
+///
+/// Databases written before versioning existed already hold a
+/// `code_entities` table, so the first migration only creates what is absent.
+const MIGRATIONS: &[&str] = &[
+    // 1: entity table, matching the layout the FFI layer has always written
+    "CREATE TABLE IF NOT EXISTS code_entities (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        file_path TEXT NOT NULL,
+        entity_type TEXT NOT NULL,
+        start_line INTEGER,
+        end_line INTEGER,
+        content TEXT,
+        indexed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+    );",
+    // 2: relationships, embeddings and lookup indexes
+    "CREATE TABLE IF NOT EXISTS code_relationships (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        source_entity_id TEXT NOT NULL,
+        target_entity_id TEXT NOT NULL,
+        relationship_type TEXT NOT NULL,
+        UNIQUE (source_entity_id, target_entity_id, relationship_type)
+    );
+    CREATE TABLE IF NOT EXISTS entity_embeddings (
+        entity_id TEXT PRIMARY KEY,
+        embedding BLOB NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_code_entities_file_path ON code_entities (file_path);
+    CREATE INDEX IF NOT EXISTS idx_code_entities_name ON code_entities (name);
+    CREATE INDEX IF NOT EXISTS idx_code_relationships_target
+        ON code_relationships (target_entity_id);",
+    // 3: full-text index over entity names and content, kept in step with
+    // `code_entities` by triggers so every write updates only its own rows
+    "CREATE VIRTUAL TABLE IF NOT EXISTS code_entities_fts USING fts5(
+        name, content, content = 'code_entities', content_rowid = 'rowid'
+    );
+    CREATE TRIGGER IF NOT EXISTS code_entities_fts_insert AFTER INSERT ON code_entities BEGIN
+        INSERT INTO code_entities_fts (rowid, name, content)
+        VALUES (new.rowid, new.name, new.content);
+    END;
+    CREATE TRIGGER IF NOT EXISTS code_entities_fts_delete AFTER DELETE ON code_entities BEGIN
+        INSERT INTO code_entities_fts (code_entities_fts, rowid, name, content)
+        VALUES ('delete', old.rowid, old.name, old.content);
+    END;
+    CREATE TRIGGER IF NOT EXISTS code_entities_fts_update AFTER UPDATE ON code_entities BEGIN
+        INSERT INTO code_entities_fts (code_entities_fts, rowid, name, content)
+        VALUES ('delete', old.rowid, old.name, old.content);
+        INSERT INTO code_entities_fts (rowid, name, content)
+        VALUES (new.rowid, new.name, new.content);
+    END;
+    INSERT INTO code_entities_fts (code_entities_fts) VALUES ('rebuild');",
+    // 4: entity metadata, one row per key so lookups by key and value, or
+    // key and value prefix, are index range scans
+    "CREATE TABLE IF NOT EXISTS entity_metadata (
+        entity_id TEXT NOT NULL,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (entity_id, key)
+    ) WITHOUT ROWID;
+    CREATE INDEX IF NOT EXISTS idx_entity_metadata_key_value ON entity_metadata (key, value);",
+];
+
+/// Schema version reached once every migration is applied
+pub const LATEST_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// The schema version recorded in the database
+pub fn schema_version(conn: &Connection) -> Result<u32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Apply every pending migration, returning the resulting version
+///
+/// Fails without changes when the database was written by a newer schema.
+pub fn migrate(conn: &mut Connection) -> Result<u32> {
+    let current = schema_version(conn)?;
+    if current > LATEST_VERSION {
+        anyhow::bail!(
+            "Database schema version {} is newer than the supported version {}",
+            current,
+            LATEST_VERSION
+        );
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let version = index + 1;
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)
+            .map_err(|e| anyhow::anyhow!("Migration {} failed: {}", version, e))?;
+        tx.pragma_update(None, "user_version", version)?;
+        tx.commit()?;
+        tracing::debug!("Applied storage migration {}", version);
+    }
+
+    Ok(LATEST_VERSION)
+}
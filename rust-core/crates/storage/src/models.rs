@@ -0,0 +1,74 @@
+//! Rows of the index database
+
+use code_intelligence_core::CodeEntity;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A `code_entities` row
+///
+/// Ids are opaque strings: the FFI layer writes `file:line:name` while the
+/// indexer writes UUIDs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredEntity {
+    pub id: String,
+    pub name: String,
+    pub file_path: String,
+    /// Lowercase type name such as `function` or `class`
+    pub entity_type: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub content: String,
+    /// Rows of `entity_metadata`, such as the owners a `CODEOWNERS` assigns
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+impl StoredEntity {
+    /// The row stored for an indexed core entity
+    pub fn from_core(entity: &CodeEntity) -> Self {
+        Self {
+            id: entity.id.to_string(),
+            name: entity.name.clone(),
+            file_path: entity.file_path.clone(),
+            entity_type: format!("{:?}", entity.entity_type).to_lowercase(),
+            start_line: entity.start_line,
+            end_line: entity.end_line,
+            content: entity.content.clone(),
+            metadata: entity.metadata.clone(),
+        }
+    }
+}
+
+/// A `code_relationships` row
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StoredRelationship {
+    pub source_entity_id: String,
+    pub target_entity_id: String,
+    /// Relationship name such as `calls` or `imports`
+    pub relationship_type: String,
+}
+
+/// How [`crate::StorageManager::entities_by_metadata`] compares stored values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MetadataMatch {
+    /// The value equals the one given
+    #[default]
+    Exact,
+    /// The value starts with the one given, case-sensitively
+    Prefix,
+}
+
+/// Filters for [`crate::StorageManager::query_entities`]; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct EntityQuery {
+    /// Case-insensitive substring of the entity name
+    pub name_contains: Option<String>,
+    /// Exact file path
+    pub file_path: Option<String>,
+    /// Prefix of the file path, such as a codebase root
+    pub path_prefix: Option<String>,
+    /// Entity type, compared case-insensitively
+    pub entity_type: Option<String>,
+    /// Maximum rows returned
+    pub limit: Option<usize>,
+}
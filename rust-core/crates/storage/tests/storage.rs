@@ -0,0 +1,579 @@
+use code_intelligence_storage::migrations::{self, LATEST_VERSION};
+use code_intelligence_storage::{
+    CompactResult, EntityQuery, MergeResult, MetadataMatch, StorageConfig, StorageManager,
+    StoredEntity, StoredRelationship, Synchronous,
+};
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+fn entity(id: &str, name: &str, file_path: &str, entity_type: &str, line: u32) -> StoredEntity {
+    StoredEntity {
+        id: id.to_string(),
+        name: name.to_string(),
+        file_path: file_path.to_string(),
+        entity_type: entity_type.to_string(),
+        start_line: line,
+        end_line: line + 2,
+        content: format!("{} {}() {{}}", entity_type, name),
+        metadata: Default::default(),
+    }
+}
+
+fn relationship(source: &str, target: &str) -> StoredRelationship {
+    StoredRelationship {
+        source_entity_id: source.to_string(),
+        target_entity_id: target.to_string(),
+        relationship_type: "calls".to_string(),
+    }
+}
+
+/// Two files under `/repo` and one outside it
+fn seeded() -> StorageManager {
+    let storage = StorageManager::open_in_memory().unwrap();
+    storage
+        .insert_entities(&[
+            entity("a1", "parseConfig", "/repo/config.ts", "function", 1),
+            entity("a2", "Config", "/repo/config.ts", "class", 10),
+            entity("b1", "render", "/repo/view.ts", "function", 1),
+            entity("c1", "helper", "/other/util.ts", "function", 1),
+        ])
+        .unwrap();
+    storage
+}
+
+fn ids(entities: &[StoredEntity]) -> Vec<&str> {
+    entities.iter().map(|entity| entity.id.as_str()).collect()
+}
+
+#[test]
+fn test_insert_and_get_entity() {
+    let storage = seeded();
+    assert_eq!(storage.entity_count().unwrap(), 4);
+    assert_eq!(
+        storage.get_entity("a2").unwrap(),
+        Some(entity("a2", "Config", "/repo/config.ts", "class", 10))
+    );
+    assert_eq!(storage.get_entity("missing").unwrap(), None);
+
+    // Re-inserting an id replaces the row
+    storage
+        .insert_entities(&[entity("a2", "Settings", "/repo/config.ts", "class", 10)])
+        .unwrap();
+    assert_eq!(storage.entity_count().unwrap(), 4);
+    assert_eq!(storage.get_entity("a2").unwrap().unwrap().name, "Settings");
+}
+
+#[test]
+fn test_query_entities_combines_filters() {
+    let storage = seeded();
+
+    let config_file = storage
+        .query_entities(&EntityQuery {
+            file_path: Some("/repo/config.ts".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(ids(&config_file), vec!["a1", "a2"]);
+
+    let repo_functions = storage
+        .query_entities(&EntityQuery {
+            path_prefix: Some("/repo/".to_string()),
+            entity_type: Some("Function".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(ids(&repo_functions), vec!["a1", "b1"]);
+
+    let named = storage
+        .query_entities(&EntityQuery {
+            name_contains: Some("CONFIG".to_string()),
+            limit: Some(1),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(ids(&named), vec!["a1"]);
+}
+
+#[test]
+fn test_delete_by_file_removes_dependent_rows() {
+    let storage = seeded();
+    storage
+        .insert_relationships(&[relationship("b1", "a1"), relationship("c1", "b1")])
+        .unwrap();
+    storage
+        .store_embeddings(&[("a1".to_string(), vec![0.5, 0.25])])
+        .unwrap();
+
+    assert_eq!(storage.delete_by_file("/repo/config.ts").unwrap(), 2);
+    assert_eq!(storage.entity_count().unwrap(), 2);
+    assert_eq!(storage.get_embedding("a1").unwrap(), None);
+    assert_eq!(
+        storage.relationships_of("b1").unwrap(),
+        vec![relationship("c1", "b1")]
+    );
+
+    assert_eq!(storage.delete_by_path_prefix("/repo/").unwrap(), 1);
+    assert!(storage.relationships_of("c1").unwrap().is_empty());
+    assert_eq!(storage.delete_by_file("/repo/config.ts").unwrap(), 0);
+}
+
+#[test]
+fn test_prefix_delete_treats_wildcards_literally() {
+    let storage = StorageManager::open_in_memory().unwrap();
+    storage
+        .insert_entities(&[
+            entity("x", "x", "/repo_a/x.ts", "function", 1),
+            entity("y", "y", "/repoXa/y.ts", "function", 1),
+        ])
+        .unwrap();
+
+    assert_eq!(storage.delete_by_path_prefix("/repo_a").unwrap(), 1);
+    assert!(storage.get_entity("y").unwrap().is_some());
+}
+
+#[test]
+fn test_entities_by_metadata_matches_exact_values_and_prefixes() {
+    let tagged = |id: &str, file_path: &str, line: u32, owners: &str| StoredEntity {
+        metadata: HashMap::from([("owners".to_string(), owners.to_string())]),
+        ..entity(id, id, file_path, "function", line)
+    };
+    let storage = StorageManager::open_in_memory().unwrap();
+    storage
+        .insert_entities(&[
+            tagged("charge", "/repo/payments.ts", 10, "@org/payments"),
+            tagged("refund", "/repo/payments.ts", 1, "@org/payments-ops"),
+            tagged("render", "/repo/view.ts", 1, "@org/web"),
+            entity("untagged", "untagged", "/repo/util.ts", "function", 1),
+        ])
+        .unwrap();
+
+    let exact = storage
+        .entities_by_metadata("owners", "@org/payments", MetadataMatch::Exact)
+        .unwrap();
+    assert_eq!(ids(&exact), vec!["charge"]);
+    assert_eq!(exact[0].metadata["owners"], "@org/payments");
+
+    let prefixed = storage
+        .entities_by_metadata("owners", "@org/pay", MetadataMatch::Prefix)
+        .unwrap();
+    assert_eq!(ids(&prefixed), vec!["refund", "charge"]);
+    assert!(storage
+        .entities_by_metadata("team", "@org/pay", MetadataMatch::Prefix)
+        .unwrap()
+        .is_empty());
+
+    // Both modes are answered from the (key, value) index
+    let conn = storage.connection().unwrap();
+    let plan: String = conn
+        .query_row(
+            "EXPLAIN QUERY PLAN SELECT entity_id FROM entity_metadata
+             WHERE key = 'owners' AND value >= '@org/pay' AND value < '@org/paz'",
+            [],
+            |row| row.get(3),
+        )
+        .unwrap();
+    assert!(plan.contains("idx_entity_metadata_key_value"), "{}", plan);
+    drop(conn);
+
+    // Re-inserting an entity replaces its metadata; deleting its file drops it
+    storage
+        .insert_entities(&[tagged("charge", "/repo/payments.ts", 10, "@org/billing")])
+        .unwrap();
+    assert_eq!(
+        storage.get_entity("charge").unwrap().unwrap().metadata["owners"],
+        "@org/billing"
+    );
+    storage.delete_by_file("/repo/payments.ts").unwrap();
+    assert!(storage
+        .entities_by_metadata("owners", "@org/", MetadataMatch::Prefix)
+        .unwrap()
+        .iter()
+        .all(|entity| entity.id == "render"));
+}
+
+#[test]
+fn test_replace_file_entities() {
+    let storage = seeded();
+    storage
+        .replace_file_entities(
+            "/repo/config.ts",
+            &[entity("a3", "loadConfig", "/repo/config.ts", "function", 3)],
+        )
+        .unwrap();
+
+    let file = storage
+        .query_entities(&EntityQuery {
+            file_path: Some("/repo/config.ts".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(ids(&file), vec!["a3"]);
+    assert_eq!(storage.entity_count().unwrap(), 3);
+}
+
+#[test]
+fn test_text_search_follows_file_edits() {
+    let storage = seeded();
+    assert_eq!(
+        ids(&storage.search_text("parseConfig", 10).unwrap()),
+        vec!["a1"]
+    );
+
+    // Editing the file renames the function and keeps the class
+    storage
+        .replace_file_entities(
+            "/repo/config.ts",
+            &[
+                entity("a1", "loadSettings", "/repo/config.ts", "function", 1),
+                entity("a2", "Config", "/repo/config.ts", "class", 10),
+            ],
+        )
+        .unwrap();
+    assert!(storage.search_text("parseConfig", 10).unwrap().is_empty());
+    assert_eq!(
+        ids(&storage.search_text("loadSettings", 10).unwrap()),
+        vec!["a1"]
+    );
+    assert_eq!(ids(&storage.search_text("config", 10).unwrap()), vec!["a2"]);
+
+    // Re-inserting an id in place, then deleting the file
+    storage
+        .insert_entities(&[entity("b1", "paint", "/repo/view.ts", "function", 1)])
+        .unwrap();
+    assert!(storage.search_text("render", 10).unwrap().is_empty());
+    assert_eq!(ids(&storage.search_text("paint", 10).unwrap()), vec!["b1"]);
+    storage.delete_by_file("/repo/view.ts").unwrap();
+    assert!(storage.search_text("paint", 10).unwrap().is_empty());
+
+    assert!(storage
+        .search_text("\"unbalanced OR", 10)
+        .unwrap()
+        .is_empty());
+    assert!(storage.search_text("  ", 10).unwrap().is_empty());
+}
+
+#[test]
+fn test_relationships_and_embeddings() {
+    let storage = seeded();
+    assert_eq!(
+        storage
+            .insert_relationships(&[relationship("b1", "a1"), relationship("b1", "a1")])
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        storage.relationships_of("a1").unwrap(),
+        vec![relationship("b1", "a1")]
+    );
+
+    storage
+        .store_embeddings(&[("b1".to_string(), vec![1.0, -2.0, 0.125])])
+        .unwrap();
+    assert_eq!(
+        storage.get_embedding("b1").unwrap(),
+        Some(vec![1.0, -2.0, 0.125])
+    );
+
+    storage.clear().unwrap();
+    assert_eq!(storage.entity_count().unwrap(), 0);
+    assert!(storage.relationships_of("a1").unwrap().is_empty());
+    assert_eq!(storage.get_embedding("b1").unwrap(), None);
+}
+
+#[test]
+fn test_migrations_upgrade_unversioned_database() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("index.db");
+
+    // A database written before migrations existed
+    {
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE code_entities (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                entity_type TEXT NOT NULL,
+                start_line INTEGER,
+                end_line INTEGER,
+                content TEXT,
+                indexed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            );
+            INSERT INTO code_entities (id, name, file_path, entity_type, start_line, end_line, content)
+            VALUES ('app.ts:1:main', 'main', 'app.ts', 'function', 1, 3, NULL);",
+        )
+        .unwrap();
+    }
+
+    let storage = StorageManager::open(&db_path).unwrap();
+    assert_eq!(storage.schema_version().unwrap(), LATEST_VERSION);
+    assert_eq!(
+        storage
+            .get_entity("app.ts:1:main")
+            .unwrap()
+            .unwrap()
+            .content,
+        ""
+    );
+    // Rows written before the full-text index existed are searchable
+    assert_eq!(storage.search_text("main", 10).unwrap().len(), 1);
+    storage
+        .insert_relationships(&[relationship("app.ts:1:main", "app.ts:1:main")])
+        .unwrap();
+    drop(storage);
+
+    // Reopening applies nothing further and keeps the data
+    let storage = StorageManager::open(&db_path).unwrap();
+    assert_eq!(storage.schema_version().unwrap(), LATEST_VERSION);
+    assert_eq!(storage.relationships_of("app.ts:1:main").unwrap().len(), 1);
+}
+
+#[test]
+fn test_migrations_reject_newer_schema() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.pragma_update(None, "user_version", LATEST_VERSION + 1)
+        .unwrap();
+
+    let error = migrations::migrate(&mut conn).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("newer than the supported version"));
+    assert!(conn.prepare("SELECT * FROM code_entities").is_err());
+}
+
+#[test]
+fn test_periodic_checkpoints_bound_wal_size() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("index.db");
+    let wal_path = temp_dir.path().join("index.db-wal");
+    let storage = StorageManager::open_with_config(
+        &db_path,
+        StorageConfig {
+            pool_size: 1,
+            checkpoint_every_batches: 2,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // 100 batches of 50 entities with 2 KB bodies: about 10 MB written
+    let body = "x".repeat(2048);
+    let mut largest_wal = 0;
+    for batch in 0..100 {
+        let entities: Vec<StoredEntity> = (0..50)
+            .map(|i| StoredEntity {
+                content: body.clone(),
+                ..entity(
+                    &format!("{}-{}", batch, i),
+                    "f",
+                    &format!("/repo/file_{}.ts", batch),
+                    "function",
+                    i,
+                )
+            })
+            .collect();
+        storage.insert_entities(&entities).unwrap();
+        largest_wal = largest_wal.max(std::fs::metadata(&wal_path).map_or(0, |m| m.len()));
+    }
+
+    assert_eq!(storage.entity_count().unwrap(), 5000);
+    // Left to SQLite's passive auto-checkpoint the WAL reaches about 4 MB
+    assert!(
+        largest_wal < 1024 * 1024,
+        "WAL grew to {} bytes",
+        largest_wal
+    );
+
+    storage.checkpoint().unwrap();
+    assert_eq!(std::fs::metadata(&wal_path).unwrap().len(), 0);
+}
+
+#[test]
+fn test_pragmas_are_applied_to_new_connections() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let pragma = |storage: &StorageManager, name: &str| -> i64 {
+        storage
+            .connection()
+            .unwrap()
+            .query_row(&format!("PRAGMA {}", name), [], |row| row.get(0))
+            .unwrap()
+    };
+
+    let storage = StorageManager::open(temp_dir.path().join("default.db")).unwrap();
+    assert_eq!(pragma(&storage, "page_size"), 4096);
+    assert_eq!(pragma(&storage, "cache_size"), -65536);
+    assert_eq!(pragma(&storage, "mmap_size"), 256 * 1024 * 1024);
+    // NORMAL
+    assert_eq!(pragma(&storage, "synchronous"), 1);
+
+    let storage = StorageManager::open_with_config(
+        temp_dir.path().join("tuned.db"),
+        StorageConfig {
+            pool_size: 2,
+            page_size: 8192,
+            cache_size: 2000,
+            mmap_size: 0,
+            synchronous: Synchronous::Full,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    for _ in 0..2 {
+        assert_eq!(pragma(&storage, "page_size"), 8192);
+        assert_eq!(pragma(&storage, "cache_size"), 2000);
+        assert_eq!(pragma(&storage, "mmap_size"), 0);
+        assert_eq!(pragma(&storage, "synchronous"), 2);
+    }
+    assert_eq!(Synchronous::parse("normal"), Some(Synchronous::Normal));
+    assert_eq!(Synchronous::parse("fast"), None);
+}
+
+/// Write 2000 entities with 1 KB bodies under `/repo/`, then delete them all
+fn fill_and_delete(storage: &StorageManager) {
+    let body = "x".repeat(1024);
+    let entities: Vec<StoredEntity> = (0..2000)
+        .map(|i| StoredEntity {
+            content: body.clone(),
+            ..entity(
+                &i.to_string(),
+                "f",
+                &format!("/repo/file_{}.ts", i),
+                "function",
+                1,
+            )
+        })
+        .collect();
+    storage.insert_entities(&entities).unwrap();
+    storage
+        .insert_entities(&[entity("kept", "main", "/app/main.ts", "function", 1)])
+        .unwrap();
+    assert_eq!(storage.delete_by_path_prefix("/repo/").unwrap(), 2000);
+}
+
+#[test]
+fn test_compact_shrinks_database_after_deletes() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("index.db");
+    let storage = StorageManager::open(&db_path).unwrap();
+    fill_and_delete(&storage);
+
+    storage.checkpoint().unwrap();
+    let file_before = std::fs::metadata(&db_path).unwrap().len();
+    let CompactResult {
+        size_before,
+        size_after,
+        incremental,
+    } = storage.compact().unwrap();
+    assert!(!incremental);
+    assert_eq!(size_before, file_before);
+    assert!(
+        size_after * 4 < size_before,
+        "{} -> {}",
+        size_before,
+        size_after
+    );
+    assert_eq!(std::fs::metadata(&db_path).unwrap().len(), size_after);
+    assert!(storage.get_entity("kept").unwrap().is_some());
+}
+
+#[test]
+fn test_compact_uses_incremental_vacuum_when_enabled() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("index.db");
+    // auto_vacuum can only be chosen before the first table is created
+    Connection::open(&db_path)
+        .unwrap()
+        .pragma_update(None, "auto_vacuum", "INCREMENTAL")
+        .unwrap();
+    let storage = StorageManager::open(&db_path).unwrap();
+    fill_and_delete(&storage);
+
+    let result = storage.compact().unwrap();
+    assert!(result.incremental);
+    assert!(result.size_after * 4 < result.size_before);
+    assert_eq!(storage.entity_count().unwrap(), 1);
+}
+
+#[test]
+fn test_merge_index_prefers_newer_entities() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let set_indexed_at = |storage: &StorageManager, id: &str, at: &str| {
+        storage
+            .connection()
+            .unwrap()
+            .execute(
+                "UPDATE code_entities SET indexed_at = ?2 WHERE id = ?1",
+                rusqlite::params![id, at],
+            )
+            .unwrap();
+    };
+
+    let main = StorageManager::open(temp_dir.path().join("main.db")).unwrap();
+    main.insert_entities(&[
+        entity("shared", "oldName", "/repo/a.ts", "function", 1),
+        entity("stale", "keepMine", "/repo/a.ts", "function", 5),
+        entity("main-only", "mainOnly", "/repo/a.ts", "function", 9),
+    ])
+    .unwrap();
+    set_indexed_at(&main, "shared", "2026-01-01 00:00:00");
+    set_indexed_at(&main, "stale", "2026-03-01 00:00:00");
+    main.store_embeddings(&[("shared".to_string(), vec![1.0])])
+        .unwrap();
+
+    let shard_path = temp_dir.path().join("shard.db");
+    let shard = StorageManager::open(&shard_path).unwrap();
+    shard
+        .insert_entities(&[
+            entity("shared", "newName", "/repo/a.ts", "function", 1),
+            entity("stale", "theirs", "/repo/a.ts", "function", 5),
+            entity("shard-only", "shardOnly", "/repo/b.ts", "function", 1),
+        ])
+        .unwrap();
+    set_indexed_at(&shard, "shared", "2026-02-01 00:00:00");
+    set_indexed_at(&shard, "stale", "2026-02-01 00:00:00");
+    shard
+        .store_embeddings(&[
+            ("shard-only".to_string(), vec![0.5, 0.5]),
+            ("stale".to_string(), vec![9.0]),
+        ])
+        .unwrap();
+    shard
+        .insert_relationships(&[relationship("shard-only", "shared")])
+        .unwrap();
+    drop(shard);
+
+    let merged = main.merge_index(&shard_path).unwrap();
+    assert_eq!(
+        merged,
+        MergeResult {
+            entities_added: 1,
+            entities_updated: 1,
+            entities_skipped: 1,
+            relationships_added: 1,
+            embeddings_copied: 1,
+        }
+    );
+    assert_eq!(main.entity_count().unwrap(), 4);
+    assert_eq!(main.get_entity("shared").unwrap().unwrap().name, "newName");
+    assert_eq!(main.get_entity("stale").unwrap().unwrap().name, "keepMine");
+    assert_eq!(main.get_embedding("shared").unwrap(), None);
+    assert_eq!(main.get_embedding("stale").unwrap(), None);
+    assert_eq!(
+        main.get_embedding("shard-only").unwrap(),
+        Some(vec![0.5, 0.5])
+    );
+    assert_eq!(main.relationships_of("shard-only").unwrap().len(), 1);
+    assert_eq!(
+        ids(&main.search_text("newName", 10).unwrap()),
+        vec!["shared"]
+    );
+    assert!(main.search_text("oldName", 10).unwrap().is_empty());
+
+    // Merging again finds nothing newer
+    let again = main.merge_index(&shard_path).unwrap();
+    assert_eq!(again.entities_added + again.entities_updated, 0);
+    assert_eq!(again.entities_skipped, 3);
+    assert!(main
+        .merge_index(temp_dir.path().join("missing.db"))
+        .is_err());
+}
@@ -3,11 +3,10 @@
 //! This module contains all the core data structures used throughout the application,
 //! including entities, relationships, and configuration objects.
 
-pub mod code_metric;
 pub mod api_endpoint;
 
 // Re-export commonly used types
-pub use code_metric::{
+pub use code_intelligence_metrics::models::{
     CodeMetric, MetricType, MetricThreshold, MetricSummary, 
     MetricIssue, IssueSeverity
 };
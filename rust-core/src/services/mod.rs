@@ -7,7 +7,6 @@ pub mod parallel_indexer;
 pub mod storage;
 pub mod analyzer;
 pub mod security;
-pub mod metrics;
 
 pub use indexing::IndexingService;
 pub use search::SearchService;
@@ -18,4 +17,3 @@ pub use parallel_indexer::{ParallelIndexer, ParallelIndexerConfig, IndexingTask,
 pub use storage::{StorageService, SQLiteStorage, StorageConfig, StorageType, DatabaseStats, QueryResult, Transaction, IsolationLevel};
 pub use analyzer::{AnalyzerService, TreeSitterAnalyzer, AnalysisConfig, AnalysisResult, CodeEntity, EntityType, CodeRelationship, RelationshipType, FileMetrics, AnalysisIssue, Severity};
 pub use security::{SecurityService, DefaultSecurityService, SecurityConfig, SecurityScan, SecurityFinding, FindingType, Severity as SecuritySeverity, Remediation, OWASPCategory};
-pub use metrics::{MetricsService, DefaultMetricsService, MetricsConfig, MetricsReport, FileMetrics, DirectoryMetrics, MetricsSummary, Recommendation, TrendAnalysis, BenchmarkComparison, Grade};
\ No newline at end of file
@@ -23,7 +23,7 @@ use crate::models::cache_entry::CacheEntry;
 use crate::models::plugin::Plugin;
 use crate::models::configuration::Configuration;
 use crate::models::index_job::IndexJob;
-use crate::models::code_metric::CodeMetric;
+use code_intelligence_metrics::CodeMetric;
 use crate::models::api_endpoint::APIEndpoint;
 
 /// SQLite database configuration
@@ -0,0 +1,380 @@
+//! Boolean search queries compiled to parameterized SQL
+//!
+//! Supports `AND`, `OR`, `NOT`, parentheses and quoted phrases, e.g.
+//! `User AND (login OR auth) NOT test`. Adjacent terms are joined with an
+//! implicit `AND`. Precedence from tightest to loosest is `NOT`, `AND`, `OR`.
+
+/// Columns a term is matched against
+const TERM_COLUMNS: [&str; 4] = [
+    "name",
+    "qualified_name",
+    "IFNULL(documentation, '')",
+    "IFNULL(content, '')",
+];
+
+/// Deepest nesting of parentheses and `NOT`s a query may use; queries come
+/// from clients, and the parser and SQL compiler recurse once per level
+const MAX_NESTING_DEPTH: usize = 32;
+
+/// Most terms a query may have, bounding the depth of its `AND`/`OR` chains
+const MAX_TERMS: usize = 256;
+
+/// SQL function folding text to lowercase with Unicode rules, registered on
+/// the connection; SQLite's own `lower()` and `LIKE` only fold ASCII
+pub const FOLD_CASE_FUNCTION: &str = "fold_case";
+
+/// How search terms are compared with column text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaseMatching {
+    /// Both sides folded with [`FOLD_CASE_FUNCTION`], so `Ü` matches `ü`
+    #[default]
+    Insensitive,
+    /// Exact case via `GLOB`, so `User` does not match `user`
+    Sensitive,
+}
+
+impl CaseMatching {
+    pub fn from_flag(case_sensitive: bool) -> Self {
+        if case_sensitive {
+            CaseMatching::Sensitive
+        } else {
+            CaseMatching::Insensitive
+        }
+    }
+
+    /// Predicate testing `column` against the pattern bound to `?index`
+    pub fn predicate(self, column: &str, index: usize) -> String {
+        match self {
+            CaseMatching::Insensitive => format!("{}({}) LIKE ?{}", FOLD_CASE_FUNCTION, column, index),
+            CaseMatching::Sensitive => format!("{} GLOB ?{}", column, index),
+        }
+    }
+
+    /// Pattern matching any text that contains `term`, for use with [`Self::predicate`]
+    pub fn contains_pattern(self, term: &str) -> String {
+        match self {
+            CaseMatching::Insensitive => format!("%{}%", term.to_lowercase()),
+            CaseMatching::Sensitive => format!("*{}*", escape_glob(term)),
+        }
+    }
+}
+
+/// Quote GLOB metacharacters so they match literally
+fn escape_glob(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len());
+    for c in term.chars() {
+        match c {
+            '*' | '?' | '[' => {
+                escaped.push('[');
+                escaped.push(c);
+                escaped.push(']');
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryExpr {
+    Term(String),
+    And(Box<QueryExpr>, Box<QueryExpr>),
+    Or(Box<QueryExpr>, Box<QueryExpr>),
+    Not(Box<QueryExpr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Term(String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+/// Whether a query uses a boolean operator and should bypass plain substring search
+///
+/// Parentheses or quotes alone don't count, so `parse(` still searches literally.
+pub fn is_boolean_query(query: &str) -> bool {
+    query
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .any(|word| matches!(word, "AND" | "OR" | "NOT"))
+}
+
+/// Parse a boolean query string into an expression tree
+pub fn parse(query: &str) -> Result<QueryExpr, String> {
+    let tokens = tokenize(query)?;
+    if tokens.is_empty() {
+        return Err("Empty search query".to_string());
+    }
+
+    let terms = tokens.iter().filter(|token| matches!(token, Token::Term(_))).count();
+    if terms > MAX_TERMS {
+        return Err(format!("Search query has {} terms; at most {} are allowed", terms, MAX_TERMS));
+    }
+
+    let mut parser = Parser { tokens, pos: 0, depth: 0 };
+    let expr = parser.parse_or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(format!("Unexpected {:?} in search query", token)),
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '"' => {
+                chars.next();
+                let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                if phrase.is_empty() {
+                    return Err("Empty quoted phrase in search query".to_string());
+                }
+                tokens.push(Token::Term(phrase));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Term(word),
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Parentheses and `NOT`s enclosing the current position
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Run `parse` one nesting level deeper, failing past [`MAX_NESTING_DEPTH`]
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, String>) -> Result<T, String> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(format!("Search query nests deeper than {} levels", MAX_NESTING_DEPTH));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_or(&mut self) -> Result<QueryExpr, String> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            let right = self.parse_and()?;
+            left = QueryExpr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<QueryExpr, String> {
+        let mut left = self.parse_not()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                }
+                // Implicit AND between adjacent operands
+                Some(Token::Term(_)) | Some(Token::Not) | Some(Token::Open) => {}
+                _ => break,
+            }
+            let right = self.parse_not()?;
+            left = QueryExpr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<QueryExpr, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(QueryExpr::Not(Box::new(self.nested(Self::parse_not)?)));
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<QueryExpr, String> {
+        match self.next() {
+            Some(Token::Term(term)) => Ok(QueryExpr::Term(term)),
+            Some(Token::Open) => {
+                let expr = self.nested(Self::parse_or)?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err("Unbalanced parentheses in search query".to_string()),
+                }
+            }
+            Some(token) => Err(format!("Unexpected {:?} in search query", token)),
+            None => Err("Search query ended unexpectedly".to_string()),
+        }
+    }
+}
+
+impl QueryExpr {
+    /// Compile to a SQL predicate, pushing one pattern per term onto `params`
+    pub fn to_sql(&self, params: &mut Vec<String>, case: CaseMatching) -> String {
+        match self {
+            QueryExpr::Term(term) => {
+                params.push(case.contains_pattern(term));
+                let index = params.len();
+                let predicates: Vec<String> = TERM_COLUMNS
+                    .iter()
+                    .map(|column| case.predicate(column, index))
+                    .collect();
+                format!("({})", predicates.join(" OR "))
+            }
+            QueryExpr::And(left, right) => {
+                format!("({} AND {})", left.to_sql(params, case), right.to_sql(params, case))
+            }
+            QueryExpr::Or(left, right) => {
+                format!("({} OR {})", left.to_sql(params, case), right.to_sql(params, case))
+            }
+            QueryExpr::Not(inner) => format!("(NOT {})", inner.to_sql(params, case)),
+        }
+    }
+
+    /// Terms that must or may match, i.e. those not under a `NOT`
+    pub fn positive_terms(&self) -> Vec<&str> {
+        match self {
+            QueryExpr::Term(term) => vec![term.as_str()],
+            QueryExpr::And(left, right) | QueryExpr::Or(left, right) => {
+                let mut terms = left.positive_terms();
+                terms.extend(right.positive_terms());
+                terms
+            }
+            QueryExpr::Not(_) => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(t: &str) -> QueryExpr {
+        QueryExpr::Term(t.to_string())
+    }
+
+    fn and(l: QueryExpr, r: QueryExpr) -> QueryExpr {
+        QueryExpr::And(Box::new(l), Box::new(r))
+    }
+
+    fn or(l: QueryExpr, r: QueryExpr) -> QueryExpr {
+        QueryExpr::Or(Box::new(l), Box::new(r))
+    }
+
+    fn not(e: QueryExpr) -> QueryExpr {
+        QueryExpr::Not(Box::new(e))
+    }
+
+    #[test]
+    fn test_parse_operators() {
+        assert_eq!(parse("user AND login").unwrap(), and(term("user"), term("login")));
+        assert_eq!(parse("user OR login").unwrap(), or(term("user"), term("login")));
+        assert_eq!(parse("NOT test").unwrap(), not(term("test")));
+        assert_eq!(parse("user login").unwrap(), and(term("user"), term("login")));
+        assert_eq!(parse("\"user service\"").unwrap(), term("user service"));
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        // AND binds tighter than OR
+        assert_eq!(
+            parse("a OR b AND c").unwrap(),
+            or(term("a"), and(term("b"), term("c")))
+        );
+        // Parentheses override it
+        assert_eq!(
+            parse("(a OR b) AND c").unwrap(),
+            and(or(term("a"), term("b")), term("c"))
+        );
+        // NOT binds tightest
+        assert_eq!(
+            parse("User AND (login OR auth) NOT test").unwrap(),
+            and(
+                and(term("User"), or(term("login"), term("auth"))),
+                not(term("test"))
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("").is_err());
+        assert!(parse("(a OR b").is_err());
+        assert!(parse("a AND").is_err());
+        assert!(parse("a )").is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_deep_nesting() {
+        assert!(parse(&format!("{}a{}", "(".repeat(32), ")".repeat(32))).is_ok());
+        assert!(parse(&format!("{}a{}", "(".repeat(100_000), ")".repeat(100_000))).is_err());
+        assert!(parse(&format!("{}a", "NOT ".repeat(100_000))).is_err());
+        assert!(parse(&vec!["a"; 100_000].join(" OR ")).is_err());
+    }
+
+    #[test]
+    fn test_to_sql_is_parameterized() {
+        let mut params = Vec::new();
+        let sql = parse("a NOT b").unwrap().to_sql(&mut params, CaseMatching::Insensitive);
+
+        assert_eq!(params, vec!["%a%", "%b%"]);
+        assert!(sql.contains("fold_case(name) LIKE ?1"));
+        assert!(sql.contains("(NOT (fold_case(name) LIKE ?2"));
+        assert!(!sql.contains("%a%"));
+
+        let mut params = Vec::new();
+        let sql = parse("User*").unwrap().to_sql(&mut params, CaseMatching::Sensitive);
+        assert_eq!(params, vec!["*User[*]*"]);
+        assert!(sql.contains("name GLOB ?1"));
+    }
+
+    #[test]
+    fn test_is_boolean_query() {
+        assert!(is_boolean_query("user AND login"));
+        assert!(is_boolean_query("(a OR b)"));
+        assert!(!is_boolean_query("parse("));
+        assert!(!is_boolean_query("user"));
+        assert!(!is_boolean_query("android"));
+    }
+}
@@ -0,0 +1,73 @@
+//! SARIF 2.1.0 reports of search matches, for code-scanning UIs
+//!
+//! Each matching entity becomes one result located at the entity's stored
+//! range, so a search such as `deprecated_fn` can be uploaded as a lint run
+//! and shown as annotations on pull requests.
+
+use serde_json::{json, Value};
+
+use crate::SearchResult;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Rule id shared by every result of a search run
+pub const SEARCH_RULE_ID: &str = "search-match";
+
+/// A SARIF log with a single run holding one result per search match
+pub fn search_report(query: &str, results: &[SearchResult]) -> Value {
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "code-intelligence",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": [{
+                        "id": SEARCH_RULE_ID,
+                        "shortDescription": { "text": format!("Matches for `{}`", query) },
+                    }],
+                },
+            },
+            "results": results.iter().map(|result| sarif_result(query, result)).collect::<Vec<_>>(),
+        }],
+    })
+}
+
+fn sarif_result(query: &str, result: &SearchResult) -> Value {
+    let entity = &result.entity;
+    json!({
+        "ruleId": SEARCH_RULE_ID,
+        "level": "warning",
+        "message": {
+            "text": format!(
+                "`{}` matches {} `{}`",
+                query,
+                entity.entity_type.to_lowercase(),
+                entity.qualified_name
+            ),
+        },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": artifact_location(&entity.file_path),
+                // SARIF columns are 1-based; stored columns are 0-based
+                "region": {
+                    "startLine": entity.start_line,
+                    "startColumn": entity.start_column + 1,
+                    "endLine": entity.end_line,
+                    "endColumn": entity.end_column + 1,
+                },
+            },
+        }],
+    })
+}
+
+/// Relative paths resolve against the scanned checkout; absolute ones become `file://` URIs
+fn artifact_location(file_path: &str) -> Value {
+    let uri = file_path.replace('\\', "/");
+    if uri.starts_with('/') {
+        json!({ "uri": format!("file://{}", uri) })
+    } else {
+        json!({ "uri": uri, "uriBaseId": "%SRCROOT%" })
+    }
+}
@@ -0,0 +1,264 @@
+//! Signature patterns matched against stored parameter and return types
+//!
+//! A pattern is an optional parenthesized parameter list followed by an
+//! optional `-> return type`, e.g. `(self, ..) -> Result<_>`. `_` stands for
+//! any one type, including generic arguments such as `T, E`, and a trailing
+//! `..` for any further parameters. Whitespace is ignored, and a type may be
+//! written without its path, so `Result<_>` matches `anyhow::Result<()>`.
+
+/// A parsed signature pattern; absent parts match anything
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignaturePattern {
+    pub parameters: Option<ParametersPattern>,
+    pub return_type: Option<TypePattern>,
+}
+
+/// Patterns for leading parameters, in order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParametersPattern {
+    pub leading: Vec<TypePattern>,
+    /// Ended with `..`, so more parameters may follow
+    pub open: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypePattern(Vec<Segment>);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Any,
+}
+
+/// Parse a pattern such as `(&mut self, _) -> Option<_>` or `-> Result<_>`
+pub fn parse(pattern: &str) -> Result<SignaturePattern, String> {
+    let mut rest = pattern.trim();
+    let mut parameters = None;
+
+    if rest.starts_with('(') {
+        let close = matching_paren(rest).ok_or_else(|| format!("Unclosed parameter list in signature pattern: {}", pattern))?;
+        parameters = Some(parse_parameters(&rest[1..close])?);
+        rest = rest[close + 1..].trim();
+    }
+
+    let return_type = match rest.strip_prefix("->") {
+        Some(return_type) if !return_type.trim().is_empty() => Some(TypePattern::parse(return_type)),
+        Some(_) => return Err("Missing return type after `->` in signature pattern".to_string()),
+        None if rest.is_empty() => None,
+        None => return Err(format!("Expected `(parameters)` or `-> type` in signature pattern: {}", pattern)),
+    };
+
+    if parameters.is_none() && return_type.is_none() {
+        return Err("Empty signature pattern".to_string());
+    }
+    Ok(SignaturePattern { parameters, return_type })
+}
+
+fn parse_parameters(list: &str) -> Result<ParametersPattern, String> {
+    let items: Vec<&str> = split_top_level(list).into_iter().map(str::trim).filter(|item| !item.is_empty()).collect();
+    let open = items.last() == Some(&"..");
+    let leading = if open { &items[..items.len() - 1] } else { &items[..] };
+    if leading.contains(&"..") {
+        return Err("`..` may only end a parameter list".to_string());
+    }
+    Ok(ParametersPattern {
+        leading: leading.iter().map(|item| TypePattern::parse(item)).collect(),
+        open,
+    })
+}
+
+impl SignaturePattern {
+    /// Whether an entity with these `(name, type)` parameters and return type matches
+    ///
+    /// A parameter pattern matches either the parameter's type or its name,
+    /// so `self` finds Python's untyped `self` as well as Rust's `&self`.
+    pub fn matches(&self, parameters: &[(&str, Option<&str>)], return_type: Option<&str>) -> bool {
+        if let Some(pattern) = &self.return_type {
+            if !return_type.is_some_and(|return_type| pattern.matches(return_type)) {
+                return false;
+            }
+        }
+
+        let Some(pattern) = &self.parameters else {
+            return true;
+        };
+        let count_fits = if pattern.open {
+            parameters.len() >= pattern.leading.len()
+        } else {
+            parameters.len() == pattern.leading.len()
+        };
+        count_fits && pattern.leading.iter().zip(parameters).all(|(pattern, (name, param_type))| {
+            pattern.matches(name) || param_type.is_some_and(|param_type| pattern.matches(param_type))
+        })
+    }
+}
+
+impl TypePattern {
+    fn parse(pattern: &str) -> Self {
+        let chars: Vec<char> = normalize(pattern).chars().collect();
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            let standalone = c == '_'
+                && !(i > 0 && is_ident(chars[i - 1]))
+                && !chars.get(i + 1).is_some_and(|&next| is_ident(next));
+            if standalone {
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Any);
+            } else {
+                literal.push(c);
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        TypePattern(segments)
+    }
+
+    /// Whether `text` matches, whole or with a leading `path::` dropped
+    pub fn matches(&self, text: &str) -> bool {
+        let text = normalize(text);
+        if matches_segments(&self.0, &text) {
+            return true;
+        }
+        let suffix_matches = path_suffixes(&text).any(|suffix| matches_segments(&self.0, suffix));
+        suffix_matches
+    }
+}
+
+fn matches_segments(segments: &[Segment], text: &str) -> bool {
+    match segments.split_first() {
+        None => text.is_empty(),
+        Some((Segment::Literal(literal), rest)) => {
+            text.strip_prefix(literal.as_str()).is_some_and(|text| matches_segments(rest, text))
+        }
+        // Any non-empty run with balanced brackets
+        Some((Segment::Any, rest)) => balanced_prefix_ends(text).any(|end| matches_segments(rest, &text[end..])),
+    }
+}
+
+/// Byte offsets at which `text[..end]` is a non-empty, bracket-balanced run
+fn balanced_prefix_ends(text: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut depth = 0i32;
+    let mut previous = '\0';
+    let mut closed = false;
+    text.char_indices().map_while(move |(i, c)| {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            // `->` in `impl Fn() -> T` is an arrow, not a closing bracket
+            '>' if previous == '-' => {}
+            '>' | ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+        previous = c;
+        closed |= depth < 0;
+        (!closed).then_some((depth == 0).then_some(i + c.len_utf8()))
+    }).flatten()
+}
+
+/// The rest of `text` after each top-level `::`, e.g. `Result<T>` in `std::io::Result<T>`
+fn path_suffixes(text: &str) -> impl Iterator<Item = &str> {
+    let mut depth = 0;
+    let mut starts = Vec::new();
+    let bytes = text.as_bytes();
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'<' | b'(' | b'[' => depth += 1,
+            b'>' | b')' | b']' => depth -= 1,
+            b':' if depth == 0 && bytes.get(i + 1) == Some(&b':') => {
+                starts.push(i + 2);
+            }
+            _ => {}
+        }
+    }
+    starts.into_iter().map(move |start| &text[start..])
+}
+
+/// Split on commas outside any brackets
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in list.char_indices() {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' | ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&list[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&list[start..]);
+    items
+}
+
+/// Byte offset of the `)` closing the `(` that starts `text`
+fn matching_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn normalize(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_matches(pattern: &str, text: &str) -> bool {
+        TypePattern::parse(pattern).matches(text)
+    }
+
+    #[test]
+    fn test_wildcards_match_whole_types() {
+        assert!(type_matches("Result<_>", "Result<Config>"));
+        assert!(type_matches("Result<_>", "Result<Vec<u8>, io::Error>"));
+        assert!(type_matches("Result<_>", "anyhow::Result<()>"));
+        assert!(type_matches("Option<_>", "Option < &str >"));
+        assert!(type_matches("_", "impl Fn() -> bool"));
+        assert!(type_matches("my_type", "my_type"));
+
+        assert!(!type_matches("Result<_>", "Option<Result<T>>"));
+        assert!(!type_matches("Result<_>", "Result<>"));
+        assert!(!type_matches("my_type", "my_other"));
+    }
+
+    #[test]
+    fn test_parse_and_match_signatures() {
+        let pattern = parse("(self, ..) -> Result<_>").unwrap();
+        assert!(pattern.matches(&[("self", Some("&mut self")), ("path", Some("&Path"))], Some("Result<()>")));
+        assert!(!pattern.matches(&[("path", Some("&Path"))], Some("Result<()>")));
+        assert!(!pattern.matches(&[("self", None)], None));
+
+        let exact = parse("(_, &str)").unwrap();
+        assert!(exact.matches(&[("a", Some("u32")), ("b", Some("&str"))], None));
+        assert!(!exact.matches(&[("a", Some("u32"))], None));
+
+        assert!(parse("()").unwrap().matches(&[], Some("bool")));
+        assert!(parse("(.., self)").is_err());
+        assert!(parse("Result<_>").is_err());
+        assert!(parse("->").is_err());
+        assert!(parse("").is_err());
+    }
+}
@@ -0,0 +1,377 @@
+//! JSON Schema descriptions of the exported functions, for MCP tool registration
+//!
+//! The Node MCP layer advertises these as tool definitions instead of keeping
+//! its own copies, so a parameter added here reaches clients without drifting.
+//! Keep each entry in step with the `#[napi]` signature it describes.
+
+use napi_derive::napi;
+use serde_json::{json, Map, Value};
+
+/// An MCP tool definition: `input_schema` is a JSON Schema object
+#[napi(object)]
+pub struct ToolSchema {
+    pub name: String,
+    pub description: String,
+    pub input_schema: Value,
+}
+
+struct Param {
+    name: &'static str,
+    json_type: &'static str,
+    description: &'static str,
+    required: bool,
+}
+
+const fn required(name: &'static str, json_type: &'static str, description: &'static str) -> Param {
+    Param { name, json_type, description, required: true }
+}
+
+const fn optional(name: &'static str, json_type: &'static str, description: &'static str) -> Param {
+    Param { name, json_type, description, required: false }
+}
+
+fn tool(name: &str, description: &str, params: &[Param]) -> ToolSchema {
+    let properties: Map<String, Value> = params
+        .iter()
+        .map(|param| {
+            (
+                param.name.to_string(),
+                json!({ "type": param.json_type, "description": param.description }),
+            )
+        })
+        .collect();
+    let required: Vec<&str> = params
+        .iter()
+        .filter(|param| param.required)
+        .map(|param| param.name)
+        .collect();
+
+    ToolSchema {
+        name: name.to_string(),
+        description: description.to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        }),
+    }
+}
+
+/// Tool definitions for every function exposed to MCP clients
+#[napi]
+pub fn tool_schemas() -> Vec<ToolSchema> {
+    vec![
+        tool(
+            "search_code",
+            "Search indexed entities by name and qualified name",
+            &[
+                required("query", "string", "Text matched against entity names"),
+                optional("limit", "integer", "Maximum number of results (default 10)"),
+                optional("file_filter", "string", "Substring the file path must contain"),
+                optional("scoring", "object", "Ranking adjustments such as a recency boost or tokenized matching with down-weighted stop tokens"),
+                optional("fields", "array", "Entity fields to return; others come back null"),
+                optional("file_paths", "array", "Exact files to search within"),
+                optional("case_sensitive", "boolean", "Match letter case exactly (default false)"),
+                optional("annotation", "string", "Decorator or attribute entities must carry, e.g. `@deprecated` or `#[test]`"),
+                optional("explain", "boolean", "Attach each result's score breakdown for debugging relevance (default false)"),
+                optional("context", "object", "Snippet for each result: `context_mode` (`signature_only`, `full_entity` or `window`), `context_before` and `context_after` line counts"),
+            ],
+        ),
+        tool(
+            "search_by_signature",
+            "Find functions and methods whose parameter and return types match a pattern",
+            &[
+                required("pattern", "string", "e.g. `-> Result<_>` or `(self, ..)`; `_` matches any one type, a trailing `..` any further parameters"),
+                optional("limit", "integer", "Maximum number of results (default 50)"),
+            ],
+        ),
+        tool(
+            "top_entities",
+            "The largest, most complex or most deeply nested entities, for refactoring triage",
+            &[
+                required("metric", "string", "`loc` for lines of code, `complexity` or `nesting_depth`"),
+                optional("limit", "integer", "Maximum number of results (default 20)"),
+                optional("filter", "object", "`language`, `entity_type` and `file_filter` (path substring)"),
+            ],
+        ),
+        tool(
+            "get_children",
+            "Entities directly enclosed by an entity, such as a class's methods",
+            &[required("parent_id", "string", "Id of the enclosing entity")],
+        ),
+        tool(
+            "autocomplete",
+            "Entities whose name starts with a prefix, ignoring case, for completion",
+            &[
+                required("prefix", "string", "Start of the entity name"),
+                optional("limit", "integer", "Maximum number of results (default 20)"),
+            ],
+        ),
+        tool(
+            "search_open_cursor",
+            "Open a cursor over every match of a search, read in batches with cursor_next",
+            &[
+                required("query", "string", "Text matched against entity names"),
+                optional("file_filter", "string", "Substring the file path must contain"),
+                optional("case_sensitive", "boolean", "Match letter case exactly (default false)"),
+            ],
+        ),
+        tool(
+            "cursor_next",
+            "Read the next batch of a search cursor; a short batch is the last and closes it",
+            &[
+                required("handle", "object", "Handle returned by search_open_cursor"),
+                optional("batch", "integer", "Maximum results in the batch (default 100)"),
+            ],
+        ),
+        tool(
+            "cursor_close",
+            "Close a search cursor before it is exhausted",
+            &[required("handle", "object", "Handle returned by search_open_cursor")],
+        ),
+        tool(
+            "search_code_async",
+            "Search code, giving up after a timeout or on cancellation and returning the results read so far",
+            &[
+                required("query", "string", "Text to match against entity names and documentation"),
+                optional("options", "object", "`limit`, `file_filter`, `case_sensitive`, `timeout_ms`, and `error_on_timeout` to fail instead of returning partial results"),
+            ],
+        ),
+        tool(
+            "list_findings",
+            "List TODO, FIXME, HACK and XXX comments recorded at indexing, by file and line",
+            &[optional("kind", "string", "Only this marker, e.g. `FIXME`")],
+        ),
+        tool(
+            "search_grouped",
+            "Search indexed entities and group the matches by file, best file first",
+            &[
+                required("query", "string", "Text matched against entity names"),
+                optional("limit", "integer", "Maximum number of matches across all files (default 20)"),
+            ],
+        ),
+        tool(
+            "search_to_sarif",
+            "Write the matches of a search to a SARIF 2.1.0 report for code scanning",
+            &[
+                required("query", "string", "Text matched against entity names"),
+                required("out_path", "string", "File the SARIF report is written to"),
+            ],
+        ),
+        tool(
+            "search_structured",
+            "Boolean search with filters on file_path, entity_type, language, dialect, annotation and type_parameter (`T: Display`)",
+            &[required(
+                "query",
+                "object",
+                "Object with `text`, optional `limit`, `filters`, `scoring` and `case_sensitive`",
+            )],
+        ),
+        tool(
+            "search_with_facets",
+            "Structured search that also counts every match by entity type, language or top-level directory",
+            &[
+                required("query", "object", "The `search_structured` query object"),
+                required("facet_fields", "array", "`entity_type`, `language` and/or `directory`"),
+            ],
+        ),
+        tool(
+            "refine_search",
+            "Narrow earlier search results to those also matching another query or filters",
+            &[
+                required("previous_result_ids", "array", "Entity ids returned by the earlier search"),
+                optional("additional_query", "string", "Boolean query in the `search_structured` syntax"),
+                optional("filters", "object", "Filters with the `search_structured` keys"),
+            ],
+        ),
+        tool(
+            "index_codebase",
+            "Parse and index every supported file in a directory",
+            &[
+                required("path", "string", "Directory to index"),
+                optional("force_reindex", "boolean", "Drop existing entries and re-parse every file"),
+                optional("config", "object", "Indexing options such as `use_checksums`"),
+            ],
+        ),
+        tool(
+            "index_codebase_git_tracked",
+            "Index only the files git tracks in a repository, skipping untracked files",
+            &[
+                required("repo_path", "string", "Root of the git repository"),
+                optional("include_submodules", "boolean", "Also index files inside submodules (default false)"),
+                optional("force_reindex", "boolean", "Drop existing entries and re-parse every file"),
+                optional("config", "object", "Indexing options such as `use_checksums`"),
+            ],
+        ),
+        tool(
+            "changed_entities",
+            "Index a repository and return the entities its working tree adds or modifies relative to a git ref",
+            &[
+                required("repo_path", "string", "Root of the git repository"),
+                required("base_ref", "string", "Branch, tag or commit to compare against, e.g. `main`"),
+                optional("config", "object", "Indexing options such as `use_checksums`"),
+            ],
+        ),
+        tool(
+            "reindex_file",
+            "Replace the stored entities of a single file",
+            &[
+                required("path", "string", "File to re-index; a missing file is purged"),
+                optional("codebase_id", "string", "Root of the codebase a relative path is in"),
+            ],
+        ),
+        tool(
+            "get_entities_in_file",
+            "Entities declared in an indexed file, in source order",
+            &[
+                required("file_path", "string", "Path of an indexed file"),
+                optional("codebase_id", "string", "Root of the codebase a relative path is in"),
+            ],
+        ),
+        tool(
+            "get_codebase_stats",
+            "Counts of indexed files, entities, languages and entity types",
+            &[optional("codebase_path", "string", "Restrict the counts to this path prefix")],
+        ),
+        tool(
+            "find_related",
+            "Entities related to one entity by scope, file and name",
+            &[
+                required("entity_id", "string", "Id of the entity to start from"),
+                optional("limit", "integer", "Maximum number of results (default 10)"),
+            ],
+        ),
+        tool(
+            "go_to_definition",
+            "Resolve the symbol at a position to its definitions",
+            &[
+                required("file_path", "string", "File containing the reference"),
+                required("line", "integer", "1-based line of the reference"),
+                required("column", "integer", "0-based column of the reference"),
+            ],
+        ),
+        tool(
+            "get_entity_metrics",
+            "Fan-in, fan-out and instability of one entity in the call graph",
+            &[
+                required("entity_id", "string", "Id of the entity"),
+                optional("include_manual", "boolean", "Also count manual `calls` relationships"),
+            ],
+        ),
+        tool(
+            "top_coupled_entities",
+            "Entities with the most distinct callers",
+            &[
+                optional("limit", "integer", "Maximum number of results (default 10)"),
+                optional("include_manual", "boolean", "Also count manual `calls` relationships"),
+            ],
+        ),
+        tool(
+            "add_relationship",
+            "Record a manual relationship between entities that is kept across re-indexing",
+            &[
+                required("from_id", "string", "Id of the source entity"),
+                required("to_id", "string", "Id of the target entity"),
+                required("relationship_type", "string", "e.g. `calls`, `implements` or `depends_on`"),
+            ],
+        ),
+        tool(
+            "remove_relationship",
+            "Delete a manual relationship",
+            &[
+                required("from_id", "string", "Id of the source entity"),
+                required("to_id", "string", "Id of the target entity"),
+                required("relationship_type", "string", "Type the relationship was added with"),
+            ],
+        ),
+        tool(
+            "get_callers",
+            "Entities that call the given entity",
+            &[
+                required("entity_id", "string", "Id of the called entity"),
+                optional("include_manual", "boolean", "Also follow manual `calls` relationships"),
+            ],
+        ),
+        tool(
+            "find_unreferenced",
+            "Dead-code candidates: entities with no inbound calls or imports. The call graph is heuristic, so review before deleting",
+            &[
+                optional("entity_types", "array", "Entity types to check; defaults to `Function`"),
+                optional("options", "object", "`exclude_public` and `exclude_entry_points` (both default true), an `allowlist` of name globs, `include_manual` and `limit`"),
+            ],
+        ),
+        tool(
+            "get_relationships",
+            "Every relationship into or out of an entity",
+            &[
+                required("entity_id", "string", "Id of the entity"),
+                optional("include_manual", "boolean", "Also return manual relationships"),
+            ],
+        ),
+        tool(
+            "warmup",
+            "Prime the database page cache and planner statistics after startup",
+            &[optional("options", "object", "Object with `queries`, the searches to run (default the most common names)")],
+        ),
+        tool(
+            "get_module_dependencies",
+            "Modules a file imports, as recorded when it was indexed",
+            &[
+                required("file_path", "string", "Path of an indexed file"),
+                optional("codebase_id", "string", "Root of the codebase a relative path is in"),
+            ],
+        ),
+        tool(
+            "export_dependency_graph",
+            "Import graph between files, directories or modules as GraphViz DOT or Mermaid",
+            &[
+                optional("scope", "string", "Directory to graph (default the whole index)"),
+                optional("format", "string", "`dot` (default) or `mermaid`"),
+                optional("granularity", "string", "`file` (default), `directory` or `module`"),
+            ],
+        ),
+        tool(
+            "get_file_summary",
+            "Line counts and entity types of a source file",
+            &[
+                required("file_path", "string", "Path used to detect the language"),
+                required("content", "string", "Source text of the file"),
+            ],
+        ),
+        tool(
+            "file_checksum",
+            "SHA-256 of a file's content",
+            &[required("path", "string", "File to hash")],
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(name: &str) -> ToolSchema {
+        tool_schemas().into_iter().find(|schema| schema.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_search_code_schema_lists_parameters() {
+        let schema = find("search_code").input_schema;
+
+        let properties = schema["properties"].as_object().unwrap();
+        for name in ["query", "limit", "file_filter"] {
+            assert!(properties.contains_key(name), "missing {}", name);
+        }
+        assert_eq!(properties["limit"]["type"], "integer");
+        assert_eq!(schema["required"], json!(["query"]));
+    }
+
+    #[test]
+    fn test_tool_names_are_unique() {
+        let mut names: Vec<String> = tool_schemas().into_iter().map(|schema| schema.name).collect();
+        let total = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), total);
+    }
+}